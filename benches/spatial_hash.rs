@@ -0,0 +1,166 @@
+//! Compares spatial hash queries with a naive scan of every agent.
+//! Run with `cargo bench --bench spatial_hash`.
+extern crate nalgebra;
+extern crate steering;
+
+use nalgebra::Vector2;
+use steering::{Location, Proximity, RadiusProximity, Steerable};
+use steering::spatial::{SpatialHash, SpatialHashProximity};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+const NEIGHBORHOOD: f32 = 5.0;
+const QUERIES: usize = 1000;
+
+struct Agent {
+    position: Vector2<f32>,
+    velocity: Vector2<f32>,
+}
+
+impl Location<f32, Vector2<f32>> for Agent {
+    fn get_position(&self) -> &Vector2<f32> {
+        &self.position
+    }
+
+    fn get_orientation(&self) -> f32 {
+        0.0
+    }
+}
+
+impl Steerable<f32, Vector2<f32>> for Agent {
+    fn get_linear_velocity(&self) -> &Vector2<f32> {
+        &self.velocity
+    }
+
+    fn get_angular_velocity(&self) -> f32 {
+        0.0
+    }
+
+    fn get_bounding_radius(&self) -> f32 {
+        0.5
+    }
+}
+
+/// xorshift generator so runs are repeatable without extra dependencies
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+/// spreads agents so that every agent has about 20 neighbors regardless of count
+fn create_agents(count: usize, random: &mut Random) -> Vec<Rc<RefCell<Agent>>> {
+    let side = (count as f32 * NEIGHBORHOOD * NEIGHBORHOOD * 3.14 / 20.0).sqrt();
+    (0..count)
+        .map(|_| {
+            Rc::new(RefCell::new(Agent {
+                position: Vector2::new(random.next() * side, random.next() * side),
+                velocity: Vector2::new(random.next() - 0.5, random.next() - 0.5),
+            }))
+        })
+        .collect()
+}
+
+fn time<F: FnMut()>(mut function: F) -> Duration {
+    let start = Instant::now();
+    function();
+    start.elapsed()
+}
+
+fn micros(duration: Duration) -> f64 {
+    duration.as_secs() as f64 * 1e6 + duration.subsec_nanos() as f64 / 1e3
+}
+
+fn report(name: &str, count: usize, operations: usize, duration: Duration) {
+    println!(
+        "{:>7} agents  {:<28} {:>12.3} us/op",
+        count,
+        name,
+        micros(duration) / operations as f64
+    );
+}
+
+fn bench(count: usize) {
+    let mut random = Random(0x2545_f491_4f6c_dd1d);
+    let agents = create_agents(count, &mut random);
+    let steerables: Vec<Rc<RefCell<Steerable<f32, Vector2<f32>>>>> = agents
+        .iter()
+        .map(|agent| agent.clone() as Rc<RefCell<Steerable<f32, Vector2<f32>>>>)
+        .collect();
+
+    let mut hash = SpatialHash::new(NEIGHBORHOOD).unwrap();
+    let mut ids = Vec::with_capacity(count);
+    report(
+        "insert",
+        count,
+        count,
+        time(|| for agent in steerables.iter() {
+            ids.push(hash.insert(agent.clone()));
+        }),
+    );
+
+    for agent in agents.iter() {
+        let mut agent = agent.borrow_mut();
+        let velocity = agent.velocity;
+        agent.position += velocity;
+    }
+    report("update", count, count, time(|| hash.update_all()));
+
+    let queried: Vec<Vector2<f32>> = (0..QUERIES)
+        .map(|index| agents[index * count / QUERIES].borrow().position)
+        .collect();
+    let mut found = 0;
+    report(
+        "radius query",
+        count,
+        QUERIES,
+        time(|| for center in queried.iter() {
+            found += hash.query_radius(center, NEIGHBORHOOD).len();
+        }),
+    );
+    report(
+        "10 nearest",
+        count,
+        QUERIES,
+        time(|| for center in queried.iter() {
+            found += hash.k_nearest(center, 10).len();
+        }),
+    );
+
+    let hashed = SpatialHashProximity::new(Rc::new(RefCell::new(hash)), NEIGHBORHOOD);
+    let naive = RadiusProximity::new(steerables.clone(), NEIGHBORHOOD);
+    let owners: Vec<&Rc<RefCell<Steerable<f32, Vector2<f32>>>>> = (0..QUERIES)
+        .map(|index| &steerables[index * count / QUERIES])
+        .collect();
+    report(
+        "hash proximity",
+        count,
+        QUERIES,
+        time(|| for owner in owners.iter() {
+            found += hashed.find_neighbors(&*owner.borrow(), &mut |_| true);
+        }),
+    );
+    report(
+        "naive proximity",
+        count,
+        QUERIES,
+        time(|| for owner in owners.iter() {
+            found += naive.find_neighbors(&*owner.borrow(), &mut |_| true);
+        }),
+    );
+    // keeps the optimizer from dropping the queries
+    assert!(found > 0);
+}
+
+fn main() {
+    for count in [1_000, 10_000, 100_000].iter() {
+        bench(*count);
+    }
+}
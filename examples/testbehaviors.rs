@@ -24,11 +24,13 @@ use tui::style::{Color, Modifier, Style};
 
 use nalgebra::Vector3;
 use alga::general::AbstractModule;
+use steering::Location;
 use steering::Steerable;
 use steering::SteeringAcceleration;
 use steering::Seek;
 use steering::Flee;
 use steering::Pursue;
+use steering::PursueMode;
 use steering::Arrive;
 use steering::Evade;
 use steering::SteeringAccelerationCalculator;
@@ -102,7 +104,9 @@ impl Steerable<f32> for Vehicle {
     fn get_bounding_radius(&self) -> f32 {
         self.bounding_radius
     }
+}
 
+impl Location<f32> for Vehicle {
     fn get_position(&self) -> &Vector3<f32> {
         &self.position
     }
@@ -181,19 +185,20 @@ fn main() {
         behavior: RefCell::new(SteeringBehavior {
             enabled: true,
             limiter: None,
-            target: target.clone(),
             owner: app.v.clone(),
         }),
+        target: target.clone(),
         max_prediction_time: 100.0f32,
+        mode: PursueMode::Predictive,
     };
 
     let evade = Evade {
         behavior: RefCell::new(SteeringBehavior {
             enabled: true,
             limiter: None,
-            target: target.clone(),
             owner: app.v.clone(),
         }),
+        target: target.clone(),
         max_prediction_time: 100.0f32,
     };
 
@@ -201,27 +206,27 @@ fn main() {
         behavior: RefCell::new(SteeringBehavior {
             enabled: true,
             limiter: None,
-            target: target.clone(),
             owner: app.v.clone(),
         }),
+        target: target.clone(),
     };
 
     let flee = Flee {
         behavior: RefCell::new(SteeringBehavior {
             enabled: true,
             limiter: None,
-            target: target.clone(),
             owner: app.v.clone(),
         }),
+        target: target.clone(),
     };
 
     let arrive = Arrive {
         behavior: RefCell::new(SteeringBehavior {
             enabled: true,
             limiter: None,
-            target: target.clone(),
             owner: app.v.clone(),
         }),
+        target: target.clone(),
         time_to_target: 10f32,
         deceleration_radius: 20f32,
         tolerance: 5f32,
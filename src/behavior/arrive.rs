@@ -1,22 +1,27 @@
-use super::super::{HasSteeringBehavior, SteeringAcceleration, SteeringAccelerationCalculator,
-                   SteeringBehavior};
-use nalgebra::{distance, Point3};
+use super::super::{HasSteeringBehavior, Location, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringError,
+                   SteeringStatus, SteeringVector};
+use nalgebra::Vector3;
 use alga::general::Real;
-use alga::general::AbstractModule;
+use validation;
+use steering_status::target_status;
 use std::cell::RefMut;
 use std::cell::RefCell;
 use std::rc::Rc;
 
 ///This behavior aims to arrive at target position and stop. It is like Seek
 ///behavior but aiming to be at zero speed on target.
-#[builder(pattern = "immutable")]
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
 #[derive(Builder)]
-pub struct Arrive<T>
+pub struct Arrive<T, V = Vector3<T>>
 where
     T: Real,
+    V: SteeringVector<T>,
 {
     /// Common behavior attributes
-    pub behavior: RefCell<SteeringBehavior<T>>,
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Location to arrive at
+    pub target: Rc<RefCell<Location<T, V>>>,
     /// Stop if we are close enough.
     pub tolerance: T,
     /// Reduce the speed if we are close enough
@@ -25,38 +30,239 @@ where
     pub time_to_target: T,
 }
 
+/// Tunable parameters of `Arrive` which can be loaded from data files. Owner and
+/// target are not part of them, they are supplied when the behavior is created.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ArriveParameters<T: Real> {
+    /// Stop if we are close enough
+    pub tolerance: T,
+    /// Reduce the speed if we are close enough
+    pub deceleration_radius: T,
+    /// How quick should we aproach target
+    pub time_to_target: T,
+}
+
+impl<T: Real> ArriveParameters<T> {
+    /// Checks that parameters make sense
+    pub fn validate(&self) -> Result<(), SteeringError> {
+        validation::arrival(
+            "tolerance",
+            Some(self.tolerance),
+            Some(self.deceleration_radius),
+            Some(self.time_to_target),
+        ).map_err(SteeringError::InvalidParameter)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> ArriveBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> ArriveBuilder<T, V> {
+        ArriveBuilder {
+            behavior: None,
+            target: None,
+            tolerance: None,
+            deceleration_radius: None,
+            time_to_target: None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validation::arrival(
+            "tolerance",
+            self.tolerance,
+            self.deceleration_radius,
+            self.time_to_target,
+        )
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Arrive<T, V> {
+    /// Creates the behavior using parameters loaded from a data file
+    pub fn with_parameters(
+        behavior: SteeringBehavior<T, V>,
+        target: Rc<RefCell<Location<T, V>>>,
+        parameters: &ArriveParameters<T>,
+    ) -> Arrive<T, V> {
+        Arrive {
+            behavior: RefCell::new(behavior),
+            target: target,
+            tolerance: parameters.tolerance,
+            deceleration_radius: parameters.deceleration_radius,
+            time_to_target: parameters.time_to_target,
+        }
+    }
+
+    /// returns the tunable parameters of the behavior
+    pub fn parameters(&self) -> ArriveParameters<T> {
+        ArriveParameters {
+            tolerance: self.tolerance,
+            deceleration_radius: self.deceleration_radius,
+            time_to_target: self.time_to_target,
+        }
+    }
+
+    /// Replaces the tunable parameters of the behavior
+    pub fn set_parameters(&mut self, parameters: &ArriveParameters<T>) {
+        self.tolerance = parameters.tolerance;
+        self.deceleration_radius = parameters.deceleration_radius;
+        self.time_to_target = parameters.time_to_target;
+    }
 
-impl<T: Real> HasSteeringBehavior<T> for Arrive<T> {
-    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T>> {
+    /// Tests whether the owner is within `tolerance` of the target, from then on
+    /// the behavior does not accelerate anymore
+    pub fn has_arrived(&self) -> bool {
+        let behavior = self.behavior.borrow();
+        let owner = behavior.owner.borrow();
+        owner.get_position().distance(self.target.borrow().get_position()) <= self.tolerance
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for Arrive<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
         self.behavior.borrow_mut()
     }
 }
 
-impl<T: Real> SteeringAccelerationCalculator<T> for Arrive<T> {
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V> for Arrive<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        self.parameters().validate()
+    }
+
+    fn get_status(&self) -> SteeringStatus {
+        target_status(self.target.borrow().get_position(), || {
+            if self.has_arrived() {
+                SteeringStatus::Arrived
+            } else {
+                SteeringStatus::InProgress
+            }
+        })
+    }
+
     fn calculate_real_steering(
         &self,
-        steering_acceleration: Rc<RefCell<SteeringAcceleration<T>>>,
-    ) -> Rc<RefCell<SteeringAcceleration<T>>> {
-        let behavior = self.behavior.borrow();
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        arrive(
+            &self.behavior.borrow(),
+            steering_acceleration,
+            *self.target.borrow().get_position(),
+            self.tolerance,
+            self.deceleration_radius,
+            self.time_to_target,
+        )
+    }
+}
+
+/// Sets linear acceleration needed to arrive at given position and stop there,
+/// shared by behaviors which arrive somewhere
+pub fn arrive<T: Real, V: SteeringVector<T>>(
+    behavior: &SteeringBehavior<T, V>,
+    steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    target_position: V,
+    tolerance: T,
+    deceleration_radius: T,
+    time_to_target: T,
+) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+    {
         let mut sa = steering_acceleration.borrow_mut();
-        sa.linear = *behavior.target.borrow().get_position() -
-            *behavior.owner.borrow().get_position();
-        let to_target = distance(&Point3::from_coordinates(sa.linear), &Point3::origin());
+        sa.linear = target_position - *behavior.owner.borrow().get_position();
+        let to_target = sa.linear.norm();
 
-        if to_target <= self.tolerance {
+        if to_target <= tolerance || to_target.is_zero() {
             sa.set_zero();
+        } else {
+            let mut target_speed = match behavior.limiter {
+                Some(ref lim) => lim.borrow().get_max_linear_speed(),
+                None => T::one(),
+            };
+            if to_target <= deceleration_radius {
+                target_speed *= to_target / deceleration_radius;
+            }
+            sa.linear = sa.linear.scale(target_speed / to_target);
+            sa.linear -= *behavior.owner.borrow().get_linear_velocity();
+            sa.linear = sa.linear.scale(T::one() / time_to_target);
+            sa.angular = T::zero();
         }
-        let mut target_speed = match self.behavior.borrow().limiter {
-            Some(ref lim) => lim.borrow().get_max_linear_speed(),
-            None => T::one(),
-        };
-        if to_target <= self.deceleration_radius {
-            target_speed *= to_target / self.deceleration_radius;
-        }
-        sa.linear = sa.linear.multiply_by(target_speed / to_target);
-        sa.linear -= *behavior.owner.borrow().get_linear_velocity();
-        sa.linear = sa.linear.multiply_by(T::one() / self.time_to_target);
-        sa.angular = T::zero();
-        steering_acceleration.clone()
+    }
+    steering_acceleration
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::{Arrive, ArriveBuilder, SteeringAcceleration,
+                              SteeringAccelerationCalculator, SteeringBehavior, SteeringError,
+                              SteeringStatus, StaticLocation};
+    use super::super::test_common::TestSteerable;
+    use nalgebra::Vector3;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn builder() -> ArriveBuilder<f32, Vector3<f32>> {
+        ArriveBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: Rc::new(RefCell::new(TestSteerable::new())),
+            }))
+            .target(Rc::new(RefCell::new(StaticLocation::new(
+                Vector3::new(5.0f32, 0.0, 0.0),
+                0.0,
+            ))))
+            .tolerance(0.5)
+            .deceleration_radius(2.0)
+            .time_to_target(0.1)
+    }
+
+    #[test]
+    fn builder_accepts_sensible_parameters() {
+        assert!(builder().build().is_ok());
+    }
+
+    #[test]
+    fn builder_rejects_invalid_parameters() {
+        assert_eq!(
+            Err("time_to_target must be a positive number".to_string()),
+            builder().time_to_target(0.0).build().map(|_| ())
+        );
+        assert_eq!(
+            Err("deceleration_radius must not be a negative number".to_string()),
+            builder().deceleration_radius(-1.0).build().map(|_| ())
+        );
+        assert_eq!(
+            Err("tolerance must not be greater than deceleration_radius".to_string()),
+            builder().tolerance(3.0).build().map(|_| ())
+        );
+    }
+
+    #[test]
+    fn validate_after_mutation() {
+        let mut arrive: Arrive<f32> = builder().build().unwrap();
+        assert_eq!(Ok(()), arrive.validate());
+        arrive.deceleration_radius = 0.1;
+        assert_eq!(
+            Err(SteeringError::InvalidParameter(
+                "tolerance must not be greater than deceleration_radius".to_string()
+            )),
+            arrive.validate()
+        );
+    }
+
+    #[test]
+    fn status_reports_arrival() {
+        let mut arrive: Arrive<f32> = builder().build().unwrap();
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let (_, status) = arrive.calculate_steering_with_status(sa);
+        assert_eq!(SteeringStatus::InProgress, status);
+        arrive.target = Rc::new(RefCell::new(StaticLocation::new(
+            Vector3::new(-50.2f32, 50.0, 0.0),
+            0.0,
+        )));
+        assert_eq!(SteeringStatus::Arrived, arrive.get_status());
+        arrive.target = Rc::new(RefCell::new(StaticLocation::new(
+            Vector3::new(::std::f32::INFINITY, 0.0, 0.0),
+            0.0,
+        )));
+        assert_eq!(SteeringStatus::TargetLost, arrive.get_status());
     }
 }
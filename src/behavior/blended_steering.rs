@@ -0,0 +1,151 @@
+use super::super::{HasSteeringBehavior, SteeringAcceleration, SteeringAccelerationCalculator,
+                   SteeringBehavior, SteeringError, SteeringStatus, SteeringVector};
+use nalgebra::Vector3;
+use alga::general::Real;
+use steering_status::combined_status;
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A behavior taking part in a blend with the weight of its acceleration
+#[derive(Clone)]
+pub struct BehaviorAndWeight<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// blended behavior
+    pub behavior: Rc<RefCell<SteeringAccelerationCalculator<T, V>>>,
+    /// scale of the acceleration calculated by the behavior
+    pub weight: T,
+}
+
+///BlendedSteering behavior sums up weighted accelerations of a list of
+///behaviors. The result is truncated to the maximum linear and angular
+///accelerations of the limiter if there is one.
+#[builder(pattern = "immutable")]
+#[derive(Builder)]
+pub struct BlendedSteering<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Blended behaviors
+    pub list: Vec<BehaviorAndWeight<T, V>>,
+}
+
+impl<T: Real, V: SteeringVector<T>> BlendedSteeringBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> BlendedSteeringBuilder<T, V> {
+        BlendedSteeringBuilder {
+            behavior: None,
+            list: None,
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> BlendedSteering<T, V> {
+    /// Adds a behavior to the blend
+    pub fn add(
+        &mut self,
+        behavior: Rc<RefCell<SteeringAccelerationCalculator<T, V>>>,
+        weight: T,
+    ) {
+        self.list.push(BehaviorAndWeight {
+            behavior: behavior,
+            weight: weight,
+        });
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for BlendedSteering<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V>
+    for BlendedSteering<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        for item in self.list.iter() {
+            item.behavior.borrow().validate()?;
+        }
+        Ok(())
+    }
+
+    /// a goal is reached once every blended behavior reached it
+    fn get_status(&self) -> SteeringStatus {
+        combined_status(self.list.iter().map(|item| item.behavior.borrow().get_status()))
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        steering_acceleration.borrow_mut().set_zero();
+        for item in self.list.iter() {
+            let result = item.behavior
+                .borrow_mut()
+                .calculate_steering(Rc::new(RefCell::new(SteeringAcceleration::default())));
+            let result = result.borrow();
+            steering_acceleration.borrow_mut().mul_add(
+                SteeringAcceleration::new(result.linear, result.angular),
+                item.weight,
+            );
+        }
+
+        if let Some(ref lim) = self.behavior.borrow().limiter {
+            let max_linear_acceleration = lim.borrow().get_max_linear_acceleration();
+            let max_angular_acceleration = lim.borrow().get_max_angular_acceleration();
+            let mut sa = steering_acceleration.borrow_mut();
+            let linear = sa.linear.norm();
+            if linear > max_linear_acceleration {
+                sa.linear = sa.linear.scale(max_linear_acceleration / linear);
+            }
+            if Real::abs(sa.angular) > max_angular_acceleration {
+                sa.angular = max_angular_acceleration * sa.angular.signum();
+            }
+        }
+        steering_acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::{Seek, SteeringAcceleration, SteeringAccelerationCalculator,
+                              SteeringBehavior, StaticLocation};
+    use super::super::test_common::TestSteerable2d;
+    use super::BlendedSteeringBuilder;
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn weighted_accelerations_are_summed() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let behavior = SteeringBehavior {
+            enabled: true,
+            limiter: None,
+            owner: owner.clone(),
+        };
+        let seek = |x: f32, y: f32| {
+            Rc::new(RefCell::new(Seek {
+                behavior: RefCell::new(behavior.clone()),
+                target: Rc::new(RefCell::new(StaticLocation::new(Vector2::new(x, y), 0.0))),
+            }))
+        };
+        let mut blended = BlendedSteeringBuilder::new()
+            .behavior(RefCell::new(behavior.clone()))
+            .list(Vec::new())
+            .build()
+            .unwrap();
+        blended.add(seek(10.0, 0.0), 2.0);
+        blended.add(seek(0.0, 10.0), 0.5);
+
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let sa = blended.calculate_steering(sa);
+        assert_eq!(Vector2::new(2.0, 0.5), sa.borrow().linear);
+    }
+}
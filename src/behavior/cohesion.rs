@@ -0,0 +1,105 @@
+use nalgebra::Vector3;
+use super::super::{HasSteeringBehavior, Proximity, Steerable, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringVector};
+use alga::general::Real;
+
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Cohesion behavior steers the owner towards the center of mass of its neighbors
+/// with the maximum linear acceleration
+#[builder(pattern = "immutable")]
+#[derive(Builder)]
+pub struct Cohesion<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Neighbors of the owner
+    pub proximity: Rc<RefCell<Proximity<T, V>>>,
+}
+
+impl<T: Real, V: SteeringVector<T>> CohesionBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> CohesionBuilder<T, V> {
+        CohesionBuilder {
+            behavior: None,
+            proximity: None,
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for Cohesion<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V> for Cohesion<T, V> {
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        let behavior = self.behavior.borrow();
+        let owner = behavior.owner.borrow();
+        let mut center_of_mass = V::zero();
+        let count = self.proximity.borrow().find_neighbors(
+            &*owner,
+            &mut |neighbor: &Steerable<T, V>| {
+                center_of_mass += *neighbor.get_position();
+                true
+            },
+        );
+        let mut linear = V::zero();
+        if count > 0 {
+            center_of_mass = center_of_mass.scale(T::one() / T::from_usize(count).unwrap());
+            linear = (center_of_mass - *owner.get_position())
+                .normalize_or_zero()
+                .scale(match behavior.limiter {
+                    Some(ref a) => (*a).borrow().get_max_linear_acceleration(),
+                    None => T::one(),
+                });
+        }
+        steering_acceleration.borrow_mut().linear = linear;
+        steering_acceleration.borrow_mut().angular = T::zero();
+        steering_acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CohesionBuilder;
+    use super::super::test_common::TestSteerable2d;
+    use super::super::super::{Proximity, RadiusProximity, Steerable, SteeringAcceleration,
+                              SteeringAccelerationCalculator, SteeringBehavior};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn steers_to_center_of_neighbors() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let agents = vec![
+            owner.clone() as Rc<RefCell<Steerable<f32, Vector2<f32>>>>,
+            Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(3.0f32, 2.0)))),
+            Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(3.0f32, 6.0)))),
+        ];
+        let mut cohesion = CohesionBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: owner,
+            }))
+            .proximity(Rc::new(RefCell::new(RadiusProximity::new(agents, 10.0))) as
+                Rc<RefCell<Proximity<f32, Vector2<f32>>>>)
+            .build()
+            .unwrap();
+
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = cohesion.calculate_steering(sa);
+        assert!((result.borrow().linear - Vector2::new(0.6, 0.8)).norm() < 1e-6);
+    }
+}
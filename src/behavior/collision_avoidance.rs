@@ -0,0 +1,152 @@
+use nalgebra::Vector3;
+use super::super::{HasSteeringBehavior, Proximity, Steerable, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringVector};
+use alga::general::Real;
+
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// CollisionAvoidance behavior predicts the first neighbor the owner will collide
+/// with, assuming both keep their velocity, and steers away from the predicted
+/// position of the collision. If the owner already overlaps that neighbor it
+/// steers away from the current position of the neighbor instead.
+#[builder(pattern = "immutable")]
+#[derive(Builder)]
+pub struct CollisionAvoidance<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Neighbors of the owner
+    pub proximity: Rc<RefCell<Proximity<T, V>>>,
+}
+
+impl<T: Real, V: SteeringVector<T>> CollisionAvoidanceBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> CollisionAvoidanceBuilder<T, V> {
+        CollisionAvoidanceBuilder {
+            behavior: None,
+            proximity: None,
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for CollisionAvoidance<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+struct FirstCollision<T: Real, V: SteeringVector<T>> {
+    time: T,
+    min_separation: T,
+    distance: T,
+    radius: T,
+    relative_position: V,
+    relative_velocity: V,
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V>
+    for CollisionAvoidance<T, V> {
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        let behavior = self.behavior.borrow();
+        let owner = behavior.owner.borrow();
+        let position = *owner.get_position();
+        let velocity = *owner.get_linear_velocity();
+        let owner_radius = owner.get_bounding_radius();
+        let mut first: Option<FirstCollision<T, V>> = None;
+        self.proximity.borrow().find_neighbors(
+            &*owner,
+            &mut |neighbor: &Steerable<T, V>| {
+                let relative_position = *neighbor.get_position() - position;
+                let relative_velocity = *neighbor.get_linear_velocity() - velocity;
+                let relative_speed2 = relative_velocity.norm_squared();
+                if relative_speed2.is_zero() {
+                    return false;
+                }
+                let time = -relative_position.dot(&relative_velocity) / relative_speed2;
+                if time <= T::zero() || first.as_ref().map_or(false, |first| time >= first.time) {
+                    return false;
+                }
+                let distance = relative_position.norm();
+                let min_separation = distance - relative_speed2.sqrt() * time;
+                let radius = owner_radius + neighbor.get_bounding_radius();
+                if min_separation > radius {
+                    return false;
+                }
+                first = Some(FirstCollision {
+                    time: time,
+                    min_separation: min_separation,
+                    distance: distance,
+                    radius: radius,
+                    relative_position: relative_position,
+                    relative_velocity: relative_velocity,
+                });
+                true
+            },
+        );
+        let linear = match first {
+            None => V::zero(),
+            Some(first) => {
+                let relative_position =
+                    if first.min_separation <= T::zero() || first.distance < first.radius {
+                        first.relative_position
+                    } else {
+                        first.relative_position + first.relative_velocity.scale(first.time)
+                    };
+                relative_position.normalize_or_zero().scale(-match behavior.limiter {
+                    Some(ref a) => (*a).borrow().get_max_linear_acceleration(),
+                    None => T::one(),
+                })
+            }
+        };
+        steering_acceleration.borrow_mut().linear = linear;
+        steering_acceleration.borrow_mut().angular = T::zero();
+        steering_acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CollisionAvoidanceBuilder;
+    use super::super::test_common::TestSteerable2d;
+    use super::super::super::{Proximity, RadiusProximity, Steerable, SteeringAcceleration,
+                              SteeringAccelerationCalculator, SteeringBehavior};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn avoids_predicted_collision() {
+        let mut owner = TestSteerable2d::new(Vector2::new(0.0f32, 0.0));
+        owner.set_linear_velocity(Vector2::new(1.0, 0.0));
+        let owner = Rc::new(RefCell::new(owner));
+        let mut oncoming = TestSteerable2d::new(Vector2::new(20.0f32, 3.0));
+        oncoming.set_linear_velocity(Vector2::new(-1.0, 0.0));
+        let agents = vec![
+            owner.clone() as Rc<RefCell<Steerable<f32, Vector2<f32>>>>,
+            Rc::new(RefCell::new(oncoming)),
+            Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 30.0)))),
+        ];
+        let mut avoidance = CollisionAvoidanceBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: owner,
+            }))
+            .proximity(Rc::new(RefCell::new(RadiusProximity::new(agents, 100.0))) as
+                Rc<RefCell<Proximity<f32, Vector2<f32>>>>)
+            .build()
+            .unwrap();
+
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = avoidance.calculate_steering(sa);
+        assert!((result.borrow().linear - Vector2::new(0.0, -1.0)).norm() < 1e-6);
+    }
+}
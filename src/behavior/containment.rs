@@ -0,0 +1,140 @@
+use nalgebra::Vector3;
+use super::super::{HasSteeringBehavior, RayConfiguration, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringError,
+                   SteeringVector};
+use obstacle::Obstacle;
+use alga::general::Real;
+
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Containment behavior keeps the owner inside a boundary shape such as an `Aabb`, a
+/// `Circle` or a `ConvexPolygon`. Feelers are projected along the linear velocity,
+/// every feeler ending outside the boundary pushes the owner back along the inward
+/// normal in proportion to how far it sticks out. An owner already outside the
+/// boundary steers straight back to it.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct Containment<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Shape the owner should stay in
+    pub boundary: Rc<RefCell<Obstacle<T, V>>>,
+    /// Feelers projected from the owner
+    pub feelers: RayConfiguration<T>,
+}
+
+impl<T: Real, V: SteeringVector<T>> ContainmentBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> ContainmentBuilder<T, V> {
+        ContainmentBuilder {
+            behavior: None,
+            boundary: None,
+            feelers: None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self.feelers {
+            Some(ref feelers) => feelers.validate(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for Containment<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V> for Containment<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        self.feelers.validate().map_err(SteeringError::InvalidParameter)
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        let behavior = self.behavior.borrow();
+        let max_acceleration = match behavior.limiter {
+            Some(ref a) => (*a).borrow().get_max_linear_acceleration(),
+            None => T::one(),
+        };
+        let (position, velocity) = {
+            let owner = behavior.owner.borrow();
+            (*owner.get_position(), *owner.get_linear_velocity())
+        };
+        let boundary = self.boundary.borrow();
+        let linear = if boundary.signed_distance(&position) > T::zero() {
+            (boundary.closest_point(&position) - position).normalize_or_zero()
+        } else {
+            let mut push = V::zero();
+            for feeler in self.feelers.rays(position, velocity).iter() {
+                let outside = boundary.signed_distance(&feeler.end);
+                if outside > T::zero() {
+                    let inward = (boundary.closest_point(&feeler.end) - feeler.end)
+                        .normalize_or_zero();
+                    push += inward.scale(T::min(outside / feeler.get_length(), T::one()));
+                }
+            }
+            if push.norm() > T::one() {
+                push.normalize()
+            } else {
+                push
+            }
+        };
+        steering_acceleration.borrow_mut().linear = linear.scale(max_acceleration);
+        steering_acceleration.borrow_mut().angular = T::zero();
+        steering_acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ContainmentBuilder;
+    use super::super::test_common::TestSteerable2d;
+    use super::super::super::{RayConfiguration, SteeringAcceleration,
+                              SteeringAccelerationCalculator, SteeringBehavior};
+    use obstacle::{Aabb, Obstacle};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn pushes_back_inside() {
+        let mut owner = TestSteerable2d::new(Vector2::new(5.0f32, 9.0));
+        owner.set_linear_velocity(Vector2::new(0.0, 1.0));
+        let owner = Rc::new(RefCell::new(owner));
+        let arena = Aabb::new(Vector2::new(0.0f32, 0.0), Vector2::new(10.0, 10.0)).unwrap();
+        let mut containment = ContainmentBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: owner.clone(),
+            }))
+            .boundary(Rc::new(RefCell::new(arena)) as Rc<RefCell<Obstacle<f32, Vector2<f32>>>>)
+            .feelers(RayConfiguration::Single { length: 2.0 })
+            .build()
+            .unwrap();
+
+        // the feeler sticks out by half of its length
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = containment.calculate_steering(sa.clone());
+        assert!((result.borrow().linear - Vector2::new(0.0, -0.5)).norm() < 1e-6);
+
+        owner.borrow_mut().set_position(Vector2::new(5.0, 5.0));
+        let result = containment.calculate_steering(sa.clone());
+        assert_eq!(Vector2::new(0.0, 0.0), result.borrow().linear);
+
+        owner.borrow_mut().set_position(Vector2::new(12.0, 5.0));
+        let result = containment.calculate_steering(sa);
+        assert_eq!(Vector2::new(-1.0, 0.0), result.borrow().linear);
+    }
+}
@@ -1,8 +1,10 @@
-use nalgebra::{distance_squared, Point3};
-use super::super::{HasSteeringBehavior, SteeringAcceleration, SteeringAccelerationCalculator,
-                   SteeringBehavior};
+use nalgebra::Vector3;
+use super::super::{HasSteeringBehavior, Steerable, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringError, SteeringStatus,
+                   SteeringVector};
 use alga::general::Real;
-use alga::general::AbstractModule;
+use validation;
+use steering_status::target_status;
 use std::cell::RefMut;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -10,42 +12,104 @@ use std::rc::Rc;
 ///Evade behaviour produces the negative steering of Pursue behavior.
 ///With this behavior owner tries to run away from targets future position
 ///at time t.
-#[builder(pattern = "immutable")]
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
 #[derive(Builder)]
-pub struct Evade<T>
+pub struct Evade<T, V = Vector3<T>>
 where
     T: Real,
+    V: SteeringVector<T>,
 {
     /// Common behavior attributes
-    pub behavior: RefCell<SteeringBehavior<T>>,
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Agent to evade
+    pub target: Rc<RefCell<Steerable<T, V>>>,
 
     pub max_prediction_time: T,
 }
 
-impl<T: Real> HasSteeringBehavior<T> for Evade<T> {
-    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T>> {
+/// Tunable parameters of `Evade` which can be loaded from data files. Owner and
+/// target are not part of them, they are supplied when the behavior is created.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EvadeParameters<T: Real> {
+    /// Longest time span used to predict where the target will be
+    pub max_prediction_time: T,
+}
+
+impl<T: Real> EvadeParameters<T> {
+    /// Checks that parameters make sense
+    pub fn validate(&self) -> Result<(), SteeringError> {
+        validation::non_negative("max_prediction_time", Some(self.max_prediction_time))
+            .map_err(SteeringError::InvalidParameter)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> EvadeBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> EvadeBuilder<T, V> {
+        EvadeBuilder {
+            behavior: None,
+            target: None,
+            max_prediction_time: None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validation::non_negative("max_prediction_time", self.max_prediction_time)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Evade<T, V> {
+    /// Creates the behavior using parameters loaded from a data file
+    pub fn with_parameters(
+        behavior: SteeringBehavior<T, V>,
+        target: Rc<RefCell<Steerable<T, V>>>,
+        parameters: &EvadeParameters<T>,
+    ) -> Evade<T, V> {
+        Evade {
+            behavior: RefCell::new(behavior),
+            target: target,
+            max_prediction_time: parameters.max_prediction_time,
+        }
+    }
+
+    /// returns the tunable parameters of the behavior
+    pub fn parameters(&self) -> EvadeParameters<T> {
+        EvadeParameters {
+            max_prediction_time: self.max_prediction_time,
+        }
+    }
+
+    /// Replaces the tunable parameters of the behavior
+    pub fn set_parameters(&mut self, parameters: &EvadeParameters<T>) {
+        self.max_prediction_time = parameters.max_prediction_time;
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for Evade<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
         self.behavior.borrow_mut()
     }
 }
 
-impl<T: Real> SteeringAccelerationCalculator<T> for Evade<T> {
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V> for Evade<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        self.parameters().validate()
+    }
+
+    fn get_status(&self) -> SteeringStatus {
+        target_status(self.target.borrow().get_position(), || SteeringStatus::InProgress)
+    }
+
     fn calculate_real_steering(
         &self,
-        steering_acceleration: Rc<RefCell<SteeringAcceleration<T>>>,
-    ) -> Rc<RefCell<SteeringAcceleration<T>>> {
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
         let behavior = self.behavior.borrow();
-        let square_distance = distance_squared(
-            &Point3::from_coordinates(
-                *behavior.target.borrow().get_position() -
-                    *behavior.owner.borrow().get_position(),
-            ),
-            &Point3::origin(),
-        );
-        let square_speed =
-            distance_squared(
-                &Point3::from_coordinates(*behavior.owner.borrow().get_linear_velocity()),
-                &Point3::origin(),
-            );
+        let square_distance = (*self.target.borrow().get_position() -
+                                   *behavior.owner.borrow().get_position())
+            .norm_squared();
+        let square_speed = behavior.owner.borrow().get_linear_velocity().norm_squared();
         let mut prediction_time = self.max_prediction_time;
         if square_speed > T::zero() {
             let square_prediction_time = square_distance / square_speed;
@@ -55,14 +119,14 @@ impl<T: Real> SteeringAccelerationCalculator<T> for Evade<T> {
         }
 
         let mut sa = steering_acceleration.borrow_mut();
-        sa.linear = *behavior.target.borrow().get_position();
+        sa.linear = *self.target.borrow().get_position();
         sa.mul_add(
-            SteeringAcceleration::new(*behavior.target.borrow().get_linear_velocity(), T::zero()),
+            SteeringAcceleration::new(*self.target.borrow().get_linear_velocity(), T::zero()),
             prediction_time,
         );
         sa.linear -= *behavior.owner.borrow().get_position();
-        sa.linear = sa.linear.normalize();
-        sa.linear = sa.linear.multiply_by(match self.behavior.borrow().limiter {
+        sa.linear = sa.linear.normalize_or_zero();
+        sa.linear = sa.linear.scale(match self.behavior.borrow().limiter {
             Some(ref a) => -(*a).borrow().get_max_linear_acceleration(),
             None => T::one(),
         });
@@ -1,9 +1,10 @@
-use super::super::{HasSteeringBehavior, SteeringAcceleration, SteeringAccelerationCalculator,
-                   SteeringBehavior};
-use nalgebra::{angle, distance, Point3, Vector3};
+use super::super::{HasSteeringBehavior, Location, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringError,
+                   SteeringStatus, SteeringVector};
+use nalgebra::Vector3;
 use alga::general::Real;
-use std::f32::MAX;
-use num_traits::identities::Zero;
+use validation;
+use steering_status::target_status;
 use std::cell::RefMut;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -11,27 +12,124 @@ use std::rc::Rc;
 ///Face behavior aims owner to look at target. It only produces an angular
 ///acceleration component enabling owner of the behaviour to turn around its
 ///own axis until facing towards its target
-#[builder(pattern = "immutable")]
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
 #[derive(Builder)]
-pub struct Face<T>
+pub struct Face<T, V = Vector3<T>>
 where
     T: Real,
+    V: SteeringVector<T>,
 {
     /// Common behavior attributes
-    pub behavior: RefCell<SteeringBehavior<T>>,
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Location to look at
+    pub target: Rc<RefCell<Location<T, V>>>,
     pub allign_tolerance: T,
     pub deceleration_radius: T,
     pub time_to_target: T,
 }
 
+/// Tunable parameters of `Face` which can be loaded from data files. Owner and
+/// target are not part of them, they are supplied when the behavior is created.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FaceParameters<T: Real> {
+    /// Stop rotating if remaining angle is smaller than this
+    pub allign_tolerance: T,
+    /// Reduce the angular speed if remaining angle is smaller than this
+    pub deceleration_radius: T,
+    /// How quick should we reach the goal orientation
+    pub time_to_target: T,
+}
+
+impl<T: Real> FaceParameters<T> {
+    /// Checks that parameters make sense
+    pub fn validate(&self) -> Result<(), SteeringError> {
+        validation::arrival(
+            "allign_tolerance",
+            Some(self.allign_tolerance),
+            Some(self.deceleration_radius),
+            Some(self.time_to_target),
+        ).map_err(SteeringError::InvalidParameter)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> FaceBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> FaceBuilder<T, V> {
+        FaceBuilder {
+            behavior: None,
+            target: None,
+            allign_tolerance: None,
+            deceleration_radius: None,
+            time_to_target: None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validation::arrival(
+            "allign_tolerance",
+            self.allign_tolerance,
+            self.deceleration_radius,
+            self.time_to_target,
+        )
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Face<T, V> {
+    /// Creates the behavior using parameters loaded from a data file
+    pub fn with_parameters(
+        behavior: SteeringBehavior<T, V>,
+        target: Rc<RefCell<Location<T, V>>>,
+        parameters: &FaceParameters<T>,
+    ) -> Face<T, V> {
+        Face {
+            behavior: RefCell::new(behavior),
+            target: target,
+            allign_tolerance: parameters.allign_tolerance,
+            deceleration_radius: parameters.deceleration_radius,
+            time_to_target: parameters.time_to_target,
+        }
+    }
+
+    /// returns the tunable parameters of the behavior
+    pub fn parameters(&self) -> FaceParameters<T> {
+        FaceParameters {
+            allign_tolerance: self.allign_tolerance,
+            deceleration_radius: self.deceleration_radius,
+            time_to_target: self.time_to_target,
+        }
+    }
+
+    /// Replaces the tunable parameters of the behavior
+    pub fn set_parameters(&mut self, parameters: &FaceParameters<T>) {
+        self.allign_tolerance = parameters.allign_tolerance;
+        self.deceleration_radius = parameters.deceleration_radius;
+        self.time_to_target = parameters.time_to_target;
+    }
+
+    /// Tests whether the owner looks at the target within `allign_tolerance`, from
+    /// then on the behavior does not accelerate anymore
+    pub fn is_aligned(&self) -> bool {
+        let behavior = self.behavior.borrow();
+        let owner = behavior.owner.borrow();
+        let to_target = *self.target.borrow().get_position() - *owner.get_position();
+        if to_target.is_zero() {
+            return true;
+        }
+        let rotation =
+            wrap_angle_around_zero(to_target.vector_to_angle() - owner.get_orientation());
+        Real::abs(rotation) <= self.allign_tolerance
+    }
+}
 
-impl<T: Real> HasSteeringBehavior<T> for Face<T> {
-    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T>> {
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for Face<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
         self.behavior.borrow_mut()
     }
 }
 
-fn wrap_angle_around_zero<T: Real>(inp: T) -> T {
+/// Maps given angle in radians to the range from -pi to pi
+pub fn wrap_angle_around_zero<T: Real>(inp: T) -> T {
     if inp >= T::zero() {
         let mut rotation = inp % T::two_pi();
         if rotation > T::pi() {
@@ -47,69 +145,132 @@ fn wrap_angle_around_zero<T: Real>(inp: T) -> T {
     }
 }
 
-impl<T: Real> Face<T> {
-    fn reach_orientation(
-        &self,
-        steering_acceleration: Rc<RefCell<SteeringAcceleration<T>>>,
-        target_orientation: T,
-    ) -> Rc<RefCell<SteeringAcceleration<T>>> {
-        let behavior = self.behavior.borrow();
-        let rotation = wrap_angle_around_zero(
-            target_orientation - behavior.owner.borrow().get_orientation(),
-        );
-        let abs_rotation = Real::abs(rotation);
-        if abs_rotation <= self.allign_tolerance {
-            steering_acceleration.borrow_mut().set_zero();
-        }
+/// Sets angular acceleration needed to turn the owner of the behavior towards
+/// given orientation, shared by behaviors which face something
+pub fn reach_orientation<T: Real, V: SteeringVector<T>>(
+    behavior: &SteeringBehavior<T, V>,
+    steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    target_orientation: T,
+    allign_tolerance: T,
+    deceleration_radius: T,
+    time_to_target: T,
+) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+    let rotation =
+        wrap_angle_around_zero(target_orientation - behavior.owner.borrow().get_orientation());
+    let abs_rotation = Real::abs(rotation);
+    if abs_rotation <= allign_tolerance || abs_rotation.is_zero() {
+        steering_acceleration.borrow_mut().set_zero();
+        return steering_acceleration;
+    }
 
-        let mut target_rotation = match self.behavior.borrow().limiter {
-            Some(ref lim) => lim.borrow().get_max_angular_speed(),
-            None => T::from_f32(MAX).unwrap(),
-        };
-        if abs_rotation < self.deceleration_radius {
-            target_rotation *= abs_rotation / self.deceleration_radius;
-        }
+    let mut target_rotation = match behavior.limiter {
+        Some(ref lim) => lim.borrow().get_max_angular_speed(),
+        None => T::max_value(),
+    };
+    if abs_rotation < deceleration_radius {
+        target_rotation *= abs_rotation / deceleration_radius;
+    }
 
-        target_rotation *= rotation / abs_rotation;
+    target_rotation *= rotation / abs_rotation;
 
-        steering_acceleration.borrow_mut().angular =
-            (target_rotation - behavior.owner.borrow().get_angular_velocity()) /
-                self.time_to_target;
-        let angular_acceleration = Real::abs(steering_acceleration.borrow().angular);
-        if let Some(ref lim) = self.behavior.borrow().limiter {
-            if angular_acceleration > lim.borrow().get_max_angular_speed() {
-                steering_acceleration.borrow_mut().angular *= lim.borrow().get_max_angular_speed() /
-                    angular_acceleration;
-            }
+    steering_acceleration.borrow_mut().angular =
+        (target_rotation - behavior.owner.borrow().get_angular_velocity()) / time_to_target;
+    let angular_acceleration = Real::abs(steering_acceleration.borrow().angular);
+    if let Some(ref lim) = behavior.limiter {
+        if angular_acceleration > lim.borrow().get_max_angular_speed() {
+            steering_acceleration.borrow_mut().angular *=
+                lim.borrow().get_max_angular_speed() / angular_acceleration;
         }
-        steering_acceleration.borrow_mut().linear = Vector3::zero();
-        steering_acceleration
+    } else if angular_acceleration > T::max_value() {
+        // unlimited turning overflows for a short time_to_target
+        steering_acceleration.borrow_mut().angular = T::max_value() * (rotation / abs_rotation);
     }
+    steering_acceleration.borrow_mut().linear = V::zero();
+    steering_acceleration
 }
 
-impl<T: Real> SteeringAccelerationCalculator<T> for Face<T> {
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V> for Face<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        self.parameters().validate()
+    }
+
+    fn get_status(&self) -> SteeringStatus {
+        target_status(self.target.borrow().get_position(), || {
+            if self.is_aligned() {
+                SteeringStatus::Aligned
+            } else {
+                SteeringStatus::InProgress
+            }
+        })
+    }
+
     fn calculate_real_steering(
         &self,
-        steering_acceleration: Rc<RefCell<SteeringAcceleration<T>>>,
-    ) -> Rc<RefCell<SteeringAcceleration<T>>> {
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
         let behavior = self.behavior.borrow();
-        steering_acceleration.borrow_mut().linear = behavior.target.borrow().get_position() -
+        steering_acceleration.borrow_mut().linear = *self.target.borrow().get_position() -
             *behavior.owner.borrow().get_position();
-        let to_target = distance(
-            &Point3::from_coordinates(steering_acceleration.borrow().linear),
-            &Point3::origin(),
-        );
-
-        if let Some(ref lim) = self.behavior.borrow().limiter {
-            if to_target.powi(2) < lim.borrow().get_zero_linear_speed_threshold() {
-                steering_acceleration.borrow_mut().set_zero();
-            }
+        let to_target = steering_acceleration.borrow().linear.norm();
+
+        let threshold = match self.behavior.borrow().limiter {
+            Some(ref lim) => lim.borrow().get_zero_linear_speed_threshold(),
+            None => T::zero(),
+        };
+        if to_target.powi(2) < threshold || to_target.is_zero() {
+            steering_acceleration.borrow_mut().set_zero();
+            return steering_acceleration;
         }
 
-        let target_orientation = angle(
-            &steering_acceleration.borrow().linear,
-            &Vector3::new(T::zero(), T::one(), T::zero()),
-        );
-        self.reach_orientation(steering_acceleration, target_orientation)
+        let target_orientation = steering_acceleration.borrow().linear.vector_to_angle();
+        reach_orientation(
+            &behavior,
+            steering_acceleration,
+            target_orientation,
+            self.allign_tolerance,
+            self.deceleration_radius,
+            self.time_to_target,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::{Face, FaceBuilder, SteeringAcceleration,
+                              SteeringAccelerationCalculator, SteeringBehavior, StaticLocation};
+    use super::super::test_common::TestSteerable;
+    use nalgebra::Vector3;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn builder(time_to_target: f32) -> FaceBuilder<f32, Vector3<f32>> {
+        FaceBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: Rc::new(RefCell::new(TestSteerable::new())),
+            }))
+            .target(Rc::new(RefCell::new(StaticLocation::new(
+                Vector3::new(-60.0f32, 50.0, 0.0),
+                0.0,
+            ))))
+            .allign_tolerance(0.01)
+            .deceleration_radius(0.1)
+            .time_to_target(time_to_target)
+    }
+
+    #[test]
+    fn turns_at_unlimited_speed_without_limiter() {
+        let mut face: Face<f32> = builder(2.0).build().unwrap();
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = face.calculate_steering(sa);
+        assert_eq!(::std::f32::MAX / 2.0, result.borrow().angular);
+        assert_eq!(Vector3::new(0.0, 0.0, 0.0), result.borrow().linear);
+
+        // the division by a short time_to_target overflows and is clamped
+        let mut face: Face<f32> = builder(0.1).build().unwrap();
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = face.calculate_steering(sa);
+        assert_eq!(::std::f32::MAX, result.borrow().angular);
     }
 }
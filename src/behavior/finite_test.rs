@@ -0,0 +1,173 @@
+//! Property tests asserting that behaviors never produce accelerations which are not
+//! finite numbers, whatever the positions and velocities of owner and target are.
+use super::super::{Arrive, Evade, Face, Flee, Location, Pursue, PursueMode, Seek, Steerable,
+                   SteeringAcceleration, SteeringAccelerationCalculator, SteeringBehavior};
+use nalgebra::Vector3;
+use quickcheck::quickcheck;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+type Triple = (f32, f32, f32);
+
+struct Agent {
+    position: Vector3<f32>,
+    velocity: Vector3<f32>,
+    orientation: f32,
+}
+
+impl Location<f32> for Agent {
+    fn get_position(&self) -> &Vector3<f32> {
+        &self.position
+    }
+
+    fn get_orientation(&self) -> f32 {
+        self.orientation
+    }
+}
+
+impl Steerable<f32> for Agent {
+    fn get_linear_velocity(&self) -> &Vector3<f32> {
+        &self.velocity
+    }
+
+    fn get_angular_velocity(&self) -> f32 {
+        0.0
+    }
+
+    fn get_bounding_radius(&self) -> f32 {
+        1.0
+    }
+}
+
+struct Scenario {
+    owner: Rc<RefCell<Agent>>,
+    target: Rc<RefCell<Agent>>,
+}
+
+fn scenario(owner: (Triple, Triple), target: (Triple, Triple), same_position: bool) -> Scenario {
+    let owner_position = Vector3::new((owner.0).0, (owner.0).1, (owner.0).2);
+    let target_position = if same_position {
+        owner_position
+    } else {
+        Vector3::new((target.0).0, (target.0).1, (target.0).2)
+    };
+    Scenario {
+        owner: Rc::new(RefCell::new(Agent {
+            position: owner_position,
+            velocity: Vector3::new((owner.1).0, (owner.1).1, (owner.1).2),
+            orientation: (owner.0).0,
+        })),
+        target: Rc::new(RefCell::new(Agent {
+            position: target_position,
+            velocity: Vector3::new((target.1).0, (target.1).1, (target.1).2),
+            orientation: (target.0).1,
+        })),
+    }
+}
+
+impl Scenario {
+    fn behavior(&self) -> RefCell<SteeringBehavior<f32>> {
+        RefCell::new(SteeringBehavior {
+            enabled: true,
+            limiter: None,
+            owner: self.owner.clone(),
+        })
+    }
+}
+
+fn is_finite<C: SteeringAccelerationCalculator<f32>>(calculator: &mut C) -> bool {
+    let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+    calculator.try_calculate_steering(sa).is_ok()
+}
+
+fn positive(value: f32) -> f32 {
+    value.abs() + 0.01
+}
+
+#[test]
+fn seek_and_flee_are_finite() {
+    fn prop(owner: (Triple, Triple), target: (Triple, Triple), same: bool) -> bool {
+        let s = scenario(owner, target, same);
+        let mut seek = Seek {
+            behavior: s.behavior(),
+            target: s.target.clone(),
+        };
+        let mut flee = Flee {
+            behavior: s.behavior(),
+            target: s.target.clone(),
+        };
+        is_finite(&mut seek) && is_finite(&mut flee)
+    }
+    quickcheck(prop as fn((Triple, Triple), (Triple, Triple), bool) -> bool);
+}
+
+#[test]
+fn pursue_and_evade_are_finite() {
+    fn prop(owner: (Triple, Triple), target: (Triple, Triple), same: bool, time: f32) -> bool {
+        let s = scenario(owner, target, same);
+        let mut pursue = Pursue {
+            behavior: s.behavior(),
+            target: s.target.clone(),
+            max_prediction_time: positive(time),
+            mode: PursueMode::Predictive,
+        };
+        let mut intercept = Pursue {
+            behavior: s.behavior(),
+            target: s.target.clone(),
+            max_prediction_time: positive(time),
+            mode: PursueMode::Intercept,
+        };
+        let mut evade = Evade {
+            behavior: s.behavior(),
+            target: s.target.clone(),
+            max_prediction_time: positive(time),
+        };
+        is_finite(&mut pursue) && is_finite(&mut intercept) && is_finite(&mut evade)
+    }
+    quickcheck(prop as fn((Triple, Triple), (Triple, Triple), bool, f32) -> bool);
+}
+
+#[test]
+fn arrive_and_face_are_finite() {
+    fn prop(
+        owner: (Triple, Triple),
+        target: (Triple, Triple),
+        same: bool,
+        parameters: Triple,
+    ) -> bool {
+        let s = scenario(owner, target, same);
+        let (tolerance, deceleration_radius, time_to_target) = parameters;
+        let tolerance = tolerance.abs();
+        let deceleration_radius = tolerance + positive(deceleration_radius);
+        let mut arrive = Arrive {
+            behavior: s.behavior(),
+            target: s.target.clone(),
+            tolerance,
+            deceleration_radius,
+            time_to_target: positive(time_to_target),
+        };
+        let mut face = Face {
+            behavior: s.behavior(),
+            target: s.target.clone(),
+            allign_tolerance: tolerance,
+            deceleration_radius,
+            time_to_target: positive(time_to_target),
+        };
+        is_finite(&mut arrive) && is_finite(&mut face)
+    }
+    quickcheck(prop as fn((Triple, Triple), (Triple, Triple), bool, Triple) -> bool);
+}
+
+#[test]
+fn degenerate_parameter_is_reported() {
+    let origin = ((0.0, 0.0, 0.0), (0.0, 0.0, 0.0));
+    let s = scenario(origin, ((5.0, 0.0, 0.0), (0.0, 0.0, 0.0)), false);
+    let mut arrive = Arrive {
+        behavior: s.behavior(),
+        target: s.target.clone(),
+        tolerance: 0.0,
+        deceleration_radius: 1.0,
+        time_to_target: 0.0,
+    };
+    assert!(!is_finite(&mut arrive));
+}
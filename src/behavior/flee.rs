@@ -1,7 +1,9 @@
-use super::super::{HasSteeringBehavior, SteeringAcceleration, SteeringAccelerationCalculator,
-                   SteeringBehavior};
+use super::super::{HasSteeringBehavior, Location, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringStatus,
+                   SteeringVector};
+use nalgebra::Vector3;
 use alga::general::Real;
-use alga::general::AbstractModule;
+use steering_status::target_status;
 
 use std::cell::RefMut;
 use std::cell::RefCell;
@@ -10,31 +12,48 @@ use std::rc::Rc;
 /// to go away from target
 #[builder(pattern = "immutable")]
 #[derive(Builder)]
-pub struct Flee<T>
+pub struct Flee<T, V = Vector3<T>>
 where
     T: Real,
+    V: SteeringVector<T>,
 {
     /// Common behavior attributes
-    pub behavior: RefCell<SteeringBehavior<T>>,
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// location to flee from
+    pub target: Rc<RefCell<Location<T, V>>>,
 }
 
 
-impl<T: Real> HasSteeringBehavior<T> for Flee<T> {
-    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T>> {
+impl<T: Real, V: SteeringVector<T>> FleeBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> FleeBuilder<T, V> {
+        FleeBuilder {
+            behavior: None,
+            target: None,
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for Flee<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
         self.behavior.borrow_mut()
     }
 }
 
-impl<T: Real> SteeringAccelerationCalculator<T> for Flee<T> {
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V> for Flee<T, V> {
+    fn get_status(&self) -> SteeringStatus {
+        target_status(self.target.borrow().get_position(), || SteeringStatus::InProgress)
+    }
+
     fn calculate_real_steering(
         &self,
-        steering_acceleration: Rc<RefCell<SteeringAcceleration<T>>>,
-    ) -> Rc<RefCell<SteeringAcceleration<T>>> {
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
         let behavior = self.behavior.borrow();
         steering_acceleration.borrow_mut().linear = (*behavior.owner.borrow().get_position() -
-                                                         *behavior.target.borrow().get_position())
-            .normalize()
-            .multiply_by(match self.behavior.borrow().limiter {
+                                                         *self.target.borrow().get_position())
+            .normalize_or_zero()
+            .scale(match self.behavior.borrow().limiter {
                 Some(ref l) => (*l).borrow().get_max_linear_acceleration(),
                 None => T::one(),
             });
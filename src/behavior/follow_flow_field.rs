@@ -0,0 +1,140 @@
+use nalgebra::Vector3;
+use super::super::{HasSteeringBehavior, SteeringAcceleration, SteeringAccelerationCalculator,
+                   SteeringBehavior, SteeringError, SteeringStatus, SteeringVector};
+use pathfinding::FlowField;
+use alga::general::Real;
+use validation;
+
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// FollowFlowField behavior accelerates the owner along the direction a flow field
+/// samples at its position, or where it will be after `prediction_time`. Any
+/// number of agents can share one field. The owner reaches its goal once it is in
+/// a goal cell of the field.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct FollowFlowField<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Field to follow
+    pub flow_field: Rc<RefCell<FlowField<T, V>>>,
+    /// Time used to predict the position of the owner, zero uses the current one
+    #[builder(default = "T::zero()")]
+    pub prediction_time: T,
+}
+
+impl<T: Real, V: SteeringVector<T>> FollowFlowFieldBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> FollowFlowFieldBuilder<T, V> {
+        FollowFlowFieldBuilder {
+            behavior: None,
+            flow_field: None,
+            prediction_time: None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validation::non_negative("prediction_time", self.prediction_time)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for FollowFlowField<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V>
+    for FollowFlowField<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        validation::non_negative("prediction_time", Some(self.prediction_time))
+            .map_err(SteeringError::InvalidParameter)
+    }
+
+    fn get_status(&self) -> SteeringStatus {
+        let position = *self.behavior.borrow().owner.borrow().get_position();
+        let flow_field = self.flow_field.borrow();
+        match flow_field.get_grid().cell_at(&position) {
+            Some(cell) if flow_field.is_goal(cell) => SteeringStatus::Arrived,
+            _ => SteeringStatus::InProgress,
+        }
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        let behavior = self.behavior.borrow();
+        let (position, velocity) = {
+            let owner = behavior.owner.borrow();
+            (*owner.get_position(), *owner.get_linear_velocity())
+        };
+        let location = if self.prediction_time.is_zero() {
+            position
+        } else {
+            position + velocity.scale(self.prediction_time)
+        };
+        let max_linear_acceleration = match behavior.limiter {
+            Some(ref lim) => lim.borrow().get_max_linear_acceleration(),
+            None => T::one(),
+        };
+        steering_acceleration.borrow_mut().linear = self.flow_field
+            .borrow()
+            .sample(&location)
+            .scale(max_linear_acceleration);
+        steering_acceleration.borrow_mut().angular = T::zero();
+        steering_acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FollowFlowFieldBuilder;
+    use super::super::test_common::TestSteerable2d;
+    use super::super::super::{SteeringAcceleration, SteeringAccelerationCalculator,
+                              SteeringBehavior, SteeringStatus};
+    use pathfinding::{FlowFieldBuilder, Grid, GridCell};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn agents_share_a_field() {
+        let grid = Grid::new(8, 8, 1, 1.0f32, Vector2::new(0.0, 0.0)).unwrap();
+        let flow_field = Rc::new(RefCell::new(
+            FlowFieldBuilder::new()
+                .grid(grid)
+                .goals(vec![GridCell::new(4, 4, 0)])
+                .build()
+                .unwrap(),
+        ));
+        let follow = |position: Vector2<f32>| {
+            FollowFlowFieldBuilder::new()
+                .behavior(RefCell::new(SteeringBehavior {
+                    enabled: true,
+                    limiter: None,
+                    owner: Rc::new(RefCell::new(TestSteerable2d::new(position))),
+                }))
+                .flow_field(flow_field.clone())
+                .build()
+                .unwrap()
+        };
+
+        let mut left = follow(Vector2::new(0.5, 4.5));
+        let mut above = follow(Vector2::new(4.5, 7.5));
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = left.calculate_steering(sa);
+        assert!((result.borrow().linear - Vector2::new(1.0, 0.0)).norm() < 1e-5);
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = above.calculate_steering(sa);
+        assert!((result.borrow().linear - Vector2::new(0.0, -1.0)).norm() < 1e-5);
+        assert_eq!(SteeringStatus::InProgress, left.get_status());
+        assert_eq!(SteeringStatus::Arrived, follow(Vector2::new(4.2, 4.9)).get_status());
+    }
+}
@@ -0,0 +1,296 @@
+use nalgebra::Vector3;
+use super::super::{HasSteeringBehavior, LinePath, Location, Path, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringError,
+                   SteeringStatus, SteeringVector};
+use super::follow_path::{FollowPath, FollowPathBuilder};
+use navmesh::{funnel, NavMesh};
+use alga::general::Real;
+use steering_status::target_status;
+use validation;
+
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+struct Plan<T: Real, V: SteeringVector<T>> {
+    goal_polygon: usize,
+    goal: V,
+    corridor: Vec<usize>,
+    path: Rc<RefCell<LinePath<T, V>>>,
+    follow: FollowPath<T, V>,
+}
+
+/// FollowNavMesh behavior moves the owner to the target over a navigation mesh. It
+/// plans a corridor of polygons, follows the string pulled path through it and
+/// plans again once the target moves to another polygon or the owner leaves the
+/// corridor. Moves of the target within its polygon only straighten the path
+/// through the current corridor. Without a path it produces no acceleration.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct FollowNavMesh<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Mesh the owner moves on
+    pub navmesh: Rc<NavMesh<T, V>>,
+    /// Location to move to
+    pub target: Rc<RefCell<Location<T, V>>>,
+    /// Distance along the path between the owner and the point it seeks
+    pub path_offset: T,
+    /// Stop if we are close enough to the target
+    pub tolerance: T,
+    /// Reduce the speed if we are close enough to the target
+    pub deceleration_radius: T,
+    /// How quick should we aproach the target
+    pub time_to_target: T,
+    #[builder(setter(skip), default = "RefCell::new(None)")]
+    plan: RefCell<Option<Plan<T, V>>>,
+}
+
+impl<T: Real, V: SteeringVector<T>> FollowNavMeshBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> FollowNavMeshBuilder<T, V> {
+        FollowNavMeshBuilder {
+            behavior: None,
+            navmesh: None,
+            target: None,
+            path_offset: None,
+            tolerance: None,
+            deceleration_radius: None,
+            time_to_target: None,
+            plan: Default::default(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validation::positive("path_offset", self.path_offset)?;
+        validation::arrival(
+            "tolerance",
+            self.tolerance,
+            self.deceleration_radius,
+            self.time_to_target,
+        )
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> FollowNavMesh<T, V> {
+    /// returns the polygons of the current plan, `None` without a path to the target
+    pub fn get_corridor(&self) -> Option<Vec<usize>> {
+        self.plan.borrow().as_ref().map(|plan| plan.corridor.clone())
+    }
+
+    /// returns the waypoints of the current plan, `None` without a path to the
+    /// target
+    pub fn get_waypoints(&self) -> Option<Vec<V>> {
+        self.plan
+            .borrow()
+            .as_ref()
+            .map(|plan| plan.path.borrow().get_waypoints())
+    }
+
+    /// Updates the plan to the current positions of the owner and the target,
+    /// returns whether a path to the target exists
+    pub fn update_plan(&self) -> bool {
+        let position = *self.behavior.borrow().owner.borrow().get_position();
+        let goal = *self.target.borrow().get_position();
+        let goal_polygon = match self.navmesh.locate(&goal) {
+            Some(goal_polygon) if goal.is_finite() => goal_polygon,
+            _ => {
+                *self.plan.borrow_mut() = None;
+                return false;
+            }
+        };
+        let owner_polygon = self.navmesh.locate(&position);
+        let mut plan = self.plan.borrow_mut();
+        if let Some(ref mut current) = *plan {
+            if current.goal_polygon == goal_polygon {
+                // an owner pushed off the mesh for a moment keeps its plan
+                let index = match owner_polygon {
+                    Some(owner_polygon) => {
+                        current.corridor.iter().position(|polygon| *polygon == owner_polygon)
+                    }
+                    None => Some(0),
+                };
+                if let Some(index) = index {
+                    if current.goal != goal {
+                        current.corridor.drain(..index);
+                        *current.path.borrow_mut() =
+                            line_path(&*self.navmesh, &current.corridor, position, goal);
+                        current.goal = goal;
+                    }
+                    return true;
+                }
+            }
+        }
+        let owner_polygon = match owner_polygon {
+            Some(owner_polygon) => owner_polygon,
+            None => {
+                *plan = None;
+                return false;
+            }
+        };
+        *plan = self.navmesh
+            .find_corridor(owner_polygon, &position, goal_polygon, &goal)
+            .map(|corridor| {
+                let path = Rc::new(RefCell::new(
+                    line_path(&*self.navmesh, &corridor, position, goal),
+                ));
+                let follow = FollowPathBuilder::new()
+                    .behavior(RefCell::new(self.behavior.borrow().clone()))
+                    .path(path.clone() as Rc<RefCell<Path<T, V>>>)
+                    .path_offset(self.path_offset)
+                    .tolerance(self.tolerance)
+                    .deceleration_radius(self.deceleration_radius)
+                    .time_to_target(self.time_to_target)
+                    .build()
+                    .unwrap();
+                Plan {
+                    goal_polygon: goal_polygon,
+                    goal: goal,
+                    corridor: corridor,
+                    path: path,
+                    follow: follow,
+                }
+            });
+        plan.is_some()
+    }
+}
+
+/// returns the string pulled path through a corridor
+fn line_path<T: Real, V: SteeringVector<T>>(
+    navmesh: &NavMesh<T, V>,
+    corridor: &[usize],
+    start: V,
+    goal: V,
+) -> LinePath<T, V> {
+    let mut waypoints = funnel(&navmesh.portals(corridor), start, goal);
+    if waypoints.len() < 2 {
+        waypoints.push(goal);
+    }
+    LinePath::new(waypoints, true).unwrap()
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for FollowNavMesh<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V>
+    for FollowNavMesh<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        validation::positive("path_offset", Some(self.path_offset))
+            .and_then(|_| {
+                validation::arrival(
+                    "tolerance",
+                    Some(self.tolerance),
+                    Some(self.deceleration_radius),
+                    Some(self.time_to_target),
+                )
+            })
+            .map_err(SteeringError::InvalidParameter)
+    }
+
+    fn get_status(&self) -> SteeringStatus {
+        let target = *self.target.borrow().get_position();
+        target_status(&target, || {
+            let position = *self.behavior.borrow().owner.borrow().get_position();
+            if position.distance(&target) <= self.tolerance {
+                SteeringStatus::Arrived
+            } else {
+                SteeringStatus::InProgress
+            }
+        })
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        if !self.update_plan() {
+            steering_acceleration.borrow_mut().set_zero();
+            return steering_acceleration;
+        }
+        let plan = self.plan.borrow();
+        let plan = plan.as_ref().unwrap();
+        *plan.follow.behavior.borrow_mut() = self.behavior.borrow().clone();
+        plan.follow.calculate_real_steering(steering_acceleration)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FollowNavMeshBuilder;
+    use super::super::test_common::TestSteerable2d;
+    use super::super::super::{SteeringAcceleration, SteeringAccelerationCalculator,
+                              SteeringBehavior, SteeringStatus, StaticLocation};
+    use navmesh::NavMesh;
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn plans_again_when_the_target_changes_polygons() {
+        let navmesh = NavMesh::new(
+            vec![
+                Vector2::new(0.0f32, 0.0),
+                Vector2::new(1.0, 0.0),
+                Vector2::new(2.0, 0.0),
+                Vector2::new(0.0, 1.0),
+                Vector2::new(1.0, 1.0),
+                Vector2::new(2.0, 1.0),
+                Vector2::new(1.0, 2.0),
+                Vector2::new(2.0, 2.0),
+            ],
+            vec![vec![0, 1, 4, 3], vec![1, 2, 5, 4], vec![4, 5, 7, 6]],
+        ).unwrap();
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.5f32, 0.5))));
+        let target = Rc::new(RefCell::new(StaticLocation::new(Vector2::new(1.5f32, 1.8), 0.0)));
+        let mut follow = FollowNavMeshBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: owner.clone(),
+            }))
+            .navmesh(Rc::new(navmesh))
+            .target(target.clone())
+            .path_offset(0.5)
+            .tolerance(0.1)
+            .deceleration_radius(0.2)
+            .time_to_target(0.1)
+            .build()
+            .unwrap();
+
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = follow.calculate_steering(sa);
+        let expected = Vector2::new(1.0f32, 1.0).normalize();
+        assert!((result.borrow().linear - expected).norm() < 1e-5);
+        assert_eq!(Some(vec![0, 1, 2]), follow.get_corridor());
+        assert_eq!(SteeringStatus::InProgress, follow.get_status());
+
+        // moving within the goal polygon keeps the corridor
+        target.borrow_mut().position = Vector2::new(1.8, 1.5);
+        assert!(follow.update_plan());
+        assert_eq!(Some(vec![0, 1, 2]), follow.get_corridor());
+        assert_eq!(Some(&Vector2::new(1.8, 1.5)), follow.get_waypoints().unwrap().last());
+
+        target.borrow_mut().position = Vector2::new(1.5, 0.5);
+        assert!(follow.update_plan());
+        assert_eq!(Some(vec![0, 1]), follow.get_corridor());
+        assert_eq!(
+            Some(vec![Vector2::new(0.5, 0.5), Vector2::new(1.5, 0.5)]),
+            follow.get_waypoints()
+        );
+
+        target.borrow_mut().position = Vector2::new(0.5, 1.5);
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        assert!(follow.calculate_steering(sa).borrow().is_zero());
+        assert_eq!(None, follow.get_corridor());
+
+        owner.borrow_mut().set_position(Vector2::new(0.5, 1.45));
+        assert_eq!(SteeringStatus::Arrived, follow.get_status());
+    }
+}
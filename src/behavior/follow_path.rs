@@ -0,0 +1,296 @@
+use nalgebra::Vector3;
+use super::super::{HasSteeringBehavior, Path, SpeedProfile, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringError,
+                   SteeringStatus, SteeringVector};
+use super::arrive::arrive;
+use super::match_velocity::match_velocity;
+use alga::general::Real;
+use validation;
+use std::cell::Cell;
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+///FollowPath behavior steers the owner along a path. It finds the point of the
+///path closest to the owner, or to where the owner will be after
+///`prediction_time`, and seeks the point `path_offset` further along the path.
+///A negative offset follows the path backwards. If arriving is enabled the owner
+///slows down and stops at the end of an open path. With a speed profile the owner
+///matches the speed planned at its distance along the path instead, slowing down
+///before bends and stopping at the end of an open path.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct FollowPath<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Path to follow
+    pub path: Rc<RefCell<Path<T, V>>>,
+    /// Distance along the path between the owner and the point it seeks
+    pub path_offset: T,
+    /// Time used to predict the position of the owner, zero uses the current one
+    #[builder(default = "T::zero()")]
+    pub prediction_time: T,
+    /// Arrive at the end of an open path instead of overshooting it
+    #[builder(default = "true")]
+    pub arrive_enabled: bool,
+    /// Stop if we are close enough to the end of the path
+    pub tolerance: T,
+    /// Reduce the speed if we are close enough to the end of the path
+    pub deceleration_radius: T,
+    /// How quick should we aproach the end of the path, or the planned velocity
+    pub time_to_target: T,
+    /// Speeds planned along the path, it is ignored when following the path
+    /// backwards
+    #[builder(default = "None")]
+    pub speed_profile: Option<Rc<SpeedProfile<T>>>,
+    #[builder(setter(skip), default = "Cell::new(T::zero())")]
+    path_distance: Cell<T>,
+}
+
+impl<T: Real, V: SteeringVector<T>> FollowPathBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> FollowPathBuilder<T, V> {
+        FollowPathBuilder {
+            behavior: None,
+            path: None,
+            path_offset: None,
+            prediction_time: None,
+            arrive_enabled: None,
+            tolerance: None,
+            deceleration_radius: None,
+            time_to_target: None,
+            speed_profile: None,
+            path_distance: Default::default(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validation::non_negative("prediction_time", self.prediction_time)?;
+        validation::arrival(
+            "tolerance",
+            self.tolerance,
+            self.deceleration_radius,
+            self.time_to_target,
+        )
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> FollowPath<T, V> {
+    /// returns the distance along the path of the owner found by the last
+    /// calculation
+    pub fn get_path_distance(&self) -> T {
+        self.path_distance.get()
+    }
+
+    /// Tests whether the owner reached the end of an open path within
+    /// `tolerance`, the end is the start of the path if the offset is negative. A
+    /// closed path never ends.
+    pub fn is_at_end(&self) -> bool {
+        let path = self.path.borrow();
+        if !path.is_open() {
+            return false;
+        }
+        let end = if self.path_offset < T::zero() {
+            path.get_start_point()
+        } else {
+            path.get_end_point()
+        };
+        let behavior = self.behavior.borrow();
+        let owner = behavior.owner.borrow();
+        owner.get_position().distance(&end) <= self.tolerance
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for FollowPath<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V> for FollowPath<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        validation::non_negative("prediction_time", Some(self.prediction_time))
+            .and_then(|_| {
+                validation::arrival(
+                    "tolerance",
+                    Some(self.tolerance),
+                    Some(self.deceleration_radius),
+                    Some(self.time_to_target),
+                )
+            })
+            .map_err(SteeringError::InvalidParameter)
+    }
+
+    fn get_status(&self) -> SteeringStatus {
+        if self.is_at_end() {
+            SteeringStatus::PathFinished
+        } else {
+            SteeringStatus::InProgress
+        }
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        let behavior = self.behavior.borrow();
+        let path = self.path.borrow();
+        let (position, velocity) = {
+            let owner = behavior.owner.borrow();
+            (*owner.get_position(), *owner.get_linear_velocity())
+        };
+        let location = if self.prediction_time.is_zero() {
+            position
+        } else {
+            position + velocity.scale(self.prediction_time)
+        };
+        let distance = path.calculate_distance(&location);
+        self.path_distance.set(distance);
+        let target_distance = distance + self.path_offset;
+        let target_position = path.calculate_target_position(target_distance);
+
+        if let Some(ref speed_profile) = self.speed_profile {
+            if self.path_offset >= T::zero() {
+                let velocity = (target_position - position)
+                    .normalize_or_zero()
+                    .scale(speed_profile.speed_at(distance));
+                return match_velocity(
+                    &behavior,
+                    steering_acceleration,
+                    velocity,
+                    self.time_to_target,
+                );
+            }
+        }
+
+        if self.arrive_enabled && path.is_open() {
+            let near_end = if self.path_offset >= T::zero() {
+                target_distance > path.get_length() - self.deceleration_radius
+            } else {
+                target_distance < self.deceleration_radius
+            };
+            if near_end {
+                return arrive(
+                    &behavior,
+                    steering_acceleration,
+                    target_position,
+                    self.tolerance,
+                    self.deceleration_radius,
+                    self.time_to_target,
+                );
+            }
+        }
+
+        let max_linear_acceleration = match behavior.limiter {
+            Some(ref lim) => lim.borrow().get_max_linear_acceleration(),
+            None => T::one(),
+        };
+        steering_acceleration.borrow_mut().linear = (target_position - position)
+            .normalize_or_zero()
+            .scale(max_linear_acceleration);
+        steering_acceleration.borrow_mut().angular = T::zero();
+        steering_acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::{LinePath, Path, SpeedProfile, SteeringAcceleration,
+                              SteeringAccelerationCalculator, SteeringBehavior};
+    use super::super::test_common::TestSteerable2d;
+    use super::{FollowPath, FollowPathBuilder};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn follow_path(
+        owner: Rc<RefCell<TestSteerable2d>>,
+        open: bool,
+    ) -> FollowPath<f32, Vector2<f32>> {
+        let path = LinePath::new(
+            vec![
+                Vector2::new(0.0f32, 0.0),
+                Vector2::new(10.0, 0.0),
+                Vector2::new(10.0, 10.0),
+            ],
+            open,
+        ).unwrap();
+        FollowPathBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: owner,
+            }))
+            .path(Rc::new(RefCell::new(path)) as Rc<RefCell<Path<f32, Vector2<f32>>>>)
+            .path_offset(2.0)
+            .tolerance(0.5)
+            .deceleration_radius(1.0)
+            .time_to_target(0.1)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn seeks_ahead_on_the_path() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(9.0f32, -1.0))));
+        let mut follow = follow_path(owner.clone(), true);
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let sa = follow.calculate_steering(sa);
+        assert_eq!(9.0, follow.get_path_distance());
+        let expected = Vector2::new(1.0f32, 2.0).normalize();
+        assert!((sa.borrow().linear - expected).norm() < 1e-6);
+        assert!(!follow.is_at_end());
+
+        owner.borrow_mut().set_position(Vector2::new(10.0, 9.8));
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        assert!(follow.calculate_steering(sa).borrow().is_zero());
+        assert!(follow.is_at_end());
+    }
+
+    #[test]
+    fn matches_the_planned_speed() {
+        let mut owner = TestSteerable2d::new(Vector2::new(8.0f32, 0.0));
+        owner.set_linear_velocity(Vector2::new(2.1, 0.0));
+        let owner = Rc::new(RefCell::new(owner));
+        let path = LinePath::new(vec![Vector2::new(0.0f32, 0.0), Vector2::new(10.0, 0.0)], true)
+            .unwrap();
+        let speed_profile = SpeedProfile::new(&path, 0.5, 4.0, 1.0, 1.0).unwrap();
+        let mut follow = FollowPathBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: owner.clone(),
+            }))
+            .path(Rc::new(RefCell::new(path)) as Rc<RefCell<Path<f32, Vector2<f32>>>>)
+            .path_offset(1.0)
+            .tolerance(0.5)
+            .deceleration_radius(1.0)
+            .time_to_target(0.5)
+            .speed_profile(Some(Rc::new(speed_profile)))
+            .build()
+            .unwrap();
+
+        // the profile plans 2 at 8 to stop at 10 with an acceleration of 1
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = follow.calculate_steering(sa);
+        assert!((result.borrow().linear - Vector2::new(-0.2, 0.0)).norm() < 1e-5);
+
+        owner.borrow_mut().set_position(Vector2::new(10.0, 0.0));
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = follow.calculate_steering(sa);
+        assert!((result.borrow().linear - Vector2::new(-1.0, 0.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn closed_path_never_ends() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(10.0f32, 10.0))));
+        let mut follow = follow_path(owner, false);
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        assert!(!follow.calculate_steering(sa).borrow().is_zero());
+        assert!(!follow.is_at_end());
+    }
+}
@@ -0,0 +1,202 @@
+use nalgebra::Vector3;
+use super::super::{HasSteeringBehavior, Location, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringError,
+                   SteeringStatus, SteeringVector};
+use super::arrive::arrive;
+use obstacle::ObstacleSet;
+use alga::general::Real;
+use steering_status::target_status;
+use validation;
+
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Hide behavior looks for the hiding spots behind obstacles near the owner, as seen
+/// from the hunter, and arrives at the closest one. Without an obstacle in
+/// `detection_radius` it produces no acceleration.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct Hide<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Location to hide from
+    pub target: Rc<RefCell<Location<T, V>>>,
+    /// Obstacles to hide behind
+    pub obstacles: Rc<RefCell<ObstacleSet<T, V>>>,
+    /// Only obstacles closer than this to the owner are considered
+    pub detection_radius: T,
+    /// Distance between a hiding spot and the boundary of its obstacle
+    pub distance_from_boundary: T,
+    /// Stop if we are close enough to the hiding spot
+    pub tolerance: T,
+    /// Reduce the speed if we are close enough to the hiding spot
+    pub deceleration_radius: T,
+    /// How quick should we aproach the hiding spot
+    pub time_to_target: T,
+}
+
+impl<T: Real, V: SteeringVector<T>> HideBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> HideBuilder<T, V> {
+        HideBuilder {
+            behavior: None,
+            target: None,
+            obstacles: None,
+            detection_radius: None,
+            distance_from_boundary: None,
+            tolerance: None,
+            deceleration_radius: None,
+            time_to_target: None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validation::non_negative("detection_radius", self.detection_radius)?;
+        validation::non_negative("distance_from_boundary", self.distance_from_boundary)?;
+        validation::arrival(
+            "tolerance",
+            self.tolerance,
+            self.deceleration_radius,
+            self.time_to_target,
+        )
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Hide<T, V> {
+    /// returns the hiding spot closest to the owner
+    pub fn find_hiding_spot(&self) -> Option<V> {
+        let behavior = self.behavior.borrow();
+        let position = *behavior.owner.borrow().get_position();
+        let hunter = *self.target.borrow().get_position();
+        let mut closest: Option<(T, V)> = None;
+        self.obstacles.borrow().find_obstacles(
+            &position,
+            self.detection_radius,
+            &mut |obstacle| {
+                let spot = obstacle.hiding_spot(&hunter, self.distance_from_boundary);
+                let square_distance = (spot - position).norm_squared();
+                if closest.map_or(true, |closest| square_distance < closest.0) {
+                    closest = Some((square_distance, spot));
+                }
+            },
+        );
+        closest.map(|closest| closest.1)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for Hide<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V> for Hide<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        validation::non_negative("detection_radius", Some(self.detection_radius))
+            .and_then(|_| {
+                validation::non_negative(
+                    "distance_from_boundary",
+                    Some(self.distance_from_boundary),
+                )
+            })
+            .and_then(|_| {
+                validation::arrival(
+                    "tolerance",
+                    Some(self.tolerance),
+                    Some(self.deceleration_radius),
+                    Some(self.time_to_target),
+                )
+            })
+            .map_err(SteeringError::InvalidParameter)
+    }
+
+    fn get_status(&self) -> SteeringStatus {
+        target_status(self.target.borrow().get_position(), || {
+            let position = *self.behavior.borrow().owner.borrow().get_position();
+            match self.find_hiding_spot() {
+                Some(spot) if spot.distance(&position) <= self.tolerance => {
+                    SteeringStatus::Arrived
+                }
+                _ => SteeringStatus::InProgress,
+            }
+        })
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        match self.find_hiding_spot() {
+            Some(spot) => arrive(
+                &*self.behavior.borrow(),
+                steering_acceleration,
+                spot,
+                self.tolerance,
+                self.deceleration_radius,
+                self.time_to_target,
+            ),
+            None => {
+                steering_acceleration.borrow_mut().set_zero();
+                steering_acceleration
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HideBuilder;
+    use super::super::test_common::TestSteerable2d;
+    use super::super::super::{SteeringAcceleration, SteeringAccelerationCalculator,
+                              SteeringBehavior, SteeringStatus, StaticLocation};
+    use obstacle::{Circle, Obstacle, ObstacleSet, Segment};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn hides_behind_closest_obstacle() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let obstacles: Vec<Rc<Obstacle<f32, Vector2<f32>>>> = vec![
+            Rc::new(Circle::new(Vector2::new(3.0f32, 0.0), 1.0)),
+            Rc::new(Segment::new(Vector2::new(-2.0f32, 5.0), Vector2::new(2.0, 5.0))),
+            Rc::new(Circle::new(Vector2::new(-40.0f32, 0.0), 1.0)),
+        ];
+        let hunter = Rc::new(RefCell::new(StaticLocation::new(Vector2::new(10.0f32, 0.0), 0.0)));
+        let mut hide = HideBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: owner.clone(),
+            }))
+            .target(hunter.clone())
+            .obstacles(Rc::new(RefCell::new(obstacles)) as
+                Rc<RefCell<ObstacleSet<f32, Vector2<f32>>>>)
+            .detection_radius(10.0)
+            .distance_from_boundary(0.5)
+            .tolerance(0.1)
+            .deceleration_radius(1.0)
+            .time_to_target(1.0)
+            .build()
+            .unwrap();
+
+        // the spot behind the circle is at (1.5, 0), the one behind the wall further
+        let spot = hide.find_hiding_spot().unwrap();
+        assert!((spot - Vector2::new(1.5, 0.0)).norm() < 1e-5);
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = hide.calculate_steering(sa);
+        assert!((result.borrow().linear - Vector2::new(1.0, 0.0)).norm() < 1e-5);
+        assert_eq!(SteeringStatus::InProgress, hide.get_status());
+
+        owner.borrow_mut().set_position(Vector2::new(1.5, 0.0));
+        assert_eq!(SteeringStatus::Arrived, hide.get_status());
+
+        hunter.borrow_mut().position = Vector2::new(::std::f32::NAN, 0.0);
+        assert_eq!(SteeringStatus::TargetLost, hide.get_status());
+    }
+}
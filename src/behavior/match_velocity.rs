@@ -0,0 +1,223 @@
+use nalgebra::Vector3;
+use super::super::{HasSteeringBehavior, Steerable, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringError, SteeringStatus,
+                   SteeringVector};
+use alga::general::Real;
+use validation;
+use steering_status::target_status;
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+///MatchVelocity behavior accelerates the owner to move with the velocity of the
+///target, reaching it within `time_to_target` if the max linear acceleration
+///allows.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct MatchVelocity<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Agent whose velocity is matched
+    pub target: Rc<RefCell<Steerable<T, V>>>,
+    /// How quick should we match the velocity
+    pub time_to_target: T,
+}
+
+impl<T: Real, V: SteeringVector<T>> MatchVelocityBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> MatchVelocityBuilder<T, V> {
+        MatchVelocityBuilder {
+            behavior: None,
+            target: None,
+            time_to_target: None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validation::positive("time_to_target", self.time_to_target)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for MatchVelocity<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V>
+    for MatchVelocity<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        validation::positive("time_to_target", Some(self.time_to_target))
+            .map_err(SteeringError::InvalidParameter)
+    }
+
+    fn get_status(&self) -> SteeringStatus {
+        target_status(self.target.borrow().get_position(), || SteeringStatus::InProgress)
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        match_velocity(
+            &self.behavior.borrow(),
+            steering_acceleration,
+            *self.target.borrow().get_linear_velocity(),
+            self.time_to_target,
+        )
+    }
+}
+
+/// Sets linear acceleration needed to reach given velocity within
+/// `time_to_target`, capped by the max linear acceleration. Shared by behaviors
+/// which steer the velocity of the owner.
+pub fn match_velocity<T: Real, V: SteeringVector<T>>(
+    behavior: &SteeringBehavior<T, V>,
+    steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    target_velocity: V,
+    time_to_target: T,
+) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+    {
+        let mut sa = steering_acceleration.borrow_mut();
+        let max_linear_acceleration = match behavior.limiter {
+            Some(ref lim) => lim.borrow().get_max_linear_acceleration(),
+            None => T::one(),
+        };
+        let linear = (target_velocity - *behavior.owner.borrow().get_linear_velocity())
+            .scale(T::one() / time_to_target);
+        let norm = linear.norm();
+        sa.linear = if norm > max_linear_acceleration {
+            linear.scale(max_linear_acceleration / norm)
+        } else {
+            linear
+        };
+        sa.angular = T::zero();
+    }
+    steering_acceleration
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MatchVelocity, MatchVelocityBuilder};
+    use super::super::test_common::TestSteerable2d;
+    use super::super::super::{SteeringAcceleration, SteeringAccelerationCalculator,
+                              SteeringBehavior, SteeringError, SteeringStatus};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn matches_the_velocity_of_the_target() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let mut target = TestSteerable2d::new(Vector2::new(5.0f32, 5.0));
+        target.set_linear_velocity(Vector2::new(0.05, 0.0));
+        let target = Rc::new(RefCell::new(target));
+        let mut match_velocity = MatchVelocityBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: owner.clone(),
+            }))
+            .target(target.clone())
+            .time_to_target(0.1)
+            .build()
+            .unwrap();
+
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = match_velocity.calculate_steering(sa);
+        assert!((result.borrow().linear - Vector2::new(0.5, 0.0)).norm() < 1e-6);
+
+        // accelerations beyond the max are capped
+        target.borrow_mut().set_linear_velocity(Vector2::new(0.0, -3.0));
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = match_velocity.calculate_steering(sa);
+        assert!((result.borrow().linear - Vector2::new(0.0, -1.0)).norm() < 1e-6);
+
+        owner.borrow_mut().set_linear_velocity(Vector2::new(0.0, -3.0));
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        assert!(match_velocity.calculate_steering(sa).borrow().is_zero());
+    }
+
+    fn builder(
+        target_velocity: Vector2<f32>,
+    ) -> (
+        Rc<RefCell<TestSteerable2d>>,
+        Rc<RefCell<TestSteerable2d>>,
+        MatchVelocityBuilder<f32, Vector2<f32>>,
+    ) {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let mut target = TestSteerable2d::new(Vector2::new(5.0f32, 5.0));
+        target.set_linear_velocity(target_velocity);
+        let target = Rc::new(RefCell::new(target));
+        let builder = MatchVelocityBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: owner.clone(),
+            }))
+            .target(target.clone());
+        (owner, target, builder)
+    }
+
+    #[test]
+    fn acceleration_scales_with_time_to_target() {
+        let (_, _, builder) = builder(Vector2::new(0.1, -0.05));
+        let mut quick: MatchVelocity<f32, Vector2<f32>> =
+            builder.time_to_target(0.5).build().unwrap();
+        let mut slow: MatchVelocity<f32, Vector2<f32>> =
+            builder.time_to_target(1.0).build().unwrap();
+
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let quick_linear = quick.calculate_steering(sa).borrow().linear;
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let slow_linear = slow.calculate_steering(sa).borrow().linear;
+        assert!((quick_linear - Vector2::new(0.2, -0.1)).norm() < 1e-6);
+        assert!((quick_linear - slow_linear * 2.0).norm() < 1e-6);
+    }
+
+    #[test]
+    fn brakes_to_a_standing_target() {
+        let (owner, _, builder) = builder(Vector2::new(0.0, 0.0));
+        let mut match_velocity = builder.time_to_target(0.5).build().unwrap();
+        owner.borrow_mut().set_linear_velocity(Vector2::new(0.25, 0.0));
+
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        sa.borrow_mut().angular = 1.0;
+        let result = match_velocity.calculate_steering(sa);
+        assert!((result.borrow().linear - Vector2::new(-0.5, 0.0)).norm() < 1e-6);
+        assert_eq!(0.0, result.borrow().angular);
+    }
+
+    #[test]
+    fn validate_rejects_non_positive_time_to_target() {
+        let (_, _, builder) = builder(Vector2::new(0.0, 0.0));
+        assert_eq!(
+            Some("time_to_target must be a positive number".to_string()),
+            builder.clone().time_to_target(0.0).build().err()
+        );
+        assert!(builder.clone().time_to_target(-1.0).build().is_err());
+
+        let mut match_velocity = builder.time_to_target(0.1).build().unwrap();
+        assert_eq!(Ok(()), match_velocity.validate());
+        match_velocity.time_to_target = ::std::f32::NAN;
+        assert_eq!(
+            Err(SteeringError::InvalidParameter(
+                "time_to_target must be a positive number".to_string()
+            )),
+            match_velocity.validate()
+        );
+    }
+
+    #[test]
+    fn status_reports_lost_target() {
+        let (_, target, builder) = builder(Vector2::new(0.0, 0.0));
+        let match_velocity = builder.time_to_target(0.1).build().unwrap();
+        assert_eq!(SteeringStatus::InProgress, match_velocity.get_status());
+        target.borrow_mut().set_position(Vector2::new(::std::f32::NAN, 0.0));
+        assert_eq!(SteeringStatus::TargetLost, match_velocity.get_status());
+    }
+}
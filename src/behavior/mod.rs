@@ -1,16 +1,61 @@
+//! Steering behaviors. Each one is created through its builder, whose `new()`
+//! returns a builder with no fields set. Derived `default()` of a builder needs its
+//! type parameters to implement `Default`, which the `Real` and `SteeringVector`
+//! bounds do not guarantee.
 mod seek;
 mod flee;
 mod pursue;
 mod arrive;
+mod match_velocity;
 mod evade;
 mod face;
+mod wander;
+mod follow_path;
+mod blended_steering;
+mod priority_steering;
+mod state_machine;
+mod utility_steering;
+mod separation;
+mod cohesion;
+mod collision_avoidance;
+mod raycast_obstacle_avoidance;
+mod hide;
+mod containment;
+mod wall_avoidance;
+mod follow_nav_mesh;
+mod follow_flow_field;
+mod patrol;
 
 #[cfg(test)]
-mod test_common;
+pub mod test_common;
+#[cfg(test)]
+mod finite_test;
+#[cfg(all(test, feature = "serde"))]
+mod serde_test;
 
-pub use self::seek::Seek;
-pub use self::flee::Flee;
-pub use self::pursue::Pursue;
-pub use self::arrive::Arrive;
-pub use self::evade::Evade;
-pub use self::face::Face;
+pub use self::seek::{Seek, SeekBuilder};
+pub use self::flee::{Flee, FleeBuilder};
+pub use self::pursue::{Pursue, PursueBuilder, PursueMode, PursueParameters};
+pub use self::arrive::{Arrive, ArriveBuilder, ArriveParameters};
+pub use self::match_velocity::{MatchVelocity, MatchVelocityBuilder};
+pub use self::evade::{Evade, EvadeBuilder, EvadeParameters};
+pub use self::face::{Face, FaceBuilder, FaceParameters};
+pub use self::wander::{Wander, WanderBuilder, WanderParameters};
+pub use self::follow_path::{FollowPath, FollowPathBuilder};
+pub use self::blended_steering::{BehaviorAndWeight, BlendedSteering, BlendedSteeringBuilder};
+pub use self::priority_steering::{PrioritySteering, PrioritySteeringBuilder};
+pub use self::state_machine::{SteeringState, SteeringStateMachine, SteeringStateMachineBuilder,
+                              SteeringTransition, TransitionGuard};
+pub use self::utility_steering::{Consideration, ResponseCurve, UtilityContext, UtilityOption,
+                                 UtilitySelection, UtilitySteering, UtilitySteeringBuilder};
+pub use self::separation::{Separation, SeparationBuilder};
+pub use self::cohesion::{Cohesion, CohesionBuilder};
+pub use self::collision_avoidance::{CollisionAvoidance, CollisionAvoidanceBuilder};
+pub use self::raycast_obstacle_avoidance::{RayConfiguration, RaycastObstacleAvoidance,
+                                          RaycastObstacleAvoidanceBuilder};
+pub use self::hide::{Hide, HideBuilder};
+pub use self::containment::{Containment, ContainmentBuilder};
+pub use self::wall_avoidance::{WallAvoidance, WallAvoidanceBuilder};
+pub use self::follow_nav_mesh::{FollowNavMesh, FollowNavMeshBuilder};
+pub use self::follow_flow_field::{FollowFlowField, FollowFlowFieldBuilder};
+pub use self::patrol::{Patrol, PatrolBuilder, PatrolMode, PatrolWaypoint};
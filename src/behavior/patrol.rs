@@ -0,0 +1,440 @@
+use nalgebra::Vector3;
+use super::super::{ArriveParameters, FaceParameters, HasSteeringBehavior, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringError,
+                   SteeringStatus, SteeringVector};
+use super::arrive::arrive;
+use super::face::reach_orientation;
+use alga::general::Real;
+use steering_vector::is_finite;
+use validation;
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Order in which a `Patrol` visits its waypoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PatrolMode {
+    /// goes back to the first waypoint after the last one
+    Loop,
+    /// walks the waypoints back and forth, the last one is visited once per turn
+    PingPong,
+    /// stops at the last waypoint
+    Once,
+}
+
+/// A stop of a patrol
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PatrolWaypoint<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// Location to arrive at
+    pub position: V,
+    /// Seconds to wait at the waypoint before moving on
+    pub dwell_time: T,
+    /// Orientation to face while waiting, the owner keeps its orientation if missing
+    pub orientation: Option<T>,
+}
+
+impl<T: Real, V: SteeringVector<T>> PatrolWaypoint<T, V> {
+    /// Creates a waypoint the owner passes without waiting
+    pub fn new(position: V) -> PatrolWaypoint<T, V> {
+        PatrolWaypoint {
+            position: position,
+            dwell_time: T::zero(),
+            orientation: None,
+        }
+    }
+}
+
+///Patrol behavior arrives at a list of waypoints one after another, waits at each
+///one for its dwell time while facing the orientation of the waypoint and moves on
+///in the order the mode asks for. Call `update` once per frame to advance the
+///patrol, it reports every waypoint the owner reaches.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct Patrol<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Stops of the patrol, at least one is needed
+    pub waypoints: Vec<PatrolWaypoint<T, V>>,
+    /// Order of the stops
+    #[builder(default = "PatrolMode::Loop")]
+    pub mode: PatrolMode,
+    /// How the owner arrives at a waypoint, `tolerance` tells when it is reached
+    pub arrive: ArriveParameters<T>,
+    /// How the owner faces the orientation of a waypoint, needed if any waypoint
+    /// has one
+    #[builder(default = "None")]
+    pub face: Option<FaceParameters<T>>,
+    #[builder(setter(skip), default = "0")]
+    current: usize,
+    #[builder(setter(skip), default = "true")]
+    forward: bool,
+    #[builder(setter(skip), default = "None")]
+    dwell_elapsed: Option<T>,
+    #[builder(setter(skip), default = "false")]
+    finished: bool,
+}
+
+impl<T: Real, V: SteeringVector<T>> PatrolBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> PatrolBuilder<T, V> {
+        PatrolBuilder {
+            behavior: None,
+            waypoints: None,
+            mode: None,
+            arrive: None,
+            face: None,
+            current: Default::default(),
+            forward: Default::default(),
+            dwell_elapsed: Default::default(),
+            finished: Default::default(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(ref arrive) = self.arrive {
+            check_arrive(arrive)?;
+        }
+        if let Some(ref waypoints) = self.waypoints {
+            let face = self.face.as_ref().and_then(|face| face.as_ref());
+            check_waypoints(waypoints, face)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks the parameters of arriving at a waypoint
+fn check_arrive<T: Real>(arrive: &ArriveParameters<T>) -> Result<(), String> {
+    validation::arrival(
+        "tolerance",
+        Some(arrive.tolerance),
+        Some(arrive.deceleration_radius),
+        Some(arrive.time_to_target),
+    )
+}
+
+/// Checks waypoints and the face parameters their orientations need
+fn check_waypoints<T: Real, V: SteeringVector<T>>(
+    waypoints: &[PatrolWaypoint<T, V>],
+    face: Option<&FaceParameters<T>>,
+) -> Result<(), String> {
+    if waypoints.is_empty() {
+        return Err("patrol needs at least one waypoint".to_string());
+    }
+    for waypoint in waypoints {
+        if !waypoint.position.is_finite() {
+            return Err("waypoint positions must be finite".to_string());
+        }
+        validation::non_negative("dwell_time", Some(waypoint.dwell_time))?;
+        if let Some(orientation) = waypoint.orientation {
+            if !is_finite(orientation) {
+                return Err("waypoint orientations must be finite".to_string());
+            }
+            if face.is_none() {
+                return Err("face must be set to face waypoint orientations".to_string());
+            }
+        }
+    }
+    match face {
+        Some(face) => validation::arrival(
+            "allign_tolerance",
+            Some(face.allign_tolerance),
+            Some(face.deceleration_radius),
+            Some(face.time_to_target),
+        ),
+        None => Ok(()),
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Patrol<T, V> {
+    /// returns the index of the waypoint the owner moves to or waits at
+    pub fn get_current_index(&self) -> usize {
+        self.current
+    }
+
+    /// Tests whether the owner waits at the current waypoint
+    pub fn is_waiting(&self) -> bool {
+        self.dwell_elapsed.is_some()
+    }
+
+    /// Tests whether a patrol in `Once` mode reached and waited at its last
+    /// waypoint, from then on the owner stays there
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Starts the patrol over from the first waypoint
+    pub fn reset(&mut self) {
+        self.current = 0;
+        self.forward = true;
+        self.dwell_elapsed = None;
+        self.finished = false;
+    }
+
+    /// Advances the wait at the current waypoint by `delta_time` seconds and moves
+    /// on once it is over. Returns the index of the waypoint if the owner just
+    /// reached it.
+    pub fn update(&mut self, delta_time: T) -> Option<usize> {
+        if self.finished || self.waypoints.is_empty() {
+            return None;
+        }
+        if let Some(elapsed) = self.dwell_elapsed {
+            let elapsed = elapsed + delta_time;
+            if elapsed >= self.waypoints[self.current].dwell_time {
+                self.advance();
+            } else {
+                self.dwell_elapsed = Some(elapsed);
+            }
+            return None;
+        }
+        let reached = {
+            let behavior = self.behavior.borrow();
+            let owner = behavior.owner.borrow();
+            owner
+                .get_position()
+                .distance(&self.waypoints[self.current].position)
+                <= self.arrive.tolerance
+        };
+        if !reached {
+            return None;
+        }
+        let index = self.current;
+        if self.waypoints[index].dwell_time > T::zero() {
+            self.dwell_elapsed = Some(T::zero());
+        } else {
+            self.advance();
+        }
+        Some(index)
+    }
+
+    /// moves on to the next waypoint of the mode
+    fn advance(&mut self) {
+        self.dwell_elapsed = None;
+        let last = self.waypoints.len() - 1;
+        match self.mode {
+            PatrolMode::Loop => {
+                self.current = if self.current < last { self.current + 1 } else { 0 };
+            }
+            PatrolMode::PingPong => {
+                if last == 0 {
+                    return;
+                }
+                if self.forward && self.current == last {
+                    self.forward = false;
+                } else if !self.forward && self.current == 0 {
+                    self.forward = true;
+                }
+                if self.forward {
+                    self.current += 1;
+                } else {
+                    self.current -= 1;
+                }
+            }
+            PatrolMode::Once => {
+                if self.current < last {
+                    self.current += 1;
+                } else {
+                    self.finished = true;
+                }
+            }
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for Patrol<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V> for Patrol<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        check_arrive(&self.arrive)
+            .and_then(|_| check_waypoints(&self.waypoints, self.face.as_ref()))
+            .map_err(SteeringError::InvalidParameter)
+    }
+
+    fn get_status(&self) -> SteeringStatus {
+        if self.finished {
+            SteeringStatus::PathFinished
+        } else {
+            SteeringStatus::InProgress
+        }
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        let waypoint = match self.waypoints.get(self.current) {
+            Some(waypoint) => waypoint,
+            None => {
+                steering_acceleration.borrow_mut().set_zero();
+                return steering_acceleration;
+            }
+        };
+        let behavior = self.behavior.borrow();
+        let steering_acceleration = arrive(
+            &behavior,
+            steering_acceleration,
+            waypoint.position,
+            self.arrive.tolerance,
+            self.arrive.deceleration_radius,
+            self.arrive.time_to_target,
+        );
+        if !self.is_waiting() && !self.finished {
+            return steering_acceleration;
+        }
+        match (waypoint.orientation, self.face.as_ref()) {
+            (Some(orientation), Some(face)) => {
+                // facing clears the linear part, the owner still holds its position
+                let linear = steering_acceleration.borrow().linear;
+                let steering_acceleration = reach_orientation(
+                    &behavior,
+                    steering_acceleration,
+                    orientation,
+                    face.allign_tolerance,
+                    face.deceleration_radius,
+                    face.time_to_target,
+                );
+                steering_acceleration.borrow_mut().linear = linear;
+                steering_acceleration
+            }
+            _ => steering_acceleration,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Patrol, PatrolBuilder, PatrolMode, PatrolWaypoint};
+    use super::super::test_common::TestSteerable2d;
+    use super::super::super::{ArriveParameters, FaceParameters, SteeringAcceleration,
+                              SteeringAccelerationCalculator, SteeringBehavior, SteeringStatus};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::f32::consts::PI;
+    use std::rc::Rc;
+
+    fn guard_patrol(
+        owner: Rc<RefCell<TestSteerable2d>>,
+        mode: PatrolMode,
+    ) -> Patrol<f32, Vector2<f32>> {
+        let mut guard_post = PatrolWaypoint::new(Vector2::new(10.0, 0.0));
+        guard_post.dwell_time = 1.0;
+        guard_post.orientation = Some(PI / 2.0);
+        PatrolBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: owner,
+            }))
+            .waypoints(vec![
+                PatrolWaypoint::new(Vector2::new(0.0, 0.0)),
+                guard_post,
+                PatrolWaypoint::new(Vector2::new(10.0, 10.0)),
+            ])
+            .mode(mode)
+            .arrive(ArriveParameters {
+                tolerance: 0.5,
+                deceleration_radius: 2.0,
+                time_to_target: 0.1,
+            })
+            .face(Some(FaceParameters {
+                allign_tolerance: 0.01,
+                deceleration_radius: 0.5,
+                time_to_target: 0.1,
+            }))
+            .build()
+            .unwrap()
+    }
+
+    /// moves the owner onto each waypoint it heads to and collects the ones reached
+    fn visits(
+        patrol: &mut Patrol<f32, Vector2<f32>>,
+        owner: &RefCell<TestSteerable2d>,
+    ) -> Vec<usize> {
+        (0..6)
+            .filter_map(|_| {
+                let position = patrol.waypoints[patrol.get_current_index()].position;
+                owner.borrow_mut().set_position(position);
+                let reached = patrol.update(0.0);
+                patrol.update(2.0);
+                reached
+            })
+            .collect()
+    }
+
+    #[test]
+    fn waits_and_faces_at_waypoints() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.2f32, 0.0))));
+        let mut patrol = guard_patrol(owner.clone(), PatrolMode::Loop);
+        assert_eq!(Some(0), patrol.update(0.1));
+        assert_eq!(1, patrol.get_current_index());
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = patrol.calculate_steering(sa);
+        assert!((result.borrow().linear - Vector2::new(10.0, 0.0)).norm() < 1e-4);
+        assert_eq!(0.0, result.borrow().angular);
+
+        owner.borrow_mut().set_position(Vector2::new(9.8, 0.0));
+        assert_eq!(Some(1), patrol.update(0.1));
+        assert!(patrol.is_waiting());
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = patrol.calculate_steering(sa);
+        assert_eq!(Vector2::new(0.0, 0.0), result.borrow().linear);
+        assert!(result.borrow().angular > 0.0);
+
+        assert_eq!(None, patrol.update(0.6));
+        assert_eq!(1, patrol.get_current_index());
+        assert_eq!(None, patrol.update(0.6));
+        assert!(!patrol.is_waiting());
+        assert_eq!(2, patrol.get_current_index());
+    }
+
+    #[test]
+    fn modes_order_the_waypoints() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let mut patrol = guard_patrol(owner.clone(), PatrolMode::Loop);
+        assert_eq!(vec![0, 1, 2, 0, 1, 2], visits(&mut patrol, &owner));
+
+        let mut patrol = guard_patrol(owner.clone(), PatrolMode::PingPong);
+        assert_eq!(vec![0, 1, 2, 1, 0, 1], visits(&mut patrol, &owner));
+
+        let mut patrol = guard_patrol(owner.clone(), PatrolMode::Once);
+        assert_eq!(vec![0, 1, 2], visits(&mut patrol, &owner));
+        assert!(patrol.is_finished());
+        assert_eq!(SteeringStatus::PathFinished, patrol.get_status());
+        patrol.reset();
+        assert_eq!(SteeringStatus::InProgress, patrol.get_status());
+    }
+
+    #[test]
+    fn orientations_need_face_parameters() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let mut waypoint = PatrolWaypoint::new(Vector2::new(1.0f32, 0.0));
+        waypoint.orientation = Some(0.0);
+        let result = PatrolBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: owner,
+            }))
+            .waypoints(vec![waypoint])
+            .arrive(ArriveParameters {
+                tolerance: 0.5,
+                deceleration_radius: 2.0,
+                time_to_target: 0.1,
+            })
+            .build()
+            .map(|_| ());
+        assert_eq!(
+            Err("face must be set to face waypoint orientations".to_string()),
+            result
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use super::super::{HasSteeringBehavior, SteeringAcceleration, SteeringAccelerationCalculator,
+                   SteeringBehavior, SteeringError, SteeringStatus, SteeringVector};
+use nalgebra::Vector3;
+use alga::general::Real;
+use std::cell::Cell;
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+///PrioritySteering behavior goes through a list of behaviors in order and
+///returns the first acceleration whose magnitude exceeds epsilon. Behaviors
+///usually are BlendedSteering groups, e.g. obstacle avoidance first and
+///flocking when there is nothing to avoid. If no behavior produces a large
+///enough acceleration, the acceleration of the last one is returned.
+#[builder(pattern = "immutable")]
+#[derive(Builder)]
+pub struct PrioritySteering<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Behaviors in decreasing priority
+    pub behaviors: Vec<Rc<RefCell<SteeringAccelerationCalculator<T, V>>>>,
+    /// Accelerations with a smaller magnitude are treated as zero
+    #[builder(default = "T::from_f32(0.001).unwrap()")]
+    pub epsilon: T,
+    #[builder(setter(skip), default = "Cell::new(None)")]
+    selected_behavior_index: Cell<Option<usize>>,
+}
+
+impl<T: Real, V: SteeringVector<T>> PrioritySteeringBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> PrioritySteeringBuilder<T, V> {
+        PrioritySteeringBuilder {
+            behavior: None,
+            behaviors: None,
+            epsilon: None,
+            selected_behavior_index: Default::default(),
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> PrioritySteering<T, V> {
+    /// Adds a behavior with a lower priority than the ones already added
+    pub fn add(&mut self, behavior: Rc<RefCell<SteeringAccelerationCalculator<T, V>>>) {
+        self.behaviors.push(behavior);
+    }
+
+    /// returns the index of the behavior selected by the last calculation
+    pub fn get_selected_behavior_index(&self) -> Option<usize> {
+        self.selected_behavior_index.get()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for PrioritySteering<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V>
+    for PrioritySteering<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        for behavior in self.behaviors.iter() {
+            behavior.borrow().validate()?;
+        }
+        Ok(())
+    }
+
+    /// returns the status of the behavior selected by the last calculation
+    fn get_status(&self) -> SteeringStatus {
+        self.selected_behavior_index
+            .get()
+            .and_then(|index| self.behaviors.get(index))
+            .map_or(SteeringStatus::InProgress, |behavior| behavior.borrow().get_status())
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        let epsilon = self.epsilon * self.epsilon;
+        self.selected_behavior_index.set(None);
+        steering_acceleration.borrow_mut().set_zero();
+        for (index, behavior) in self.behaviors.iter().enumerate() {
+            self.selected_behavior_index.set(Some(index));
+            behavior
+                .borrow_mut()
+                .calculate_steering(steering_acceleration.clone());
+            if steering_acceleration.borrow().calculate_square_magnitude() > epsilon {
+                break;
+            }
+        }
+        steering_acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::{Seek, SteeringAcceleration, SteeringAccelerationCalculator,
+                              SteeringBehavior, StaticLocation};
+    use super::super::test_common::TestSteerable2d;
+    use super::PrioritySteeringBuilder;
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn first_non_zero_acceleration_wins() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let behavior = SteeringBehavior {
+            enabled: true,
+            limiter: None,
+            owner: owner.clone(),
+        };
+        let seek = |x: f32, y: f32| {
+            Rc::new(RefCell::new(Seek {
+                behavior: RefCell::new(behavior.clone()),
+                target: Rc::new(RefCell::new(StaticLocation::new(Vector2::new(x, y), 0.0))),
+            }))
+        };
+        let mut priority = PrioritySteeringBuilder::new()
+            .behavior(RefCell::new(behavior.clone()))
+            .behaviors(Vec::new())
+            .build()
+            .unwrap();
+        priority.add(seek(0.0, 0.0));
+        priority.add(seek(0.0, 10.0));
+        priority.add(seek(10.0, 0.0));
+
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let sa = priority.calculate_steering(sa);
+        assert_eq!(Vector2::new(0.0, 1.0), sa.borrow().linear);
+        assert_eq!(Some(1), priority.get_selected_behavior_index());
+    }
+}
@@ -1,8 +1,11 @@
-use nalgebra::{distance_squared, Point3};
-use super::super::{HasSteeringBehavior, SteeringAcceleration, SteeringAccelerationCalculator,
-                   SteeringBehavior};
+use nalgebra::Vector3;
+use super::super::{HasSteeringBehavior, Steerable, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringError, SteeringStatus,
+                   SteeringVector};
 use alga::general::Real;
-use alga::general::AbstractModule;
+use intercept::{intercept, predict};
+use validation;
+use steering_status::target_status;
 use std::cell::RefMut;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -10,43 +13,175 @@ use std::rc::Rc;
 ///Pursue behaviour aims to steer the agent towards target by predicting
 ///where target will be in time t. Steering calculation is based on targets
 ///future position. It is like aiming at a moving target.
-#[builder(pattern = "immutable")]
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
 #[derive(Builder)]
-pub struct Pursue<T>
+pub struct Pursue<T, V = Vector3<T>>
 where
     T: Real,
+    V: SteeringVector<T>,
 {
     /// Common behavior attributes
-    pub behavior: RefCell<SteeringBehavior<T>>,
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Agent to pursue
+    pub target: Rc<RefCell<Steerable<T, V>>>,
 
     pub max_prediction_time: T,
+    /// How the position of the target is predicted
+    #[builder(default = "PursueMode::Predictive")]
+    pub mode: PursueMode,
 }
 
+/// How `Pursue` predicts where the target will be
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum PursueMode {
+    /// extrapolates the velocity of the target for the time the owner needs to
+    /// cover the current distance at its current speed
+    Predictive,
+    /// solves for the point where the owner moving at its max linear speed meets
+    /// the target, taking the acceleration of the target into account. Falls back
+    /// to `Predictive` if the target escapes.
+    Intercept,
+}
+
+impl Default for PursueMode {
+    fn default() -> PursueMode {
+        PursueMode::Predictive
+    }
+}
+
+/// Tunable parameters of `Pursue` which can be loaded from data files. Owner and
+/// target are not part of them, they are supplied when the behavior is created.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PursueParameters<T: Real> {
+    /// Longest time span used to predict where the target will be
+    pub max_prediction_time: T,
+    /// How the position of the target is predicted, `Predictive` if missing
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mode: PursueMode,
+}
+
+impl<T: Real> PursueParameters<T> {
+    /// Checks that parameters make sense
+    pub fn validate(&self) -> Result<(), SteeringError> {
+        validation::non_negative("max_prediction_time", Some(self.max_prediction_time))
+            .map_err(SteeringError::InvalidParameter)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> PursueBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> PursueBuilder<T, V> {
+        PursueBuilder {
+            behavior: None,
+            target: None,
+            max_prediction_time: None,
+            mode: None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validation::non_negative("max_prediction_time", self.max_prediction_time)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Pursue<T, V> {
+    /// Creates the behavior using parameters loaded from a data file
+    pub fn with_parameters(
+        behavior: SteeringBehavior<T, V>,
+        target: Rc<RefCell<Steerable<T, V>>>,
+        parameters: &PursueParameters<T>,
+    ) -> Pursue<T, V> {
+        Pursue {
+            behavior: RefCell::new(behavior),
+            target: target,
+            max_prediction_time: parameters.max_prediction_time,
+            mode: parameters.mode,
+        }
+    }
+
+    /// returns the tunable parameters of the behavior
+    pub fn parameters(&self) -> PursueParameters<T> {
+        PursueParameters {
+            max_prediction_time: self.max_prediction_time,
+            mode: self.mode,
+        }
+    }
+
+    /// Replaces the tunable parameters of the behavior
+    pub fn set_parameters(&mut self, parameters: &PursueParameters<T>) {
+        self.max_prediction_time = parameters.max_prediction_time;
+        self.mode = parameters.mode;
+    }
 
-impl<T: Real> HasSteeringBehavior<T> for Pursue<T> {
-    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T>> {
+    /// returns where the owner moving at its max linear speed meets the target,
+    /// not looking further ahead than `max_prediction_time`. `None` if the target
+    /// escapes.
+    fn intercept_point(&self, behavior: &SteeringBehavior<T, V>) -> Option<V> {
+        let max_linear_speed = match behavior.limiter {
+            Some(ref lim) => lim.borrow().get_max_linear_speed(),
+            None => T::one(),
+        };
+        let target = self.target.borrow();
+        let acceleration = target.get_linear_acceleration();
+        intercept(
+            behavior.owner.borrow().get_position(),
+            max_linear_speed,
+            target.get_position(),
+            target.get_linear_velocity(),
+            Some(&acceleration),
+        ).map(|result| if result.time > self.max_prediction_time {
+            predict(
+                target.get_position(),
+                target.get_linear_velocity(),
+                Some(&acceleration),
+                self.max_prediction_time,
+            )
+        } else {
+            result.point
+        })
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for Pursue<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
         self.behavior.borrow_mut()
     }
 }
 
-impl<T: Real> SteeringAccelerationCalculator<T> for Pursue<T> {
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V> for Pursue<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        self.parameters().validate()
+    }
+
+    fn get_status(&self) -> SteeringStatus {
+        target_status(self.target.borrow().get_position(), || SteeringStatus::InProgress)
+    }
+
     fn calculate_real_steering(
         &self,
-        steering_acceleration: Rc<RefCell<SteeringAcceleration<T>>>,
-    ) -> Rc<RefCell<SteeringAcceleration<T>>> {
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
         let behavior = self.behavior.borrow();
-        let square_distance = distance_squared(
-            &Point3::from_coordinates(
-                *behavior.target.borrow().get_position() -
-                    *behavior.owner.borrow().get_position(),
-            ),
-            &Point3::origin(),
-        );
-        let square_speed =
-            distance_squared(
-                &Point3::from_coordinates(*behavior.owner.borrow().get_linear_velocity()),
-                &Point3::origin(),
-            );
+        if self.mode == PursueMode::Intercept {
+            if let Some(point) = self.intercept_point(&behavior) {
+                let mut sa = steering_acceleration.borrow_mut();
+                sa.linear = (point - *behavior.owner.borrow().get_position())
+                    .normalize_or_zero()
+                    .scale(match behavior.limiter {
+                        Some(ref a) => (*a).borrow().get_max_linear_acceleration(),
+                        None => T::one(),
+                    });
+                sa.angular = T::zero();
+                return steering_acceleration.clone();
+            }
+        }
+
+        let square_distance = (*self.target.borrow().get_position() -
+                                   *behavior.owner.borrow().get_position())
+            .norm_squared();
+        let square_speed = behavior.owner.borrow().get_linear_velocity().norm_squared();
         let mut prediction_time = self.max_prediction_time;
         if square_speed > T::zero() {
             let square_prediction_time = square_distance / square_speed;
@@ -55,18 +190,18 @@ impl<T: Real> SteeringAccelerationCalculator<T> for Pursue<T> {
             }
         }
 
-        steering_acceleration.borrow_mut().linear = *behavior.target.borrow().get_position();
+        steering_acceleration.borrow_mut().linear = *self.target.borrow().get_position();
         steering_acceleration.borrow_mut().mul_add(
             SteeringAcceleration::new(
-                *behavior.target.borrow().get_linear_velocity(),
+                *self.target.borrow().get_linear_velocity(),
                 T::zero(),
             ),
             prediction_time,
         );
         let mut sa = steering_acceleration.borrow_mut();
         sa.linear -= *behavior.owner.borrow().get_position();
-        sa.linear = sa.linear.normalize();
-        sa.linear = sa.linear.multiply_by(match self.behavior.borrow().limiter {
+        sa.linear = sa.linear.normalize_or_zero();
+        sa.linear = sa.linear.scale(match self.behavior.borrow().limiter {
             Some(ref a) => (*a).borrow().get_max_linear_acceleration(),
             None => T::one(),
         });
@@ -74,3 +209,46 @@ impl<T: Real> SteeringAccelerationCalculator<T> for Pursue<T> {
         steering_acceleration.clone()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{PursueBuilder, PursueMode};
+    use super::super::test_common::TestSteerable2d;
+    use super::super::super::{SteeringAcceleration, SteeringAccelerationCalculator,
+                              SteeringBehavior};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn intercept_leads_the_target() {
+        let mut target = TestSteerable2d::new(Vector2::new(4.0f32, -3.0));
+        target.set_linear_velocity(Vector2::new(0.0, 0.75));
+        let target = Rc::new(RefCell::new(target));
+        let steer = |mode: PursueMode, max_prediction_time: f32| {
+            let mut pursue = PursueBuilder::new()
+                .behavior(RefCell::new(SteeringBehavior {
+                    enabled: true,
+                    limiter: None,
+                    owner: Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0, 0.0)))),
+                }))
+                .target(target.clone())
+                .max_prediction_time(max_prediction_time)
+                .mode(mode)
+                .build()
+                .unwrap();
+            let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+            let linear = pursue.calculate_steering(sa).borrow().linear;
+            linear
+        };
+
+        // moving at the unit speed the owner meets the target at (4, 0) after 4
+        let linear = steer(PursueMode::Intercept, 10.0);
+        assert!((linear - Vector2::new(1.0, 0.0)).norm() < 1e-5);
+        let linear = steer(PursueMode::Intercept, 2.0);
+        assert!((linear - Vector2::new(4.0, -1.5).normalize()).norm() < 1e-5);
+        // a resting owner predicts as far as it may
+        let linear = steer(PursueMode::Predictive, 10.0);
+        assert!((linear - Vector2::new(4.0, 4.5).normalize()).norm() < 1e-5);
+    }
+}
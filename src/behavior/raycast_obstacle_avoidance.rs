@@ -0,0 +1,263 @@
+use nalgebra::Vector3;
+use super::super::{HasSteeringBehavior, Ray, RaycastCollisionDetector, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringError,
+                   SteeringVector};
+use alga::general::Real;
+use validation;
+
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Rays cast from the owner along its linear velocity to look for obstacles. Whiskers
+/// and side rays are rotated around the z axis like scalar orientations.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RayConfiguration<T: Real> {
+    /// a single ray of given length
+    Single {
+        /// length of the ray
+        length: T,
+    },
+    /// two parallel rays at both sides of the owner
+    ParallelSide {
+        /// length of the rays
+        length: T,
+        /// distance between the rays and the owner
+        side_offset: T,
+    },
+    /// a central ray and two whiskers turned to both sides
+    CentralWithWhiskers {
+        /// length of the central ray
+        length: T,
+        /// length of the whiskers
+        whisker_length: T,
+        /// angle in radians between the central ray and the whiskers
+        whisker_angle: T,
+    },
+}
+
+impl<T: Real> RayConfiguration<T> {
+    /// returns the rays of an owner at given position moving with given velocity, an
+    /// owner standing still casts no ray
+    pub fn rays<V: SteeringVector<T>>(&self, position: V, velocity: V) -> Vec<Ray<T, V>> {
+        let direction = velocity.normalize_or_zero();
+        if direction.is_zero() {
+            return Vec::new();
+        }
+        let angle = velocity.vector_to_angle();
+        match *self {
+            RayConfiguration::Single { length } => {
+                vec![Ray::new(position, position + direction.scale(length))]
+            }
+            RayConfiguration::ParallelSide {
+                length,
+                side_offset,
+            } => {
+                let side = V::angle_to_vector(angle - T::frac_pi_2()).scale(side_offset);
+                vec![
+                    Ray::new(position + side, position + side + direction.scale(length)),
+                    Ray::new(position - side, position - side + direction.scale(length)),
+                ]
+            }
+            RayConfiguration::CentralWithWhiskers {
+                length,
+                whisker_length,
+                whisker_angle,
+            } => vec![
+                Ray::new(position, position + direction.scale(length)),
+                Ray::new(
+                    position,
+                    position + V::angle_to_vector(angle + whisker_angle).scale(whisker_length),
+                ),
+                Ray::new(
+                    position,
+                    position + V::angle_to_vector(angle - whisker_angle).scale(whisker_length),
+                ),
+            ],
+        }
+    }
+
+    /// Checks that lengths are positive and offsets are not negative
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            RayConfiguration::Single { length } => validation::positive("length", Some(length)),
+            RayConfiguration::ParallelSide {
+                length,
+                side_offset,
+            } => {
+                validation::positive("length", Some(length))?;
+                validation::non_negative("side_offset", Some(side_offset))
+            }
+            RayConfiguration::CentralWithWhiskers {
+                length,
+                whisker_length,
+                whisker_angle,
+            } => {
+                validation::positive("length", Some(length))?;
+                validation::positive("whisker_length", Some(whisker_length))?;
+                validation::non_negative("whisker_angle", Some(whisker_angle))
+            }
+        }
+    }
+}
+
+/// RaycastObstacleAvoidance behavior casts rays along the velocity of the owner and
+/// steers towards a point `distance_from_boundary` away from the closest collision
+/// along the surface normal
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct RaycastObstacleAvoidance<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Finds obstacles along the rays
+    pub detector: Rc<RefCell<RaycastCollisionDetector<T, V>>>,
+    /// Rays to cast
+    pub ray_configuration: RayConfiguration<T>,
+    /// Distance to keep from obstacles
+    pub distance_from_boundary: T,
+}
+
+impl<T: Real, V: SteeringVector<T>> RaycastObstacleAvoidanceBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> RaycastObstacleAvoidanceBuilder<T, V> {
+        RaycastObstacleAvoidanceBuilder {
+            behavior: None,
+            detector: None,
+            ray_configuration: None,
+            distance_from_boundary: None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(ref ray_configuration) = self.ray_configuration {
+            ray_configuration.validate()?;
+        }
+        validation::non_negative("distance_from_boundary", self.distance_from_boundary)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V>
+    for RaycastObstacleAvoidance<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V>
+    for RaycastObstacleAvoidance<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        self.ray_configuration
+            .validate()
+            .and_then(|_| {
+                validation::non_negative(
+                    "distance_from_boundary",
+                    Some(self.distance_from_boundary),
+                )
+            })
+            .map_err(SteeringError::InvalidParameter)
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        let behavior = self.behavior.borrow();
+        let (position, velocity) = {
+            let owner = behavior.owner.borrow();
+            (*owner.get_position(), *owner.get_linear_velocity())
+        };
+        let detector = self.detector.borrow();
+        let mut closest: Option<(T, V)> = None;
+        for ray in self.ray_configuration.rays(position, velocity).iter() {
+            if let Some(collision) = detector.find_collision(ray) {
+                let square_distance = (collision.point - position).norm_squared();
+                if closest.map_or(true, |closest| square_distance < closest.0) {
+                    let target = collision.point +
+                        collision.normal.scale(self.distance_from_boundary);
+                    closest = Some((square_distance, target));
+                }
+            }
+        }
+        steering_acceleration.borrow_mut().linear = match closest {
+            Some((_, target)) => (target - position).normalize_or_zero().scale(
+                match behavior.limiter {
+                    Some(ref a) => (*a).borrow().get_max_linear_acceleration(),
+                    None => T::one(),
+                },
+            ),
+            None => V::zero(),
+        };
+        steering_acceleration.borrow_mut().angular = T::zero();
+        steering_acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RayConfiguration, RaycastObstacleAvoidanceBuilder};
+    use super::super::test_common::TestSteerable2d;
+    use super::super::super::{RaycastCollisionDetector, SteeringAcceleration,
+                              SteeringAccelerationCalculator, SteeringBehavior};
+    use obstacle::{Circle, Obstacle};
+    use spatial::KdTree;
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn steers_around_obstacle() {
+        let mut owner = TestSteerable2d::new(Vector2::new(0.0f32, 0.0));
+        owner.set_linear_velocity(Vector2::new(0.0, 1.0));
+        let obstacles = vec![
+            Rc::new(Circle::new(Vector2::new(0.0f32, 5.0), 1.0)) as
+                Rc<Obstacle<f32, Vector2<f32>>>,
+        ];
+        let detector = Rc::new(RefCell::new(KdTree::new(obstacles))) as
+            Rc<RefCell<RaycastCollisionDetector<f32, Vector2<f32>>>>;
+        let mut avoidance = RaycastObstacleAvoidanceBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: Rc::new(RefCell::new(owner)),
+            }))
+            .detector(detector.clone())
+            .ray_configuration(RayConfiguration::Single { length: 10.0 })
+            .distance_from_boundary(2.0)
+            .build()
+            .unwrap();
+
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = avoidance.calculate_steering(sa.clone());
+        // collision at (0, 4) pushes the target back to (0, 2)
+        assert_eq!(Vector2::new(0.0, 1.0), result.borrow().linear);
+
+        avoidance.ray_configuration = RayConfiguration::Single { length: 3.0 };
+        let result = avoidance.calculate_steering(sa);
+        assert_eq!(Vector2::new(0.0, 0.0), result.borrow().linear);
+
+        assert!(RaycastObstacleAvoidanceBuilder::<f32, Vector2<f32>>::new()
+            .detector(detector)
+            .ray_configuration(RayConfiguration::Single { length: -1.0 })
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn whiskers_are_turned_to_both_sides() {
+        let configuration = RayConfiguration::CentralWithWhiskers {
+            length: 2.0f32,
+            whisker_length: 1.0,
+            whisker_angle: ::std::f32::consts::FRAC_PI_2,
+        };
+        let rays = configuration.rays(Vector2::new(0.0f32, 0.0), Vector2::new(0.0, 3.0));
+        assert_eq!(3, rays.len());
+        assert!((rays[0].end - Vector2::new(0.0, 2.0)).norm() < 1e-6);
+        assert!((rays[1].end - Vector2::new(-1.0, 0.0)).norm() < 1e-6);
+        assert!((rays[2].end - Vector2::new(1.0, 0.0)).norm() < 1e-6);
+    }
+}
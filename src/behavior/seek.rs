@@ -1,8 +1,11 @@
+use super::super::Location;
 use super::super::SteeringBehavior;
 use super::super::HasSteeringBehavior;
-use super::super::{SteeringAcceleration, SteeringAccelerationCalculator};
+use super::super::{SteeringAcceleration, SteeringAccelerationCalculator, SteeringStatus,
+                   SteeringVector};
+use nalgebra::Vector3;
 use alga::general::Real;
-use alga::general::AbstractModule;
+use steering_status::target_status;
 
 use std::cell::RefMut;
 use std::cell::RefCell;
@@ -11,30 +14,47 @@ use std::rc::Rc;
 /// Seek behavior calculates the maximum linear valocity to reach the target location
 #[builder(pattern = "immutable")]
 #[derive(Builder)]
-pub struct Seek<T>
+pub struct Seek<T, V = Vector3<T>>
 where
     T: Real,
+    V: SteeringVector<T>,
 {
     /// common steering behavior attributes
-    pub behavior: RefCell<SteeringBehavior<T>>,
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// location to seek
+    pub target: Rc<RefCell<Location<T, V>>>,
 }
 
-impl<T: Real> HasSteeringBehavior<T> for Seek<T> {
-    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T>> {
+impl<T: Real, V: SteeringVector<T>> SeekBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> SeekBuilder<T, V> {
+        SeekBuilder {
+            behavior: None,
+            target: None,
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for Seek<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
         self.behavior.borrow_mut()
     }
 }
 
-impl<T: Real> SteeringAccelerationCalculator<T> for Seek<T> {
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V> for Seek<T, V> {
+    fn get_status(&self) -> SteeringStatus {
+        target_status(self.target.borrow().get_position(), || SteeringStatus::InProgress)
+    }
+
     fn calculate_real_steering(
         &self,
-        steering_acceleration: Rc<RefCell<SteeringAcceleration<T>>>,
-    ) -> Rc<RefCell<SteeringAcceleration<T>>> {
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
         let behavior = self.behavior.borrow().clone();
-        let position_diff = behavior.target.borrow().get_position() -
+        let position_diff = *self.target.borrow().get_position() -
             *behavior.owner.borrow().get_position();
         steering_acceleration.borrow_mut().linear =
-            position_diff.normalize().multiply_by(match self.behavior
+            position_diff.normalize_or_zero().scale(match self.behavior
                 .borrow()
                 .limiter {
                 Some(ref a) => (*a).borrow().get_max_linear_acceleration(),
@@ -48,11 +68,12 @@ impl<T: Real> SteeringAccelerationCalculator<T> for Seek<T> {
 #[cfg(test)]
 mod test {
     use super::Seek;
-    use super::super::test_common::TestSteerable;
+    use super::super::test_common::{TestSteerable, TestSteerable2d};
     use super::SteeringBehavior;
     use super::SteeringAccelerationCalculator;
     use super::SteeringAcceleration;
-    use nalgebra::Vector3;
+    use super::super::super::StaticLocation;
+    use nalgebra::{Vector2, Vector3};
     use std::cell::RefCell;
     use std::rc::Rc;
 
@@ -65,16 +86,18 @@ mod test {
             behavior: RefCell::new(SteeringBehavior {
                 enabled: true,
                 limiter: None,
-
-                target: Rc::new(RefCell::new(test_target)),
                 owner: Rc::new(RefCell::new(test_owner)),
             }),
+            target: Rc::new(RefCell::new(test_target)),
         };
 
         let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
 
         let acceleration_result = test_behavior.calculate_steering(sa);
-        // assert_eq!(Vector3::new(0.0f32,0.0,0.0), acceleration_result.linear);
+        assert_eq!(
+            Vector3::new(0.0f32, 0.0, 0.0),
+            acceleration_result.borrow().linear
+        );
         assert_eq!(0.0f32, acceleration_result.borrow().angular);
     }
 
@@ -90,9 +113,9 @@ mod test {
             behavior: RefCell::new(SteeringBehavior {
                 enabled: true,
                 limiter: None,
-                target: Rc::new(RefCell::new(test_target)),
                 owner: Rc::new(RefCell::new(test_owner)),
             }),
+            target: Rc::new(RefCell::new(test_target)),
         };
 
         let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
@@ -104,4 +127,55 @@ mod test {
         );
         assert_eq!(0.0f32, acceleration_result.borrow().angular);
     }
+
+    #[test]
+    fn test_static_location() {
+        let mut test_owner = TestSteerable::new();
+        test_owner.set_position(Vector3::new(0.0f32, 0.0, 0.0));
+
+        let mut test_behavior = Seek {
+            behavior: RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: Rc::new(RefCell::new(test_owner)),
+            }),
+            target: Rc::new(RefCell::new(
+                StaticLocation::new(Vector3::new(0.0f32, 2.0, 0.0), 0.0),
+            )),
+        };
+
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+
+        let acceleration_result = test_behavior.calculate_steering(sa);
+        assert_eq!(
+            Vector3::new(0.0f32, 1.0, 0.0),
+            acceleration_result.borrow().linear
+        );
+        assert_eq!(0.0f32, acceleration_result.borrow().angular);
+    }
+
+    #[test]
+    fn test_two_dimensions() {
+        let test_owner = TestSteerable2d::new(Vector2::new(1.0f32, 1.0));
+
+        let mut test_behavior = Seek {
+            behavior: RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: Rc::new(RefCell::new(test_owner)),
+            }),
+            target: Rc::new(RefCell::new(
+                StaticLocation::new(Vector2::new(-2.0f32, 5.0), 0.0),
+            )),
+        };
+
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+
+        let acceleration_result = test_behavior.calculate_steering(sa);
+        assert_eq!(
+            Vector2::new(-0.6f32, 0.8),
+            acceleration_result.borrow().linear
+        );
+        assert_eq!(0.0f32, acceleration_result.borrow().angular);
+    }
 }
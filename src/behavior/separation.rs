@@ -0,0 +1,132 @@
+use nalgebra::Vector3;
+use super::super::{HasSteeringBehavior, Proximity, Steerable, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringError,
+                   SteeringVector};
+use alga::general::Real;
+use validation;
+
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Separation behavior pushes the owner away from its neighbors. The repulsion of a
+/// neighbor is inversely proportional to the square of its distance and is limited
+/// by the maximum linear acceleration.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct Separation<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Neighbors of the owner
+    pub proximity: Rc<RefCell<Proximity<T, V>>>,
+    /// Strength of the repulsion of a neighbor at unit distance
+    #[builder(default = "T::one()")]
+    pub decay_coefficient: T,
+}
+
+impl<T: Real, V: SteeringVector<T>> SeparationBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> SeparationBuilder<T, V> {
+        SeparationBuilder {
+            behavior: None,
+            proximity: None,
+            decay_coefficient: None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validation::non_negative("decay_coefficient", self.decay_coefficient)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for Separation<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V> for Separation<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        validation::non_negative("decay_coefficient", Some(self.decay_coefficient))
+            .map_err(SteeringError::InvalidParameter)
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        let behavior = self.behavior.borrow();
+        let max_acceleration = match behavior.limiter {
+            Some(ref a) => (*a).borrow().get_max_linear_acceleration(),
+            None => T::one(),
+        };
+        let owner = behavior.owner.borrow();
+        let position = *owner.get_position();
+        let mut linear = V::zero();
+        self.proximity.borrow().find_neighbors(
+            &*owner,
+            &mut |neighbor: &Steerable<T, V>| {
+                let to_owner = position - *neighbor.get_position();
+                let square_distance = to_owner.norm_squared();
+                if square_distance.is_zero() {
+                    return true;
+                }
+                let mut strength = self.decay_coefficient / square_distance;
+                if strength > max_acceleration {
+                    strength = max_acceleration;
+                }
+                linear += to_owner.scale(strength / square_distance.sqrt());
+                true
+            },
+        );
+        steering_acceleration.borrow_mut().linear = linear;
+        steering_acceleration.borrow_mut().angular = T::zero();
+        steering_acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SeparationBuilder;
+    use super::super::test_common::TestSteerable2d;
+    use super::super::super::{Proximity, RadiusProximity, Steerable, SteeringAcceleration,
+                              SteeringAccelerationCalculator, SteeringBehavior};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn pushes_away_from_neighbors() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let agents = vec![
+            owner.clone() as Rc<RefCell<Steerable<f32, Vector2<f32>>>>,
+            Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(2.0f32, 0.0)))),
+            Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, -4.0)))),
+            Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(50.0f32, 0.0)))),
+        ];
+        let proximity = RadiusProximity::new(agents, 5.0);
+        let mut separation = SeparationBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: owner,
+            }))
+            .proximity(Rc::new(RefCell::new(proximity)) as
+                Rc<RefCell<Proximity<f32, Vector2<f32>>>>)
+            .build()
+            .unwrap();
+        assert!(SeparationBuilder::<f32, Vector2<f32>>::new()
+            .decay_coefficient(-1.0)
+            .build()
+            .is_err());
+
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = separation.calculate_steering(sa);
+        assert!((result.borrow().linear - Vector2::new(-0.25, 0.0625)).norm() < 1e-6);
+        assert_eq!(0.0f32, result.borrow().angular);
+    }
+}
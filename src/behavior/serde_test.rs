@@ -0,0 +1,84 @@
+//! Round trip tests of the types designers tune in data files, both in JSON and RON.
+use super::super::{Arrive, ArriveParameters, Face3dParameters, FullLimiter, Limiter, PursueMode,
+                   PursueParameters, SteeringAcceleration, SteeringBehavior, StaticLocation};
+use super::super::vehicle::BicycleCar;
+use super::test_common::TestSteerable;
+use nalgebra::{Vector2, Vector3};
+use ron;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::rc::Rc;
+
+fn assert_round_trip<S: Serialize + DeserializeOwned + PartialEq + Debug>(value: S) {
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(value, serde_json::from_str::<S>(&json).unwrap());
+    let ron = ron::to_string(&value).unwrap();
+    assert_eq!(value, ron::from_str::<S>(&ron).unwrap());
+}
+
+#[test]
+fn steering_acceleration_round_trip() {
+    assert_round_trip(SteeringAcceleration::new(Vector3::new(1.0f32, -2.0, 0.5), 0.25));
+    assert_round_trip(SteeringAcceleration::new(Vector2::new(3.0f64, 4.0), -1.0));
+}
+
+#[test]
+fn limiter_round_trip() {
+    let limiter: FullLimiter<f32> = serde_json::from_str(
+        r#"{
+            "max_linear_acceleration": 10.0,
+            "max_linear_speed": 5.0,
+            "max_angular_acceleration": 2.0,
+            "max_angular_speed": 1.0,
+            "zero_linear_speed_threshold": 0.001
+        }"#,
+    ).unwrap();
+    assert_eq!(5.0, limiter.get_max_linear_speed());
+    assert_eq!(2.0, limiter.get_max_angular_acceleration());
+    assert_round_trip(limiter);
+}
+
+#[test]
+fn behavior_parameters_round_trip() {
+    assert_round_trip(PursueParameters {
+        max_prediction_time: 1.5f32,
+        mode: PursueMode::Intercept,
+    });
+    let parameters: PursueParameters<f32> =
+        ron::from_str("(max_prediction_time: 1.0)").unwrap();
+    assert_eq!(PursueMode::Predictive, parameters.mode);
+    assert_round_trip(Face3dParameters {
+        forward: Vector3::y(),
+        allign_tolerance: 0.01f32,
+        deceleration_radius: 0.5,
+        time_to_target: 0.1,
+    });
+    assert_round_trip(BicycleCar {
+        wheelbase: 2.5f32,
+        max_steering_angle: 0.6,
+        max_acceleration: 3.0,
+        max_deceleration: 8.0,
+        max_speed: 30.0,
+    });
+}
+
+#[test]
+fn behavior_from_data_file() {
+    let parameters: ArriveParameters<f32> = ron::from_str(
+        "(tolerance: 0.5, deceleration_radius: 10.0, time_to_target: 0.1)",
+    ).unwrap();
+    let arrive = Arrive::with_parameters(
+        SteeringBehavior {
+            enabled: true,
+            limiter: None,
+            owner: Rc::new(RefCell::new(TestSteerable::new())),
+        },
+        Rc::new(RefCell::new(StaticLocation::new(Vector3::new(1.0f32, 0.0, 0.0), 0.0))),
+        &parameters,
+    );
+    assert_eq!(10.0, arrive.deceleration_radius);
+    assert_eq!(parameters, arrive.parameters());
+}
@@ -0,0 +1,434 @@
+use super::super::{HasSteeringBehavior, Location, Steerable, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringError,
+                   SteeringStatus, SteeringVector};
+use super::face::wrap_angle_around_zero;
+use nalgebra::Vector3;
+use alga::general::Real;
+use validation;
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Condition over owner and target of a state machine which has to hold for a
+/// transition to fire. Guards depending on the target never hold if the state
+/// machine has no target.
+#[derive(Clone)]
+pub enum TransitionGuard<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// target is closer than given distance
+    TargetCloserThan(T),
+    /// target is farther than given distance
+    TargetFartherThan(T),
+    /// target is within given range and at most `half_angle` radians away from
+    /// the direction the owner is looking to
+    TargetInView { range: T, half_angle: T },
+    /// speed of the owner is below given value
+    SpeedBelow(T),
+    /// speed of the owner is above given value
+    SpeedAbove(T),
+    /// holds when given guard does not
+    Not(Box<TransitionGuard<T, V>>),
+    /// condition supplied by the game, e.g. a line of sight test against level
+    /// geometry
+    Custom(Rc<Fn(&Steerable<T, V>, Option<&Location<T, V>>) -> bool>),
+}
+
+impl<T: Real, V: SteeringVector<T>> TransitionGuard<T, V> {
+    /// Tests whether the guard holds for given owner and target
+    pub fn holds(&self, owner: &Steerable<T, V>, target: Option<&Location<T, V>>) -> bool {
+        let distance = || target.map(|target| owner.get_position().distance(target.get_position()));
+        match *self {
+            TransitionGuard::TargetCloserThan(limit) => distance().map_or(false, |d| d < limit),
+            TransitionGuard::TargetFartherThan(limit) => distance().map_or(false, |d| d > limit),
+            TransitionGuard::TargetInView { range, half_angle } => match target {
+                Some(target) => {
+                    let to_target = *target.get_position() - *owner.get_position();
+                    let distance = to_target.norm();
+                    if distance > range {
+                        false
+                    } else if distance.is_zero() {
+                        true
+                    } else {
+                        let angle = wrap_angle_around_zero(
+                            to_target.vector_to_angle() - owner.get_orientation(),
+                        );
+                        Real::abs(angle) <= half_angle
+                    }
+                }
+                None => false,
+            },
+            TransitionGuard::SpeedBelow(limit) => owner.get_linear_velocity().norm() < limit,
+            TransitionGuard::SpeedAbove(limit) => owner.get_linear_velocity().norm() > limit,
+            TransitionGuard::Not(ref guard) => !guard.holds(owner, target),
+            TransitionGuard::Custom(ref condition) => condition(owner, target),
+        }
+    }
+}
+
+/// Named state of a state machine and the behavior steering the owner while the
+/// state is active
+#[derive(Clone)]
+pub struct SteeringState<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// name used by transitions
+    pub name: String,
+    /// behavior of the state
+    pub behavior: Rc<RefCell<SteeringAccelerationCalculator<T, V>>>,
+}
+
+/// Switch from one state to another which happens once all guards hold
+#[derive(Clone)]
+pub struct SteeringTransition<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// name of the state the transition leaves
+    pub from: String,
+    /// name of the state the transition enters
+    pub to: String,
+    /// conditions which all have to hold
+    pub guards: Vec<TransitionGuard<T, V>>,
+}
+
+///SteeringStateMachine runs the behavior of its current state and switches
+///states when the guards of a transition hold, e.g. Wander until the target is
+///in view, Pursue it until it is close, then Arrive and Face it. A switch is not
+///a hard cut, the blended acceleration at the moment of the switch fades out while
+///the one of the new state fades in over `blend_time` seconds, so switching again
+///during a cross-fade does not jump either. Call `update` once per frame
+///to evaluate transitions and advance the cross-fade. The first added state is
+///the initial one.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct SteeringStateMachine<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Location the guards are tested against
+    #[builder(default = "None")]
+    pub target: Option<Rc<RefCell<Location<T, V>>>>,
+    /// Seconds it takes to cross-fade from one state to another
+    pub blend_time: T,
+    #[builder(setter(skip), default = "Vec::new()")]
+    states: Vec<SteeringState<T, V>>,
+    #[builder(setter(skip), default = "Vec::new()")]
+    transitions: Vec<SteeringTransition<T, V>>,
+    #[builder(setter(skip), default = "0")]
+    current: usize,
+    /// states fading out with their weights at the last switch
+    #[builder(setter(skip), default = "Vec::new()")]
+    fading: Vec<(usize, T)>,
+    #[builder(setter(skip), default = "T::zero()")]
+    blend_elapsed: T,
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringStateMachineBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> SteeringStateMachineBuilder<T, V> {
+        SteeringStateMachineBuilder {
+            behavior: None,
+            target: None,
+            blend_time: None,
+            states: Default::default(),
+            transitions: Default::default(),
+            current: Default::default(),
+            fading: Default::default(),
+            blend_elapsed: Default::default(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validation::non_negative("blend_time", self.blend_time)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringStateMachine<T, V> {
+    /// Adds a state, the first one added is the initial state
+    pub fn add_state(
+        &mut self,
+        name: &str,
+        behavior: Rc<RefCell<SteeringAccelerationCalculator<T, V>>>,
+    ) {
+        self.states.push(SteeringState {
+            name: name.to_string(),
+            behavior: behavior,
+        });
+    }
+
+    /// Adds a transition, transitions leaving the same state are tested in the
+    /// order they were added
+    pub fn add_transition(&mut self, from: &str, to: &str, guards: Vec<TransitionGuard<T, V>>) {
+        self.transitions.push(SteeringTransition {
+            from: from.to_string(),
+            to: to.to_string(),
+            guards: guards,
+        });
+    }
+
+    /// returns the name of the current state
+    pub fn get_current_state(&self) -> Option<&str> {
+        self.states.get(self.current).map(|state| &state.name[..])
+    }
+
+    /// returns the weight of the current state in the cross-fade, one once the
+    /// previous states faded out
+    pub fn get_blend_weight(&self) -> T {
+        if self.fading.is_empty() || self.blend_elapsed >= self.blend_time {
+            T::one()
+        } else {
+            self.blend_elapsed / self.blend_time
+        }
+    }
+
+    /// Switches to given state starting a cross-fade, returns false if there is no
+    /// such state. A switch during a cross-fade starts a new one from the current
+    /// blend of states.
+    pub fn set_state(&mut self, name: &str) -> bool {
+        match self.index_of(name) {
+            Some(index) => {
+                if index != self.current {
+                    self.fading = self.get_blend();
+                    self.current = index;
+                    self.blend_elapsed = T::zero();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// returns the states making up the output with their weights, every state
+    /// appears once
+    fn get_blend(&self) -> Vec<(usize, T)> {
+        let weight = self.get_blend_weight();
+        let mut blend = vec![(self.current, weight)];
+        if weight < T::one() {
+            for &(index, fading) in self.fading.iter() {
+                let fading = fading * (T::one() - weight);
+                match blend.iter().position(|&(other, _)| other == index) {
+                    Some(position) => blend[position].1 += fading,
+                    None => blend.push((index, fading)),
+                }
+            }
+        }
+        blend
+    }
+
+    /// Advances the cross-fade by `delta_time` seconds and fires the first
+    /// transition out of the current state whose guards all hold. Returns whether
+    /// the state changed.
+    pub fn update(&mut self, delta_time: T) -> bool {
+        self.blend_elapsed += delta_time;
+        if self.blend_elapsed >= self.blend_time {
+            self.fading.clear();
+        }
+
+        let next = {
+            let current = match self.get_current_state() {
+                Some(current) => current,
+                None => return false,
+            };
+            let behavior = self.behavior.borrow();
+            let owner = behavior.owner.borrow();
+            let target = self.target.as_ref().map(|target| target.borrow());
+            self.transitions
+                .iter()
+                .filter(|transition| transition.from == current)
+                .find(|transition| {
+                    transition
+                        .guards
+                        .iter()
+                        .all(|guard| guard.holds(&*owner, target.as_ref().map(|t| &**t)))
+                })
+                .map(|transition| transition.to.clone())
+        };
+        match next {
+            Some(name) => self.set_state(&name),
+            None => false,
+        }
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.states.iter().position(|state| state.name == name)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for SteeringStateMachine<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V>
+    for SteeringStateMachine<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        for transition in self.transitions.iter() {
+            for name in [&transition.from, &transition.to].iter() {
+                if self.index_of(name).is_none() {
+                    return Err(SteeringError::InvalidParameter(
+                        format!("transition refers to unknown state {}", name),
+                    ));
+                }
+            }
+        }
+        for state in self.states.iter() {
+            state.behavior.borrow().validate()?;
+        }
+        Ok(())
+    }
+
+    /// returns the status of the behavior of the current state
+    fn get_status(&self) -> SteeringStatus {
+        self.states
+            .get(self.current)
+            .map_or(SteeringStatus::InProgress, |state| state.behavior.borrow().get_status())
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        steering_acceleration.borrow_mut().set_zero();
+        for &(index, weight) in self.get_blend().iter() {
+            if let Some(state) = self.states.get(index) {
+                let result = state
+                    .behavior
+                    .borrow_mut()
+                    .calculate_steering(Rc::new(RefCell::new(SteeringAcceleration::default())));
+                let result = result.borrow();
+                steering_acceleration
+                    .borrow_mut()
+                    .mul_add(SteeringAcceleration::new(result.linear, result.angular), weight);
+            }
+        }
+        steering_acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::{Location, Seek, SteeringAcceleration,
+                              SteeringAccelerationCalculator, SteeringBehavior, StaticLocation};
+    use super::super::test_common::TestSteerable2d;
+    use super::{SteeringStateMachine, SteeringStateMachineBuilder, TransitionGuard};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn transitions_cross_fade() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let behavior = SteeringBehavior {
+            enabled: true,
+            limiter: None,
+            owner: owner.clone(),
+        };
+        let seek = |x: f32, y: f32| {
+            Rc::new(RefCell::new(Seek {
+                behavior: RefCell::new(behavior.clone()),
+                target: Rc::new(RefCell::new(StaticLocation::new(Vector2::new(x, y), 0.0))),
+            }))
+        };
+        let target = Rc::new(RefCell::new(StaticLocation::new(Vector2::new(20.0f32, 0.0), 0.0)));
+        let mut machine = SteeringStateMachineBuilder::new()
+            .behavior(RefCell::new(behavior.clone()))
+            .target(Some(target.clone() as Rc<RefCell<Location<f32, Vector2<f32>>>>))
+            .blend_time(1.0)
+            .build()
+            .unwrap();
+        machine.add_state("search", seek(10.0, 0.0));
+        machine.add_state("chase", seek(0.0, 10.0));
+        machine.add_transition(
+            "search",
+            "chase",
+            vec![TransitionGuard::TargetCloserThan(10.0)],
+        );
+        let linear = |machine: &mut SteeringStateMachine<f32, Vector2<f32>>| {
+            let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+            let linear = machine.calculate_steering(sa).borrow().linear;
+            linear
+        };
+
+        assert!(!machine.update(0.1));
+        assert_eq!(Some("search"), machine.get_current_state());
+        target.borrow_mut().position = Vector2::new(5.0, 0.0);
+        assert!(machine.update(0.1));
+        assert_eq!(Some("chase"), machine.get_current_state());
+        assert_eq!(Vector2::new(1.0, 0.0), linear(&mut machine));
+        machine.update(0.5);
+        assert_eq!(Vector2::new(0.5, 0.5), linear(&mut machine));
+        machine.update(0.5);
+        assert_eq!(1.0, machine.get_blend_weight());
+        assert_eq!(Vector2::new(0.0, 1.0), linear(&mut machine));
+        assert!(machine.try_calculate_steering(Rc::new(RefCell::new(
+            SteeringAcceleration::default(),
+        ))).is_ok());
+
+        machine.add_transition("chase", "attack", Vec::new());
+        assert!(machine.validate().is_err());
+    }
+
+    #[test]
+    fn switching_during_a_cross_fade_is_continuous() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let behavior = SteeringBehavior {
+            enabled: true,
+            limiter: None,
+            owner: owner.clone(),
+        };
+        let seek = |x: f32, y: f32| {
+            Rc::new(RefCell::new(Seek {
+                behavior: RefCell::new(behavior.clone()),
+                target: Rc::new(RefCell::new(StaticLocation::new(Vector2::new(x, y), 0.0))),
+            }))
+        };
+        let mut machine = SteeringStateMachineBuilder::new()
+            .behavior(RefCell::new(behavior.clone()))
+            .blend_time(1.0)
+            .build()
+            .unwrap();
+        machine.add_state("a", seek(10.0, 0.0));
+        machine.add_state("b", seek(0.0, 10.0));
+        machine.add_state("c", seek(-10.0, 0.0));
+        let linear = |machine: &mut SteeringStateMachine<f32, Vector2<f32>>| {
+            let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+            let linear = machine.calculate_steering(sa).borrow().linear;
+            linear
+        };
+
+        assert!(machine.set_state("b"));
+        machine.update(0.25);
+        let before = linear(&mut machine);
+        assert!((before - Vector2::new(0.75, 0.25)).norm() < 1e-6);
+        assert!(machine.set_state("c"));
+        assert!((linear(&mut machine) - before).norm() < 1e-6);
+        machine.update(0.5);
+        let expected = Vector2::new(-0.5, 0.0) + before * 0.5;
+        assert!((linear(&mut machine) - expected).norm() < 1e-6);
+
+        // switching back to a fading state does not jump either
+        let before = linear(&mut machine);
+        assert!(machine.set_state("a"));
+        assert!((linear(&mut machine) - before).norm() < 1e-6);
+        machine.update(1.0);
+        assert_eq!(Vector2::new(1.0, 0.0), linear(&mut machine));
+    }
+
+    #[test]
+    fn guards_test_owner_and_target() {
+        let owner = TestSteerable2d::new(Vector2::new(0.0f32, 0.0));
+        let ahead = StaticLocation::new(Vector2::new(0.0f32, 5.0), 0.0);
+        let aside = StaticLocation::new(Vector2::new(5.0f32, 0.0), 0.0);
+        let in_view = TransitionGuard::TargetInView {
+            range: 10.0,
+            half_angle: 0.5,
+        };
+        assert!(in_view.holds(&owner, Some(&ahead as &Location<f32, Vector2<f32>>)));
+        assert!(!in_view.holds(&owner, Some(&aside as &Location<f32, Vector2<f32>>)));
+        assert!(!in_view.holds(&owner, None));
+        let hidden = TransitionGuard::Not(Box::new(in_view));
+        assert!(hidden.holds(&owner, Some(&aside as &Location<f32, Vector2<f32>>)));
+        assert!(TransitionGuard::SpeedBelow(0.5).holds(&owner, None));
+        assert!(!TransitionGuard::SpeedAbove(0.5).holds(&owner, None));
+        let custom = TransitionGuard::Custom(Rc::new(|_: &_, target: Option<&_>| target.is_some()));
+        assert!(custom.holds(&owner, Some(&ahead as &Location<f32, Vector2<f32>>)));
+    }
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
-use super::super::{Steerable, SteeringAcceleration, SteeringAccelerationCalculator};
-use nalgebra::Vector3;
+use super::super::{Location, Steerable, SteeringAcceleration, SteeringAccelerationCalculator};
+use nalgebra::{Vector2, Vector3};
 use alga::general::AbstractModule;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -24,7 +24,9 @@ impl Steerable<f32> for TestSteerable {
     fn get_bounding_radius(&self) -> f32 {
         self.bounding_radius
     }
+}
 
+impl Location<f32> for TestSteerable {
     fn get_position(&self) -> &Vector3<f32> {
         &self.position
     }
@@ -62,3 +64,51 @@ impl TestSteerable {
         self.position = position;
     }
 }
+
+pub struct TestSteerable2d {
+    linear_velocity: Vector2<f32>,
+    position: Vector2<f32>,
+}
+
+impl Steerable<f32, Vector2<f32>> for TestSteerable2d {
+    fn get_linear_velocity(&self) -> &Vector2<f32> {
+        &self.linear_velocity
+    }
+
+    fn get_angular_velocity(&self) -> f32 {
+        0.0f32
+    }
+
+    fn get_bounding_radius(&self) -> f32 {
+        2.0f32
+    }
+}
+
+impl Location<f32, Vector2<f32>> for TestSteerable2d {
+    fn get_position(&self) -> &Vector2<f32> {
+        &self.position
+    }
+
+    fn get_orientation(&self) -> f32 {
+        0.0f32
+    }
+}
+
+impl TestSteerable2d {
+    pub fn new(position: Vector2<f32>) -> Self {
+        TestSteerable2d {
+            linear_velocity: Vector2::new(0.0, 0.0),
+            position: position,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn set_position(&mut self, position: Vector2<f32>) {
+        self.position = position;
+    }
+
+    #[allow(dead_code)]
+    pub fn set_linear_velocity(&mut self, linear_velocity: Vector2<f32>) {
+        self.linear_velocity = linear_velocity;
+    }
+}
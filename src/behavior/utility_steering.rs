@@ -0,0 +1,453 @@
+use super::super::{HasSteeringBehavior, SteeringAcceleration, SteeringAccelerationCalculator,
+                   SteeringBehavior, SteeringError, SteeringStatus, SteeringVector};
+use nalgebra::Vector3;
+use alga::general::Real;
+use validation;
+use std::cell::Cell;
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Named inputs of utility scoring such as distance to target, health or number of
+/// threats. The game updates them before steering is calculated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UtilityContext<T: Real> {
+    values: HashMap<String, T>,
+}
+
+impl<T: Real> UtilityContext<T> {
+    /// Creates a context without inputs
+    pub fn new() -> UtilityContext<T> {
+        UtilityContext {
+            values: HashMap::new(),
+        }
+    }
+
+    /// Sets the value of given input
+    pub fn set(&mut self, name: &str, value: T) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    /// returns the value of given input
+    pub fn get(&self, name: &str) -> Option<T> {
+        self.values.get(name).cloned()
+    }
+}
+
+/// Maps an input normalized to the range from zero to one to a score, results are
+/// clamped to the same range
+#[derive(Clone)]
+pub enum ResponseCurve<T: Real> {
+    /// `slope * x + intercept`
+    Linear { slope: T, intercept: T },
+    /// `slope * (x - shift)^2 + intercept`
+    Quadratic { slope: T, shift: T, intercept: T },
+    /// S shaped curve rising from zero to one around `midpoint`
+    Logistic { steepness: T, midpoint: T },
+    /// curve supplied by the game
+    Custom(Rc<Fn(T) -> T>),
+}
+
+impl<T: Real> ResponseCurve<T> {
+    /// returns the score of given normalized input
+    pub fn evaluate(&self, x: T) -> T {
+        let score = match *self {
+            ResponseCurve::Linear { slope, intercept } => slope * x + intercept,
+            ResponseCurve::Quadratic {
+                slope,
+                shift,
+                intercept,
+            } => slope * (x - shift) * (x - shift) + intercept,
+            ResponseCurve::Logistic {
+                steepness,
+                midpoint,
+            } => T::one() / (T::one() + (-steepness * (x - midpoint)).exp()),
+            ResponseCurve::Custom(ref curve) => curve(x),
+        };
+        score.max(T::zero()).min(T::one())
+    }
+}
+
+/// Scores one input of the context. The input is normalized from the range
+/// `min..max` and passed through the response curve, a missing input scores zero.
+#[derive(Clone)]
+pub struct Consideration<T: Real> {
+    /// name of the context input
+    pub input: String,
+    /// input value mapped to zero
+    pub min: T,
+    /// input value mapped to one
+    pub max: T,
+    /// curve turning the normalized input into a score
+    pub curve: ResponseCurve<T>,
+}
+
+impl<T: Real> Consideration<T> {
+    /// returns the score of the consideration in given context
+    pub fn score(&self, context: &UtilityContext<T>) -> T {
+        match context.get(&self.input) {
+            Some(value) => {
+                let range = self.max - self.min;
+                let x = if range.is_zero() {
+                    T::zero()
+                } else {
+                    ((value - self.min) / range).max(T::zero()).min(T::one())
+                };
+                self.curve.evaluate(x)
+            }
+            None => T::zero(),
+        }
+    }
+}
+
+/// A behavior taking part in utility selection
+#[derive(Clone)]
+pub struct UtilityOption<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// scored behavior
+    pub behavior: Rc<RefCell<SteeringAccelerationCalculator<T, V>>>,
+    /// scale of the utility of the behavior
+    pub weight: T,
+    /// utility is the weight multiplied by the scores of all considerations
+    pub considerations: Vec<Consideration<T>>,
+}
+
+impl<T: Real, V: SteeringVector<T>> UtilityOption<T, V> {
+    /// returns the utility of the behavior in given context
+    pub fn utility(&self, context: &UtilityContext<T>) -> T {
+        self.considerations
+            .iter()
+            .fold(self.weight, |utility, consideration| {
+                utility * consideration.score(context)
+            })
+    }
+}
+
+/// How the utilities of the behaviors turn into steering
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UtilitySelection<T: Real> {
+    /// only the behavior with the highest utility steers
+    Highest,
+    /// accelerations are blended with softmax weights of the utilities, a lower
+    /// temperature favors the highest utilities more
+    Softmax { temperature: T },
+}
+
+///UtilitySteering scores its behaviors every calculation and lets the most useful
+///one steer or blends them by their utility. A behavior with zero utility never
+///steers. To avoid flapping between behaviors with similar utilities the behavior
+///selected by the previous calculation gets `hysteresis` added to its utility.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct UtilitySteering<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Inputs of the considerations
+    pub context: Rc<RefCell<UtilityContext<T>>>,
+    /// How utilities turn into steering
+    #[builder(default = "UtilitySelection::Highest")]
+    pub selection: UtilitySelection<T>,
+    /// Bonus utility of the previously selected behavior
+    #[builder(default = "T::zero()")]
+    pub hysteresis: T,
+    #[builder(setter(skip), default = "Vec::new()")]
+    options: Vec<UtilityOption<T, V>>,
+    #[builder(setter(skip), default = "Cell::new(None)")]
+    selected_behavior_index: Cell<Option<usize>>,
+    #[builder(setter(skip), default = "RefCell::new(Vec::new())")]
+    utilities: RefCell<Vec<T>>,
+}
+
+fn validate_selection<T: Real>(
+    selection: Option<UtilitySelection<T>>,
+    hysteresis: Option<T>,
+) -> Result<(), String> {
+    validation::non_negative("hysteresis", hysteresis)?;
+    match selection {
+        Some(UtilitySelection::Softmax { temperature }) => {
+            validation::positive("temperature", Some(temperature))
+        }
+        _ => Ok(()),
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> UtilitySteeringBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> UtilitySteeringBuilder<T, V> {
+        UtilitySteeringBuilder {
+            behavior: None,
+            context: None,
+            selection: None,
+            hysteresis: None,
+            options: Default::default(),
+            selected_behavior_index: Default::default(),
+            utilities: Default::default(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validate_selection(self.selection, self.hysteresis)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> UtilitySteering<T, V> {
+    /// Adds a behavior whose utility is given weight multiplied by the scores of
+    /// given considerations
+    pub fn add(
+        &mut self,
+        behavior: Rc<RefCell<SteeringAccelerationCalculator<T, V>>>,
+        weight: T,
+        considerations: Vec<Consideration<T>>,
+    ) {
+        self.options.push(UtilityOption {
+            behavior: behavior,
+            weight: weight,
+            considerations: considerations,
+        });
+    }
+
+    /// returns the index of the behavior with the highest utility in the last
+    /// calculation, `None` if no behavior was useful
+    pub fn get_selected_behavior_index(&self) -> Option<usize> {
+        self.selected_behavior_index.get()
+    }
+
+    /// returns the utilities of the last calculation including hysteresis
+    pub fn get_utilities(&self) -> Vec<T> {
+        self.utilities.borrow().clone()
+    }
+
+    fn score(&self) -> Vec<T> {
+        let context = self.context.borrow();
+        let previous = self.selected_behavior_index.get();
+        let mut utilities: Vec<T> = self.options
+            .iter()
+            .map(|option| option.utility(&context))
+            .collect();
+        if let Some(index) = previous {
+            if index < utilities.len() && utilities[index] > T::zero() {
+                utilities[index] += self.hysteresis;
+            }
+        }
+        let mut selected: Option<usize> = None;
+        for (index, utility) in utilities.iter().enumerate() {
+            if *utility > T::zero() && selected.map_or(true, |best| *utility > utilities[best]) {
+                selected = Some(index);
+            }
+        }
+        self.selected_behavior_index.set(selected);
+        utilities
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for UtilitySteering<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V>
+    for UtilitySteering<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        validate_selection(Some(self.selection), Some(self.hysteresis))
+            .map_err(SteeringError::InvalidParameter)?;
+        for option in self.options.iter() {
+            option.behavior.borrow().validate()?;
+        }
+        Ok(())
+    }
+
+    /// returns the status of the behavior with the highest utility
+    fn get_status(&self) -> SteeringStatus {
+        self.selected_behavior_index
+            .get()
+            .and_then(|index| self.options.get(index))
+            .map_or(SteeringStatus::InProgress, |option| {
+                option.behavior.borrow().get_status()
+            })
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        steering_acceleration.borrow_mut().set_zero();
+        let utilities = self.score();
+        let selected = match self.selected_behavior_index.get() {
+            Some(selected) => selected,
+            None => {
+                *self.utilities.borrow_mut() = utilities;
+                return steering_acceleration;
+            }
+        };
+
+        let weights: Vec<T> = match self.selection {
+            UtilitySelection::Highest => utilities
+                .iter()
+                .enumerate()
+                .map(|(index, _)| if index == selected { T::one() } else { T::zero() })
+                .collect(),
+            UtilitySelection::Softmax { temperature } => {
+                let highest = utilities[selected];
+                let exponentials: Vec<T> = utilities
+                    .iter()
+                    .map(|utility| if *utility > T::zero() {
+                        ((*utility - highest) / temperature).exp()
+                    } else {
+                        T::zero()
+                    })
+                    .collect();
+                let sum = exponentials.iter().fold(T::zero(), |sum, e| sum + *e);
+                exponentials.iter().map(|e| *e / sum).collect()
+            }
+        };
+
+        for (option, weight) in self.options.iter().zip(weights.iter()) {
+            if weight.is_zero() {
+                continue;
+            }
+            let result = option
+                .behavior
+                .borrow_mut()
+                .calculate_steering(Rc::new(RefCell::new(SteeringAcceleration::default())));
+            let result = result.borrow();
+            steering_acceleration.borrow_mut().mul_add(
+                SteeringAcceleration::new(result.linear, result.angular),
+                *weight,
+            );
+        }
+        *self.utilities.borrow_mut() = utilities;
+        steering_acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::{Seek, SteeringAcceleration, SteeringAccelerationCalculator,
+                              SteeringBehavior, StaticLocation};
+    use super::super::test_common::TestSteerable2d;
+    use super::{Consideration, ResponseCurve, UtilityContext, UtilitySelection,
+                UtilitySteering, UtilitySteeringBuilder};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn consideration(input: &str, curve: ResponseCurve<f32>) -> Consideration<f32> {
+        Consideration {
+            input: input.to_string(),
+            min: 0.0,
+            max: 10.0,
+            curve: curve,
+        }
+    }
+
+    fn utility_steering(
+        selection: UtilitySelection<f32>,
+        hysteresis: f32,
+    ) -> (UtilitySteering<f32, Vector2<f32>>, Rc<RefCell<UtilityContext<f32>>>) {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let behavior = SteeringBehavior {
+            enabled: true,
+            limiter: None,
+            owner: owner,
+        };
+        let seek = |x: f32, y: f32| {
+            Rc::new(RefCell::new(Seek {
+                behavior: RefCell::new(behavior.clone()),
+                target: Rc::new(RefCell::new(StaticLocation::new(Vector2::new(x, y), 0.0))),
+            }))
+        };
+        let context = Rc::new(RefCell::new(UtilityContext::new()));
+        let mut utility = UtilitySteeringBuilder::new()
+            .behavior(RefCell::new(behavior.clone()))
+            .context(context.clone())
+            .selection(selection)
+            .hysteresis(hysteresis)
+            .build()
+            .unwrap();
+        let rising = ResponseCurve::Linear {
+            slope: 1.0,
+            intercept: 0.0,
+        };
+        let falling = ResponseCurve::Linear {
+            slope: -1.0,
+            intercept: 1.0,
+        };
+        utility.add(seek(10.0, 0.0), 1.0, vec![consideration("threat", rising)]);
+        utility.add(seek(0.0, 10.0), 1.0, vec![consideration("threat", falling)]);
+        (utility, context)
+    }
+
+    fn linear(utility: &mut UtilitySteering<f32, Vector2<f32>>) -> Vector2<f32> {
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let linear = utility.calculate_steering(sa).borrow().linear;
+        linear
+    }
+
+    #[test]
+    fn response_curves() {
+        let quadratic = ResponseCurve::Quadratic {
+            slope: 4.0,
+            shift: 0.5,
+            intercept: 0.0,
+        };
+        assert_eq!(1.0, quadratic.evaluate(0.0f32));
+        assert_eq!(0.0, quadratic.evaluate(0.5));
+        let logistic = ResponseCurve::Logistic {
+            steepness: 10.0,
+            midpoint: 0.5,
+        };
+        assert_eq!(0.5, logistic.evaluate(0.5f32));
+        assert!(logistic.evaluate(1.0) > 0.99);
+        let custom = ResponseCurve::Custom(Rc::new(|x: f32| 2.0 * x));
+        assert_eq!(1.0, custom.evaluate(0.75));
+    }
+
+    #[test]
+    fn highest_utility_steers_with_hysteresis() {
+        let (mut utility, context) = utility_steering(UtilitySelection::Highest, 0.2);
+        assert_eq!(Vector2::new(0.0, 0.0), linear(&mut utility));
+        assert_eq!(None, utility.get_selected_behavior_index());
+
+        context.borrow_mut().set("threat", 8.0);
+        assert_eq!(Vector2::new(1.0, 0.0), linear(&mut utility));
+        assert_eq!(Some(0), utility.get_selected_behavior_index());
+        context.borrow_mut().set("threat", 4.5);
+        assert_eq!(Vector2::new(1.0, 0.0), linear(&mut utility));
+        context.borrow_mut().set("threat", 3.0);
+        assert_eq!(Vector2::new(0.0, 1.0), linear(&mut utility));
+        assert_eq!(Some(1), utility.get_selected_behavior_index());
+    }
+
+    #[test]
+    fn softmax_blends_utilities() {
+        let (mut utility, context) = utility_steering(
+            UtilitySelection::Softmax { temperature: 0.1 },
+            0.0,
+        );
+        context.borrow_mut().set("threat", 5.0);
+        assert_eq!(Vector2::new(0.5, 0.5), linear(&mut utility));
+        context.borrow_mut().set("threat", 6.0);
+        let blended = linear(&mut utility);
+        assert!(blended.x > 0.8 && blended.y > 0.0);
+        assert!((blended.x + blended.y - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn builder_rejects_invalid_parameters() {
+        let (utility, context) = utility_steering(UtilitySelection::Highest, 0.0);
+        assert_eq!(
+            Err("temperature must be a positive number".to_string()),
+            UtilitySteeringBuilder::new()
+                .behavior(RefCell::new(utility.behavior.borrow().clone()))
+                .context(context)
+                .selection(UtilitySelection::Softmax { temperature: 0.0 })
+                .build()
+                .map(|_| ())
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use nalgebra::Vector2;
+use super::super::{HasSteeringBehavior, RayConfiguration, SteeringAcceleration,
+                   SteeringAccelerationCalculator, SteeringBehavior, SteeringError,
+                   SteeringVector};
+use obstacle::{Obstacle, Segment};
+use alga::general::Real;
+
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// WallAvoidance behavior keeps the owner away from wall segments on a plane.
+/// Feelers are projected along the linear velocity, every feeler crossing a wall
+/// pushes the owner along the wall normal in proportion to how far the feeler
+/// reaches beyond the wall.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct WallAvoidance<T>
+where
+    T: Real,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, Vector2<T>>>,
+    /// Walls to avoid
+    pub walls: Vec<Segment<T>>,
+    /// Feelers projected from the owner
+    pub feelers: RayConfiguration<T>,
+}
+
+impl<T: Real> WallAvoidanceBuilder<T> {
+    /// returns a builder with no fields set
+    pub fn new() -> WallAvoidanceBuilder<T> {
+        WallAvoidanceBuilder {
+            behavior: None,
+            walls: None,
+            feelers: None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self.feelers {
+            Some(ref feelers) => feelers.validate(),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T: Real> HasSteeringBehavior<T, Vector2<T>> for WallAvoidance<T> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, Vector2<T>>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real> SteeringAccelerationCalculator<T, Vector2<T>> for WallAvoidance<T> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        self.feelers.validate().map_err(SteeringError::InvalidParameter)
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, Vector2<T>>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, Vector2<T>>>> {
+        let behavior = self.behavior.borrow();
+        let max_acceleration = match behavior.limiter {
+            Some(ref a) => (*a).borrow().get_max_linear_acceleration(),
+            None => T::one(),
+        };
+        let (position, velocity) = {
+            let owner = behavior.owner.borrow();
+            (*owner.get_position(), *owner.get_linear_velocity())
+        };
+        let mut push = Vector2::new(T::zero(), T::zero());
+        for feeler in self.feelers.rays(position, velocity).iter() {
+            // only the wall the feeler crosses first counts
+            let mut closest: Option<(T, Vector2<T>)> = None;
+            for wall in self.walls.iter() {
+                if let Some(collision) = wall.intersect_ray(feeler) {
+                    let distance = SteeringVector::distance(&feeler.start, &collision.point);
+                    if closest.map_or(true, |closest| distance < closest.0) {
+                        closest = Some((distance, collision.normal));
+                    }
+                }
+            }
+            if let Some((distance, normal)) = closest {
+                let length = feeler.get_length();
+                push += normal.scale((length - distance) / length);
+            }
+        }
+        if SteeringVector::norm(&push) > T::one() {
+            push = SteeringVector::normalize(&push);
+        }
+        steering_acceleration.borrow_mut().linear = push.scale(max_acceleration);
+        steering_acceleration.borrow_mut().angular = T::zero();
+        steering_acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::WallAvoidanceBuilder;
+    use super::super::test_common::TestSteerable2d;
+    use super::super::super::{RayConfiguration, SteeringAcceleration,
+                              SteeringAccelerationCalculator, SteeringBehavior};
+    use obstacle::Segment;
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn pushes_away_from_crossed_wall() {
+        let mut owner = TestSteerable2d::new(Vector2::new(0.0f32, 0.0));
+        owner.set_linear_velocity(Vector2::new(1.0, 0.0));
+        let mut avoidance = WallAvoidanceBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: Rc::new(RefCell::new(owner)),
+            }))
+            .walls(vec![
+                Segment::new(Vector2::new(3.0f32, -5.0), Vector2::new(3.0, 5.0)),
+                Segment::new(Vector2::new(3.5f32, -5.0), Vector2::new(3.5, 5.0)),
+                Segment::new(Vector2::new(-1.0f32, -5.0), Vector2::new(-1.0, 5.0)),
+            ])
+            .feelers(RayConfiguration::Single { length: 4.0 })
+            .build()
+            .unwrap();
+        assert!(WallAvoidanceBuilder::<f32>::new()
+            .feelers(RayConfiguration::Single { length: 0.0 })
+            .build()
+            .is_err());
+
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let result = avoidance.calculate_steering(sa);
+        assert!((result.borrow().linear - Vector2::new(-0.25, 0.0)).norm() < 1e-6);
+    }
+}
@@ -0,0 +1,331 @@
+use super::super::{HasSteeringBehavior, SteeringAcceleration, SteeringAccelerationCalculator,
+                   SteeringBehavior, SteeringError, SteeringVector};
+use super::face::reach_orientation;
+use nalgebra::Vector3;
+use alga::general::Real;
+use validation;
+use std::cell::Cell;
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+///Wander behavior produces a random walk. A target moves along a circle placed
+///in front of the owner, every calculation nudges it by a small random angle and
+///the owner accelerates towards it. When facing is enabled the owner turns to the
+///target like Face behavior does and accelerates along its orientation.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct Wander<T, V = Vector3<T>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior<T, V>>,
+    /// Distance of the wander circle center in front of the owner
+    pub wander_offset: T,
+    /// Radius of the wander circle
+    pub wander_radius: T,
+    /// Largest change of the wander orientation in radians per calculation
+    pub wander_rate: T,
+    /// Turn the owner towards the wander target
+    #[builder(default = "true")]
+    pub face_enabled: bool,
+    /// Stop rotating if remaining angle is smaller than this
+    pub allign_tolerance: T,
+    /// Reduce the angular speed if remaining angle is smaller than this
+    pub deceleration_radius: T,
+    /// How quick should we face the wander target
+    pub time_to_target: T,
+    #[builder(setter(skip), default = "Cell::new(T::zero())")]
+    wander_orientation: Cell<T>,
+    #[builder(setter(skip), default = "Cell::new(DEFAULT_SEED)")]
+    random_state: Cell<u64>,
+}
+
+const DEFAULT_SEED: u64 = 0x2545_f491_4f6c_dd1d;
+
+/// Tunable parameters of `Wander` which can be loaded from data files. Owner is
+/// not part of them, it is supplied when the behavior is created.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct WanderParameters<T: Real> {
+    /// Distance of the wander circle center in front of the owner
+    pub wander_offset: T,
+    /// Radius of the wander circle
+    pub wander_radius: T,
+    /// Largest change of the wander orientation in radians per calculation
+    pub wander_rate: T,
+    /// Turn the owner towards the wander target
+    pub face_enabled: bool,
+    /// Stop rotating if remaining angle is smaller than this
+    pub allign_tolerance: T,
+    /// Reduce the angular speed if remaining angle is smaller than this
+    pub deceleration_radius: T,
+    /// How quick should we face the wander target
+    pub time_to_target: T,
+}
+
+impl<T: Real> WanderParameters<T> {
+    /// Checks that parameters make sense
+    pub fn validate(&self) -> Result<(), SteeringError> {
+        validation::wander(
+            Some(self.wander_offset),
+            Some(self.wander_radius),
+            Some(self.wander_rate),
+            Some(self.allign_tolerance),
+            Some(self.deceleration_radius),
+            Some(self.time_to_target),
+        ).map_err(SteeringError::InvalidParameter)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> WanderBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> WanderBuilder<T, V> {
+        WanderBuilder {
+            behavior: None,
+            wander_offset: None,
+            wander_radius: None,
+            wander_rate: None,
+            face_enabled: None,
+            allign_tolerance: None,
+            deceleration_radius: None,
+            time_to_target: None,
+            wander_orientation: Default::default(),
+            random_state: Default::default(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        validation::wander(
+            self.wander_offset,
+            self.wander_radius,
+            self.wander_rate,
+            self.allign_tolerance,
+            self.deceleration_radius,
+            self.time_to_target,
+        )
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Wander<T, V> {
+    /// Creates the behavior using parameters loaded from a data file
+    pub fn with_parameters(
+        behavior: SteeringBehavior<T, V>,
+        parameters: &WanderParameters<T>,
+    ) -> Wander<T, V> {
+        Wander {
+            behavior: RefCell::new(behavior),
+            wander_offset: parameters.wander_offset,
+            wander_radius: parameters.wander_radius,
+            wander_rate: parameters.wander_rate,
+            face_enabled: parameters.face_enabled,
+            allign_tolerance: parameters.allign_tolerance,
+            deceleration_radius: parameters.deceleration_radius,
+            time_to_target: parameters.time_to_target,
+            wander_orientation: Cell::new(T::zero()),
+            random_state: Cell::new(DEFAULT_SEED),
+        }
+    }
+
+    /// returns the tunable parameters of the behavior
+    pub fn parameters(&self) -> WanderParameters<T> {
+        WanderParameters {
+            wander_offset: self.wander_offset,
+            wander_radius: self.wander_radius,
+            wander_rate: self.wander_rate,
+            face_enabled: self.face_enabled,
+            allign_tolerance: self.allign_tolerance,
+            deceleration_radius: self.deceleration_radius,
+            time_to_target: self.time_to_target,
+        }
+    }
+
+    /// Replaces the tunable parameters of the behavior
+    pub fn set_parameters(&mut self, parameters: &WanderParameters<T>) {
+        self.wander_offset = parameters.wander_offset;
+        self.wander_radius = parameters.wander_radius;
+        self.wander_rate = parameters.wander_rate;
+        self.face_enabled = parameters.face_enabled;
+        self.allign_tolerance = parameters.allign_tolerance;
+        self.deceleration_radius = parameters.deceleration_radius;
+        self.time_to_target = parameters.time_to_target;
+    }
+
+    /// returns the orientation of the wander target on the circle relative to
+    /// the orientation of the owner
+    pub fn get_wander_orientation(&self) -> T {
+        self.wander_orientation.get()
+    }
+
+    /// Restarts the random sequence driving the wander target, agents seeded
+    /// differently wander differently
+    pub fn set_seed(&self, seed: u64) {
+        self.random_state.set(if seed == 0 { DEFAULT_SEED } else { seed });
+    }
+
+    /// returns a random number in [-1, 1] which is more likely to be around zero
+    fn random_binomial(&self) -> T {
+        self.random() - self.random()
+    }
+
+    /// returns a random number in [0, 1) using xorshift algorithm
+    fn random(&self) -> T {
+        let mut x = self.random_state.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.random_state.set(x);
+        T::from_f64((x >> 11) as f64 / (1u64 << 53) as f64).unwrap()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> HasSteeringBehavior<T, V> for Wander<T, V> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringAccelerationCalculator<T, V> for Wander<T, V> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        self.parameters().validate()
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
+        let behavior = self.behavior.borrow();
+        self.wander_orientation
+            .set(self.wander_orientation.get() + self.random_binomial() * self.wander_rate);
+
+        let owner_orientation = behavior.owner.borrow().get_orientation();
+        let wander_center = *behavior.owner.borrow().get_position() +
+            V::angle_to_vector(owner_orientation).scale(self.wander_offset);
+        let wander_target = wander_center +
+            V::angle_to_vector(owner_orientation + self.wander_orientation.get())
+                .scale(self.wander_radius);
+
+        let max_linear_acceleration = match behavior.limiter {
+            Some(ref lim) => lim.borrow().get_max_linear_acceleration(),
+            None => T::one(),
+        };
+
+        if self.face_enabled {
+            let to_target = wander_target - *behavior.owner.borrow().get_position();
+            let steering_acceleration = if to_target.is_zero() {
+                steering_acceleration.borrow_mut().set_zero();
+                steering_acceleration
+            } else {
+                reach_orientation(
+                    &behavior,
+                    steering_acceleration,
+                    to_target.vector_to_angle(),
+                    self.allign_tolerance,
+                    self.deceleration_radius,
+                    self.time_to_target,
+                )
+            };
+            steering_acceleration.borrow_mut().linear =
+                V::angle_to_vector(owner_orientation).scale(max_linear_acceleration);
+            steering_acceleration
+        } else {
+            let to_target = wander_target - *behavior.owner.borrow().get_position();
+            steering_acceleration.borrow_mut().linear =
+                to_target.normalize_or_zero().scale(max_linear_acceleration);
+            steering_acceleration.borrow_mut().angular = T::zero();
+            steering_acceleration
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::{SteeringAcceleration, SteeringAccelerationCalculator,
+                              SteeringBehavior, SteeringError};
+    use super::super::test_common::TestSteerable2d;
+    use super::{Wander, WanderBuilder, WanderParameters};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn wander(face_enabled: bool) -> Wander<f32, Vector2<f32>> {
+        WanderBuilder::new()
+            .behavior(RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0, 0.0)))),
+            }))
+            .wander_offset(2.0)
+            .wander_radius(1.0)
+            .wander_rate(0.5)
+            .face_enabled(face_enabled)
+            .allign_tolerance(0.01)
+            .deceleration_radius(0.5)
+            .time_to_target(0.1)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn wander_orientation_changes_within_rate() {
+        let mut wander = wander(false);
+        let mut previous = wander.get_wander_orientation();
+        for _ in 0..100 {
+            let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+            let sa = wander.calculate_steering(sa);
+            let orientation = wander.get_wander_orientation();
+            assert!((orientation - previous).abs() <= 0.5);
+            assert!((sa.borrow().linear.norm() - 1.0).abs() < 1e-5);
+            assert_eq!(0.0, sa.borrow().angular);
+            previous = orientation;
+        }
+    }
+
+    #[test]
+    fn same_seed_wanders_the_same_way() {
+        let mut first = wander(true);
+        let mut second = wander(true);
+        first.set_seed(42);
+        second.set_seed(42);
+        for _ in 0..10 {
+            let first_sa =
+                first.calculate_steering(Rc::new(RefCell::new(SteeringAcceleration::default())));
+            let second_sa =
+                second.calculate_steering(Rc::new(RefCell::new(SteeringAcceleration::default())));
+            assert_eq!(*first_sa.borrow(), *second_sa.borrow());
+        }
+        assert!(first.get_wander_orientation() != 0.0);
+    }
+
+    #[test]
+    fn validate_matches_builder() {
+        assert_eq!(
+            Err("wander_rate must not be a negative number".to_string()),
+            WanderBuilder::<f32, Vector2<f32>>::new()
+                .wander_rate(-0.5)
+                .build()
+                .map(|_| ())
+        );
+        let mut parameters = wander(true).parameters();
+        assert_eq!(Ok(()), parameters.validate());
+        parameters.wander_rate = -0.5;
+        assert_eq!(
+            Err(SteeringError::InvalidParameter(
+                "wander_rate must not be a negative number".to_string()
+            )),
+            parameters.validate()
+        );
+        parameters = WanderParameters {
+            time_to_target: 0.0,
+            ..wander(true).parameters()
+        };
+        assert_eq!(
+            Err(SteeringError::InvalidParameter(
+                "time_to_target must be a positive number".to_string()
+            )),
+            parameters.validate()
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use super::{Status, Task};
+
+/// Runs its children one after the other. Fails as soon as a child fails and
+/// succeeds once every child succeeded. An empty sequence succeeds.
+pub struct Sequence {
+    children: Vec<Box<Task>>,
+    current: usize,
+}
+
+impl Sequence {
+    /// Creates a sequence of given children
+    pub fn new(children: Vec<Box<Task>>) -> Sequence {
+        Sequence {
+            children: children,
+            current: 0,
+        }
+    }
+}
+
+impl Task for Sequence {
+    fn tick(&mut self) -> Status {
+        while self.current < self.children.len() {
+            match self.children[self.current].tick() {
+                Status::Running => return Status::Running,
+                Status::Failure => {
+                    self.reset();
+                    return Status::Failure;
+                }
+                Status::Success => self.current += 1,
+            }
+        }
+        self.reset();
+        Status::Success
+    }
+
+    fn reset(&mut self) {
+        self.current = 0;
+        for child in self.children.iter_mut() {
+            child.reset();
+        }
+    }
+}
+
+/// Tries its children one after the other. Succeeds as soon as a child succeeds
+/// and fails once every child failed. An empty selector fails.
+pub struct Selector {
+    children: Vec<Box<Task>>,
+    current: usize,
+}
+
+impl Selector {
+    /// Creates a selector of given children
+    pub fn new(children: Vec<Box<Task>>) -> Selector {
+        Selector {
+            children: children,
+            current: 0,
+        }
+    }
+}
+
+impl Task for Selector {
+    fn tick(&mut self) -> Status {
+        while self.current < self.children.len() {
+            match self.children[self.current].tick() {
+                Status::Running => return Status::Running,
+                Status::Success => {
+                    self.reset();
+                    return Status::Success;
+                }
+                Status::Failure => self.current += 1,
+            }
+        }
+        self.reset();
+        Status::Failure
+    }
+
+    fn reset(&mut self) {
+        self.current = 0;
+        for child in self.children.iter_mut() {
+            child.reset();
+        }
+    }
+}
+
+/// When a parallel task completes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParallelPolicy {
+    /// succeeds when every child succeeded, fails as soon as one fails
+    Sequence,
+    /// succeeds as soon as one child succeeds, fails when every child failed
+    Selector,
+}
+
+/// Ticks all of its children every frame until the policy decides the outcome.
+/// Children which completed are not ticked again until the parallel task completes.
+/// Every steering task overwrites its whole output, so steering children need
+/// outputs of their own, e.g. to face a target while arriving at it combine the
+/// linear acceleration of `Arrive` with the angular acceleration of `Face`.
+pub struct Parallel {
+    children: Vec<Box<Task>>,
+    policy: ParallelPolicy,
+    statuses: Vec<Status>,
+}
+
+impl Parallel {
+    /// Creates a parallel task of given children
+    pub fn new(policy: ParallelPolicy, children: Vec<Box<Task>>) -> Parallel {
+        let statuses = vec![Status::Running; children.len()];
+        Parallel {
+            children: children,
+            policy: policy,
+            statuses: statuses,
+        }
+    }
+}
+
+impl Task for Parallel {
+    fn tick(&mut self) -> Status {
+        let (decisive, other) = match self.policy {
+            ParallelPolicy::Sequence => (Status::Failure, Status::Success),
+            ParallelPolicy::Selector => (Status::Success, Status::Failure),
+        };
+        for (child, status) in self.children.iter_mut().zip(self.statuses.iter_mut()) {
+            if *status == Status::Running {
+                *status = child.tick();
+            }
+            if *status == decisive {
+                self.reset();
+                return decisive;
+            }
+        }
+        if self.statuses.iter().all(|status| *status == other) {
+            self.reset();
+            other
+        } else {
+            Status::Running
+        }
+    }
+
+    fn reset(&mut self) {
+        for (child, status) in self.children.iter_mut().zip(self.statuses.iter_mut()) {
+            child.reset();
+            *status = Status::Running;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::task::test_common::Scripted;
+    use super::super::{Status, Task};
+    use super::{Parallel, ParallelPolicy, Selector, Sequence};
+
+    #[test]
+    fn sequence_runs_children_in_order() {
+        let first = Scripted::new(vec![Status::Running, Status::Success]);
+        let second = Scripted::new(vec![Status::Success]);
+        let second_ticks = second.ticks.clone();
+        let mut sequence = Sequence::new(vec![Box::new(first), Box::new(second)]);
+        assert_eq!(Status::Running, sequence.tick());
+        assert_eq!(0, second_ticks.get());
+        assert_eq!(Status::Success, sequence.tick());
+        assert_eq!(1, second_ticks.get());
+
+        let mut failing = Sequence::new(vec![
+            Box::new(Scripted::new(vec![Status::Failure])),
+            Box::new(Scripted::new(vec![Status::Success])),
+        ]);
+        assert_eq!(Status::Failure, failing.tick());
+    }
+
+    #[test]
+    fn selector_stops_at_first_success() {
+        let last = Scripted::new(vec![Status::Success]);
+        let last_ticks = last.ticks.clone();
+        let mut selector = Selector::new(vec![
+            Box::new(Scripted::new(vec![Status::Failure])),
+            Box::new(Scripted::new(vec![Status::Running, Status::Success])),
+            Box::new(last),
+        ]);
+        assert_eq!(Status::Running, selector.tick());
+        assert_eq!(Status::Success, selector.tick());
+        assert_eq!(0, last_ticks.get());
+        assert_eq!(Status::Failure, Selector::new(Vec::new()).tick());
+    }
+
+    #[test]
+    fn parallel_follows_policy() {
+        let slow = Scripted::new(vec![Status::Running, Status::Running, Status::Success]);
+        let slow_resets = slow.resets.clone();
+        let mut all = Parallel::new(
+            ParallelPolicy::Sequence,
+            vec![Box::new(Scripted::new(vec![Status::Success])), Box::new(slow)],
+        );
+        assert_eq!(Status::Running, all.tick());
+        assert_eq!(Status::Running, all.tick());
+        assert_eq!(Status::Success, all.tick());
+        assert_eq!(1, slow_resets.get());
+
+        let mut any = Parallel::new(
+            ParallelPolicy::Selector,
+            vec![
+                Box::new(Scripted::new(vec![Status::Running])),
+                Box::new(Scripted::new(vec![Status::Running, Status::Success])),
+            ],
+        );
+        assert_eq!(Status::Running, any.tick());
+        assert_eq!(Status::Success, any.tick());
+    }
+}
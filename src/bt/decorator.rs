@@ -0,0 +1,214 @@
+use super::{Status, Task};
+
+/// Turns success of its child into failure and the other way around
+pub struct Inverter {
+    child: Box<Task>,
+}
+
+impl Inverter {
+    /// Creates a decorator inverting the outcome of given child
+    pub fn new(child: Box<Task>) -> Inverter {
+        Inverter { child: child }
+    }
+}
+
+impl Task for Inverter {
+    fn tick(&mut self) -> Status {
+        match self.child.tick() {
+            Status::Running => Status::Running,
+            Status::Success => Status::Failure,
+            Status::Failure => Status::Success,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.child.reset();
+    }
+}
+
+/// Succeeds whenever its child completes
+pub struct AlwaysSucceed {
+    child: Box<Task>,
+}
+
+impl AlwaysSucceed {
+    /// Creates a decorator ignoring failures of given child
+    pub fn new(child: Box<Task>) -> AlwaysSucceed {
+        AlwaysSucceed { child: child }
+    }
+}
+
+impl Task for AlwaysSucceed {
+    fn tick(&mut self) -> Status {
+        match self.child.tick() {
+            Status::Running => Status::Running,
+            _ => Status::Success,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.child.reset();
+    }
+}
+
+/// Fails whenever its child completes
+pub struct AlwaysFail {
+    child: Box<Task>,
+}
+
+impl AlwaysFail {
+    /// Creates a decorator ignoring successes of given child
+    pub fn new(child: Box<Task>) -> AlwaysFail {
+        AlwaysFail { child: child }
+    }
+}
+
+impl Task for AlwaysFail {
+    fn tick(&mut self) -> Status {
+        match self.child.tick() {
+            Status::Running => Status::Running,
+            _ => Status::Failure,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.child.reset();
+    }
+}
+
+/// Runs its child again every time it succeeds, given number of times or
+/// forever. Fails as soon as the child fails.
+pub struct Repeat {
+    child: Box<Task>,
+    times: Option<usize>,
+    count: usize,
+}
+
+impl Repeat {
+    /// Creates a decorator running given child `times` times, forever if `None`
+    pub fn new(child: Box<Task>, times: Option<usize>) -> Repeat {
+        Repeat {
+            child: child,
+            times: times,
+            count: 0,
+        }
+    }
+}
+
+impl Task for Repeat {
+    fn tick(&mut self) -> Status {
+        match self.child.tick() {
+            Status::Running => Status::Running,
+            Status::Failure => {
+                self.reset();
+                Status::Failure
+            }
+            Status::Success => {
+                self.count += 1;
+                self.child.reset();
+                if self.times.map_or(false, |times| self.count >= times) {
+                    self.count = 0;
+                    Status::Success
+                } else {
+                    Status::Running
+                }
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.count = 0;
+        self.child.reset();
+    }
+}
+
+/// Runs its child again until it succeeds
+pub struct UntilSuccess {
+    child: Box<Task>,
+}
+
+impl UntilSuccess {
+    /// Creates a decorator retrying given child until it succeeds
+    pub fn new(child: Box<Task>) -> UntilSuccess {
+        UntilSuccess { child: child }
+    }
+}
+
+impl Task for UntilSuccess {
+    fn tick(&mut self) -> Status {
+        match self.child.tick() {
+            Status::Success => Status::Success,
+            Status::Failure => {
+                self.child.reset();
+                Status::Running
+            }
+            Status::Running => Status::Running,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.child.reset();
+    }
+}
+
+/// Runs its child again until it fails, then succeeds
+pub struct UntilFail {
+    child: Box<Task>,
+}
+
+impl UntilFail {
+    /// Creates a decorator repeating given child until it fails
+    pub fn new(child: Box<Task>) -> UntilFail {
+        UntilFail { child: child }
+    }
+}
+
+impl Task for UntilFail {
+    fn tick(&mut self) -> Status {
+        match self.child.tick() {
+            Status::Failure => Status::Success,
+            Status::Success => {
+                self.child.reset();
+                Status::Running
+            }
+            Status::Running => Status::Running,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.child.reset();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::task::test_common::Scripted;
+    use super::super::{Status, Task};
+    use super::{AlwaysFail, AlwaysSucceed, Inverter, Repeat, UntilFail, UntilSuccess};
+
+    fn once(status: Status) -> Box<Task> {
+        Box::new(Scripted::new(vec![status]))
+    }
+
+    #[test]
+    fn decorators_change_outcome() {
+        assert_eq!(Status::Failure, Inverter::new(once(Status::Success)).tick());
+        assert_eq!(Status::Running, Inverter::new(once(Status::Running)).tick());
+        assert_eq!(Status::Success, AlwaysSucceed::new(once(Status::Failure)).tick());
+        assert_eq!(Status::Failure, AlwaysFail::new(once(Status::Success)).tick());
+        assert_eq!(Status::Running, UntilSuccess::new(once(Status::Failure)).tick());
+        assert_eq!(Status::Success, UntilFail::new(once(Status::Failure)).tick());
+    }
+
+    #[test]
+    fn repeat_counts_successes() {
+        let child = Scripted::new(vec![Status::Success]);
+        let resets = child.resets.clone();
+        let mut repeat = Repeat::new(Box::new(child), Some(3));
+        assert_eq!(Status::Running, repeat.tick());
+        assert_eq!(Status::Running, repeat.tick());
+        assert_eq!(Status::Success, repeat.tick());
+        assert_eq!(3, resets.get());
+        assert_eq!(Status::Failure, Repeat::new(once(Status::Failure), None).tick());
+    }
+}
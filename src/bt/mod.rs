@@ -0,0 +1,14 @@
+//! Minimal behavior tree engine and leaf tasks which run a steering behavior until
+//! it completes. Every task is ticked once per frame and reports whether it is
+//! still running, succeeded or failed. Steering tasks write the acceleration of
+//! their behavior into an output shared with the agent, the game applies it the
+//! same way it applies any other steering acceleration.
+mod task;
+mod composite;
+mod decorator;
+mod steering_task;
+
+pub use self::task::{Status, Task};
+pub use self::composite::{Parallel, ParallelPolicy, Selector, Sequence};
+pub use self::decorator::{AlwaysFail, AlwaysSucceed, Inverter, Repeat, UntilFail, UntilSuccess};
+pub use self::steering_task::{ArriveUntilWithinTolerance, FaceUntilAligned, FollowPathUntilEnd};
@@ -0,0 +1,249 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {Arrive, Face, FollowPath, SteeringAcceleration, SteeringAccelerationCalculator,
+     SteeringStatus, SteeringVector};
+use super::{Status, Task};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Writes the acceleration of the behavior into the output until its status
+/// reports that it reached its goal. A lost target, invalid parameters or an
+/// acceleration which is not a finite number fail the task, the output is zero
+/// whenever the task completes.
+fn steer<T, V, C>(behavior: &mut C, output: &Rc<RefCell<SteeringAcceleration<T, V>>>) -> Status
+where
+    T: Real,
+    V: SteeringVector<T>,
+    C: SteeringAccelerationCalculator<T, V>,
+{
+    let status = match behavior.get_status() {
+        SteeringStatus::InProgress => match behavior.try_calculate_steering(output.clone()) {
+            Ok(_) => return Status::Running,
+            Err(_) => Status::Failure,
+        },
+        SteeringStatus::TargetLost => Status::Failure,
+        _ => Status::Success,
+    };
+    output.borrow_mut().set_zero();
+    status
+}
+
+/// Runs `Arrive` until the owner is within `tolerance` of the target, fails if
+/// the target is lost
+pub struct ArriveUntilWithinTolerance<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// behavior steering the owner
+    pub arrive: Rc<RefCell<Arrive<T, V>>>,
+    /// acceleration the game applies to the owner
+    pub output: Rc<RefCell<SteeringAcceleration<T, V>>>,
+}
+
+impl<T: Real, V: SteeringVector<T>> ArriveUntilWithinTolerance<T, V> {
+    /// Creates the task writing the acceleration of given behavior to output
+    pub fn new(
+        arrive: Rc<RefCell<Arrive<T, V>>>,
+        output: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> ArriveUntilWithinTolerance<T, V> {
+        ArriveUntilWithinTolerance {
+            arrive: arrive,
+            output: output,
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Task for ArriveUntilWithinTolerance<T, V> {
+    fn tick(&mut self) -> Status {
+        steer(&mut *self.arrive.borrow_mut(), &self.output)
+    }
+}
+
+/// Runs `Face` until the owner looks at the target within `allign_tolerance`,
+/// fails if the target is lost
+pub struct FaceUntilAligned<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// behavior steering the owner
+    pub face: Rc<RefCell<Face<T, V>>>,
+    /// acceleration the game applies to the owner
+    pub output: Rc<RefCell<SteeringAcceleration<T, V>>>,
+}
+
+impl<T: Real, V: SteeringVector<T>> FaceUntilAligned<T, V> {
+    /// Creates the task writing the acceleration of given behavior to output
+    pub fn new(
+        face: Rc<RefCell<Face<T, V>>>,
+        output: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> FaceUntilAligned<T, V> {
+        FaceUntilAligned {
+            face: face,
+            output: output,
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Task for FaceUntilAligned<T, V> {
+    fn tick(&mut self) -> Status {
+        steer(&mut *self.face.borrow_mut(), &self.output)
+    }
+}
+
+/// Runs `FollowPath` until the owner reached the end of the path, never
+/// succeeds on a closed path
+pub struct FollowPathUntilEnd<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// behavior steering the owner
+    pub follow_path: Rc<RefCell<FollowPath<T, V>>>,
+    /// acceleration the game applies to the owner
+    pub output: Rc<RefCell<SteeringAcceleration<T, V>>>,
+}
+
+impl<T: Real, V: SteeringVector<T>> FollowPathUntilEnd<T, V> {
+    /// Creates the task writing the acceleration of given behavior to output
+    pub fn new(
+        follow_path: Rc<RefCell<FollowPath<T, V>>>,
+        output: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> FollowPathUntilEnd<T, V> {
+        FollowPathUntilEnd {
+            follow_path: follow_path,
+            output: output,
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Task for FollowPathUntilEnd<T, V> {
+    fn tick(&mut self) -> Status {
+        steer(&mut *self.follow_path.borrow_mut(), &self.output)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::super::{Arrive, Face, FollowPathBuilder, LinePath, Location, Path,
+                              SteeringAcceleration, SteeringBehavior, StaticLocation};
+    use behavior::test_common::TestSteerable2d;
+    use super::super::{Parallel, ParallelPolicy, Sequence, Status, Task};
+    use super::{ArriveUntilWithinTolerance, FaceUntilAligned, FollowPathUntilEnd};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn behavior(owner: Rc<RefCell<TestSteerable2d>>) -> SteeringBehavior<f32, Vector2<f32>> {
+        SteeringBehavior {
+            enabled: true,
+            limiter: None,
+            owner: owner,
+        }
+    }
+
+    fn location(x: f32, y: f32) -> Rc<RefCell<Location<f32, Vector2<f32>>>> {
+        Rc::new(RefCell::new(StaticLocation::new(Vector2::new(x, y), 0.0)))
+    }
+
+    #[test]
+    fn arrive_then_face() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let output = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let arrive = Rc::new(RefCell::new(Arrive {
+            behavior: RefCell::new(behavior(owner.clone())),
+            target: location(5.0, 0.0),
+            tolerance: 0.5,
+            deceleration_radius: 2.0,
+            time_to_target: 0.1,
+        }));
+        let face = Rc::new(RefCell::new(Face {
+            behavior: RefCell::new(behavior(owner.clone())),
+            target: location(5.0, 5.0),
+            allign_tolerance: 0.01,
+            deceleration_radius: 0.5,
+            time_to_target: 0.1,
+        }));
+        let mut sequence = Sequence::new(vec![
+            Box::new(ArriveUntilWithinTolerance::new(arrive, output.clone())),
+            Box::new(FaceUntilAligned::new(face.clone(), output.clone())),
+        ]);
+
+        assert_eq!(Status::Running, sequence.tick());
+        assert!(output.borrow().angular == 0.0 && output.borrow().linear.x > 0.0);
+        owner.borrow_mut().set_position(Vector2::new(4.8, 0.0));
+        assert_eq!(Status::Running, sequence.tick());
+        assert!(output.borrow().linear.x == 0.0 && output.borrow().angular != 0.0);
+        face.borrow_mut().target = location(4.8, 5.0);
+        assert_eq!(Status::Success, sequence.tick());
+        assert!(output.borrow().is_zero());
+    }
+
+    #[test]
+    fn face_while_arriving() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let linear = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let angular = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let arrive = Rc::new(RefCell::new(Arrive {
+            behavior: RefCell::new(behavior(owner.clone())),
+            target: location(5.0, 0.0),
+            tolerance: 0.5,
+            deceleration_radius: 2.0,
+            time_to_target: 0.1,
+        }));
+        let face = Rc::new(RefCell::new(Face {
+            behavior: RefCell::new(behavior(owner.clone())),
+            target: location(5.0, 5.0),
+            allign_tolerance: 0.01,
+            deceleration_radius: 0.5,
+            time_to_target: 0.1,
+        }));
+        let mut parallel = Parallel::new(
+            ParallelPolicy::Sequence,
+            vec![
+                Box::new(ArriveUntilWithinTolerance::new(arrive, linear.clone())),
+                Box::new(FaceUntilAligned::new(face, angular.clone())),
+            ],
+        );
+
+        assert_eq!(Status::Running, parallel.tick());
+        let combined = SteeringAcceleration::new(linear.borrow().linear, angular.borrow().angular);
+        assert!(combined.linear.x > 0.0);
+        assert!(combined.angular != 0.0);
+    }
+
+    #[test]
+    fn invalid_behavior_or_lost_target_fails() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let output = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let face = Rc::new(RefCell::new(Face {
+            behavior: RefCell::new(behavior(owner.clone())),
+            target: location(5.0, 0.0),
+            allign_tolerance: 0.01,
+            deceleration_radius: 0.5,
+            time_to_target: 0.0,
+        }));
+        assert_eq!(Status::Failure, FaceUntilAligned::new(face, output.clone()).tick());
+
+        let arrive = Rc::new(RefCell::new(Arrive {
+            behavior: RefCell::new(behavior(owner.clone())),
+            target: location(::std::f32::NAN, 0.0),
+            tolerance: 0.5,
+            deceleration_radius: 2.0,
+            time_to_target: 0.1,
+        }));
+        let mut task = ArriveUntilWithinTolerance::new(arrive, output);
+        assert_eq!(Status::Failure, task.tick());
+    }
+
+    #[test]
+    fn follow_path_until_end() {
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0f32, 0.0))));
+        let output = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let path = LinePath::new(vec![Vector2::new(0.0f32, 0.0), Vector2::new(10.0, 0.0)], true)
+            .unwrap();
+        let follow_path = FollowPathBuilder::new()
+            .behavior(RefCell::new(behavior(owner.clone())))
+            .path(Rc::new(RefCell::new(path)) as Rc<RefCell<Path<f32, Vector2<f32>>>>)
+            .path_offset(1.0)
+            .tolerance(0.5)
+            .deceleration_radius(2.0)
+            .time_to_target(0.1)
+            .build()
+            .unwrap();
+        let mut task = FollowPathUntilEnd::new(Rc::new(RefCell::new(follow_path)), output);
+        assert_eq!(Status::Running, task.tick());
+        owner.borrow_mut().set_position(Vector2::new(9.7, 0.1));
+        assert_eq!(Status::Success, task.tick());
+    }
+}
@@ -0,0 +1,61 @@
+/// Result of ticking a task
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// task needs more ticks to complete
+    Running,
+    /// task completed
+    Success,
+    /// task can not complete
+    Failure,
+}
+
+/// Node of a behavior tree
+pub trait Task {
+    /// Runs the task for one frame
+    fn tick(&mut self) -> Status;
+
+    /// Prepares the task to start over, called when its parent abandons it or
+    /// runs it again after it completed
+    fn reset(&mut self) {}
+}
+
+#[cfg(test)]
+pub mod test_common {
+    use super::{Status, Task};
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Task returning given statuses one after the other and then the last one
+    /// forever, counting ticks and resets
+    pub struct Scripted {
+        statuses: Vec<Status>,
+        index: usize,
+        pub ticks: Rc<Cell<usize>>,
+        pub resets: Rc<Cell<usize>>,
+    }
+
+    impl Scripted {
+        pub fn new(statuses: Vec<Status>) -> Scripted {
+            Scripted {
+                statuses: statuses,
+                index: 0,
+                ticks: Rc::new(Cell::new(0)),
+                resets: Rc::new(Cell::new(0)),
+            }
+        }
+    }
+
+    impl Task for Scripted {
+        fn tick(&mut self) -> Status {
+            self.ticks.set(self.ticks.get() + 1);
+            let status = self.statuses[self.index.min(self.statuses.len() - 1)];
+            self.index += 1;
+            status
+        }
+
+        fn reset(&mut self) {
+            self.resets.set(self.resets.get() + 1);
+            self.index = 0;
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use std::error::Error;
+use std::fmt;
+
+/// Reasons a steering setup can not be loaded or built
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigErrorKind {
+    /// File is not well formed or does not describe a steering setup
+    Syntax(String),
+    /// No agent or location is known by given name
+    UnknownTarget(String),
+    /// A behavior parameter has a value which makes no sense
+    InvalidParameter(String),
+    /// File can not be read
+    Io(String),
+    /// No profile is registered by given name
+    UnknownProfile(String),
+    /// Change can not be applied to running behaviors without rebuilding them, such
+    /// as adding a behavior or changing a target
+    Incompatible(String),
+}
+
+/// Error of a steering setup pointing at the offending entry of the file, such as
+/// `groups[1].behaviors[0].behavior.Arrive.target`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    /// path of the offending entry
+    pub path: String,
+    /// what is wrong with it
+    pub kind: ConfigErrorKind,
+}
+
+impl ConfigError {
+    /// Creates an error of the entry at given path
+    pub fn new<P: Into<String>>(path: P, kind: ConfigErrorKind) -> ConfigError {
+        ConfigError {
+            path: path.into(),
+            kind: kind,
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ConfigErrorKind::Syntax(ref reason) => write!(f, "{}: {}", self.path, reason),
+            ConfigErrorKind::UnknownTarget(ref name) => {
+                write!(f, "{}: unknown target \"{}\"", self.path, name)
+            }
+            ConfigErrorKind::InvalidParameter(ref reason) => {
+                write!(f, "{}: invalid behavior parameter: {}", self.path, reason)
+            }
+            ConfigErrorKind::Io(ref reason) => write!(f, "{}: {}", self.path, reason),
+            ConfigErrorKind::UnknownProfile(ref name) => {
+                write!(f, "{}: unknown steering profile \"{}\"", self.path, name)
+            }
+            ConfigErrorKind::Incompatible(ref reason) => write!(
+                f,
+                "{}: change can not be applied to running behaviors: {}",
+                self.path,
+                reason
+            ),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn description(&self) -> &str {
+        match self.kind {
+            ConfigErrorKind::Syntax(_) => "malformed steering configuration",
+            ConfigErrorKind::UnknownTarget(_) => "unknown steering target",
+            ConfigErrorKind::InvalidParameter(_) => "invalid behavior parameter",
+            ConfigErrorKind::Io(_) => "steering configuration can not be read",
+            ConfigErrorKind::UnknownProfile(_) => "unknown steering profile",
+            ConfigErrorKind::Incompatible(_) => "incompatible steering configuration change",
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Steering setups described in data files. A setup is a priority list of groups,
+//! every group blends the accelerations of its behaviors. Behaviors refer to their
+//! targets by name, names are resolved to agents of the game when the setup is built.
+//!
+//! For example an agent limited to a speed of 5 could arrive at the player in its first
+//! group and wander in its second one, which takes over once the player is reached and
+//! arriving produces no acceleration. In RON:
+//!
+//! ```text
+//! (
+//!     limiter: Some((
+//!         max_linear_acceleration: 10.0,
+//!         max_linear_speed: 5.0,
+//!         max_angular_acceleration: 3.0,
+//!         max_angular_speed: 2.0,
+//!         zero_linear_speed_threshold: 0.001,
+//!     )),
+//!     groups: [
+//!         (behaviors: [(weight: 1.0, behavior: Arrive(target: "player",
+//!             parameters: (tolerance: 0.5, deceleration_radius: 4.0, time_to_target: 0.1)))]),
+//!         (behaviors: [(weight: 1.0, behavior: Wander(parameters: (wander_offset: 2.0,
+//!             wander_radius: 1.0, wander_rate: 0.3, face_enabled: false,
+//!             allign_tolerance: 0.01, deceleration_radius: 0.5, time_to_target: 0.1)))]),
+//!     ],
+//! )
+//! ```
+//!
+//! and the same setup in TOML:
+//!
+//! ```text
+//! [limiter]
+//! max_linear_acceleration = 10.0
+//! max_linear_speed = 5.0
+//! max_angular_acceleration = 3.0
+//! max_angular_speed = 2.0
+//! zero_linear_speed_threshold = 0.001
+//!
+//! [[groups]]
+//! [[groups.behaviors]]
+//! weight = 1.0
+//! [groups.behaviors.behavior.Arrive]
+//! target = "player"
+//! parameters = { tolerance = 0.5, deceleration_radius = 4.0, time_to_target = 0.1 }
+//!
+//! [[groups]]
+//! [[groups.behaviors]]
+//! weight = 1.0
+//! [groups.behaviors.behavior.Wander.parameters]
+//! wander_offset = 2.0
+//! wander_radius = 1.0
+//! wander_rate = 0.3
+//! face_enabled = false
+//! allign_tolerance = 0.01
+//! deceleration_radius = 0.5
+//! time_to_target = 0.1
+//! ```
+mod config_error;
+mod target_resolver;
+mod steering_config;
+mod steering_profile;
+mod profile_registry;
+
+pub use self::config_error::{ConfigError, ConfigErrorKind};
+pub use self::target_resolver::{TargetMap, TargetResolver};
+pub use self::steering_config::{BehaviorConfig, GroupConfig, SteeringConfig,
+                                WeightedBehaviorConfig};
+pub use self::steering_profile::{ConfiguredBehavior, SteeringProfile};
+pub use self::profile_registry::{PollReport, ReloadError, SteeringProfileRegistry};
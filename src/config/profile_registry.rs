@@ -0,0 +1,283 @@
+use alga::general::Real;
+use nalgebra::Vector3;
+use serde::de::DeserializeOwned;
+use {Steerable, SteeringVector};
+use super::{ConfigError, ConfigErrorKind, SteeringConfig, SteeringProfile, TargetResolver};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+use std::time::SystemTime;
+
+/// Error of a profile which could not be reloaded, running behaviors keep their
+/// previous parameters
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReloadError {
+    /// name of the profile
+    pub profile: String,
+    /// file of the profile
+    pub file: PathBuf,
+    /// what is wrong with the file
+    pub error: ConfigError,
+}
+
+impl fmt::Display for ReloadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "can not reload steering profile {} from {}: {}",
+            self.profile,
+            self.file.display(),
+            self.error
+        )
+    }
+}
+
+impl Error for ReloadError {
+    fn description(&self) -> &str {
+        "steering profile can not be reloaded"
+    }
+}
+
+/// Outcome of polling the files of the registry
+#[derive(Debug, Clone, PartialEq)]
+pub struct PollReport {
+    /// names of profiles which were reloaded and applied to running behaviors
+    pub reloaded: Vec<String>,
+    /// profiles which changed on disk but could not be reloaded
+    pub errors: Vec<ReloadError>,
+}
+
+struct ProfileFile<T: Real, V: SteeringVector<T>> {
+    path: PathBuf,
+    stamp: Option<(SystemTime, u64)>,
+    config: SteeringConfig<T>,
+    instances: Vec<Weak<RefCell<SteeringProfile<T, V>>>>,
+}
+
+/// Steering profiles loaded from files which are watched for changes. Agents get
+/// their steering from the registry and every time a profile file changes the new
+/// parameters are applied to the behaviors of all agents using the profile. Files
+/// are polled, call `poll` regularly e.g. once per second.
+pub struct SteeringProfileRegistry<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    profiles: HashMap<String, ProfileFile<T, V>>,
+}
+
+impl<T: Real + DeserializeOwned, V: SteeringVector<T>> SteeringProfileRegistry<T, V> {
+    /// Creates an empty registry
+    pub fn new() -> SteeringProfileRegistry<T, V> {
+        SteeringProfileRegistry {
+            profiles: HashMap::new(),
+        }
+    }
+
+    /// Loads the profile file and registers it by given name
+    pub fn register<P: AsRef<Path>>(&mut self, name: &str, path: P) -> Result<(), ConfigError> {
+        let path = path.as_ref().to_path_buf();
+        let stamp = stamp(&path);
+        let config = SteeringConfig::from_file(&path)?;
+        config.validate()?;
+        self.profiles.insert(
+            name.to_string(),
+            ProfileFile {
+                path: path,
+                stamp: stamp,
+                config: config,
+                instances: Vec::new(),
+            },
+        );
+        Ok(())
+    }
+
+    /// returns the configuration of given profile
+    pub fn get_config(&self, name: &str) -> Option<&SteeringConfig<T>> {
+        self.profiles.get(name).map(|profile| &profile.config)
+    }
+
+    /// Creates the behaviors of given profile steering given owner. They are updated
+    /// whenever the profile file changes as long as they are alive.
+    pub fn instantiate<R: TargetResolver<T, V>>(
+        &mut self,
+        name: &str,
+        owner: Rc<RefCell<Steerable<T, V>>>,
+        resolver: &R,
+    ) -> Result<Rc<RefCell<SteeringProfile<T, V>>>, ConfigError> {
+        let profile = self.profiles.get_mut(name).ok_or_else(|| {
+            ConfigError::new(".", ConfigErrorKind::UnknownProfile(name.to_string()))
+        })?;
+        let instance = Rc::new(RefCell::new(
+            SteeringProfile::new(&profile.config, owner, resolver)?,
+        ));
+        profile.instances.push(Rc::downgrade(&instance));
+        Ok(instance)
+    }
+
+    /// Reloads profiles whose files changed since they were loaded and applies them
+    /// to running behaviors. A profile which can not be read, is not valid or would
+    /// need rebuilding the behaviors is reported and its behaviors keep running with
+    /// the previous parameters.
+    pub fn poll(&mut self) -> PollReport {
+        let mut report = PollReport {
+            reloaded: Vec::new(),
+            errors: Vec::new(),
+        };
+        for (name, profile) in self.profiles.iter_mut() {
+            let stamp = stamp(&profile.path);
+            if stamp == profile.stamp {
+                continue;
+            }
+            profile.stamp = stamp;
+            profile
+                .instances
+                .retain(|instance| instance.upgrade().is_some());
+            match reload(profile) {
+                Ok(()) => report.reloaded.push(name.clone()),
+                Err(error) => report.errors.push(ReloadError {
+                    profile: name.clone(),
+                    file: profile.path.clone(),
+                    error: error,
+                }),
+            }
+        }
+        report
+    }
+}
+
+/// returns modification time and length of the file, a change of either one
+/// means the file has to be reloaded
+fn stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified().map(|modified| (modified, metadata.len())))
+        .ok()
+}
+
+fn reload<T: Real + DeserializeOwned, V: SteeringVector<T>>(
+    profile: &mut ProfileFile<T, V>,
+) -> Result<(), ConfigError> {
+    let config = SteeringConfig::from_file(&profile.path)?;
+    config.validate()?;
+    let instances: Vec<_> = profile
+        .instances
+        .iter()
+        .filter_map(|instance| instance.upgrade())
+        .collect();
+    // check all instances before touching any, so they never end up with different
+    // parameters
+    for instance in instances.iter() {
+        instance.borrow().check_compatible(&config)?;
+    }
+    for instance in instances.iter() {
+        instance.borrow_mut().reconfigure(&config)?;
+    }
+    profile.config = config;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{ConfigErrorKind, ConfiguredBehavior, TargetMap};
+    use super::SteeringProfileRegistry;
+    use {SteeringAcceleration, SteeringAccelerationCalculator, StaticLocation};
+    use behavior::test_common::TestSteerable2d;
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::rc::Rc;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn profile(wander_rate: f32, tolerance: f32, target: &str) -> String {
+        format!(
+            r#"(groups: [(behaviors: [
+                (weight: 0.5, behavior: Arrive(target: "{}", parameters: (
+                    tolerance: {}, deceleration_radius: 4.0, time_to_target: 0.1))),
+                (behavior: Wander(parameters: (wander_offset: 2.0, wander_radius: 1.0,
+                    wander_rate: {}, face_enabled: false, allign_tolerance: 0.01,
+                    deceleration_radius: 0.5, time_to_target: 0.1))),
+            ])])"#,
+            target,
+            tolerance,
+            wander_rate
+        )
+    }
+
+    fn file(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("steering-{}-{}.ron", name, ::std::process::id()))
+    }
+
+    /// writes the profile with a modification time of its own, writes within the
+    /// resolution of the file system clock would look unchanged otherwise
+    fn write(path: &PathBuf, contents: String, version: u64) {
+        fs::write(path, contents).unwrap();
+        fs::OpenOptions::new()
+            .write(true)
+            .open(path)
+            .and_then(|file| file.set_modified(UNIX_EPOCH + Duration::from_secs(version)))
+            .unwrap();
+    }
+
+    #[test]
+    fn running_behaviors_get_new_parameters() {
+        let path = file("reload");
+        write(&path, profile(0.3, 0.5, "home"), 1);
+
+        let mut targets = TargetMap::new();
+        targets.add_location(
+            "home",
+            Rc::new(RefCell::new(StaticLocation::new(Vector2::new(10.0f32, 0.0), 0.0))),
+        );
+        let mut registry = SteeringProfileRegistry::new();
+        registry.register("guard", &path).unwrap();
+        let owner = Rc::new(RefCell::new(TestSteerable2d::new(Vector2::new(0.0, 0.0))));
+        let guard = registry.instantiate("guard", owner, &targets).unwrap();
+
+        let steering = guard.borrow().get_steering();
+        for _ in 0..5 {
+            let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+            steering.borrow_mut().calculate_steering(sa);
+        }
+        let wander = match guard.borrow().get_behavior(0, 1) {
+            Some(&ConfiguredBehavior::Wander(ref wander)) => wander.clone(),
+            _ => panic!("second behavior is not wander"),
+        };
+        let orientation = wander.borrow().get_wander_orientation();
+        assert!(orientation != 0.0);
+        assert!(registry.poll().reloaded.is_empty());
+
+        write(&path, profile(0.25, 0.75, "home"), 2);
+        let report = registry.poll();
+        assert_eq!(vec!["guard".to_string()], report.reloaded);
+        assert_eq!(0.25, wander.borrow().wander_rate);
+        assert_eq!(orientation, wander.borrow().get_wander_orientation());
+        match guard.borrow().get_behavior(0, 0) {
+            Some(&ConfiguredBehavior::Arrive(ref arrive)) => {
+                assert_eq!(0.75, arrive.borrow().tolerance)
+            }
+            _ => panic!("first behavior is not arrive"),
+        }
+
+        write(&path, profile(-1.0, 0.75, "home"), 3);
+        let report = registry.poll();
+        assert_eq!(1, report.errors.len());
+        assert_eq!(
+            "groups[0].behaviors[1].behavior.Wander.parameters",
+            report.errors[0].error.path
+        );
+        assert_eq!(0.25, wander.borrow().wander_rate);
+
+        write(&path, profile(0.25, 0.75, "player"), 4);
+        let report = registry.poll();
+        match report.errors[0].error.kind {
+            ConfigErrorKind::Incompatible(_) => {}
+            ref kind => panic!("unexpected error {:?}", kind),
+        }
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        assert!(steering.borrow_mut().try_calculate_steering(sa).is_ok());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,425 @@
+use alga::general::Real;
+use ron;
+use serde::Deserializer;
+use serde::de::DeserializeOwned;
+use serde_json;
+use serde_path_to_error;
+use toml;
+use {ArriveParameters, EvadeParameters, FaceParameters, FullLimiter, PrioritySteering,
+     PursueParameters, Steerable, SteeringError, SteeringVector, WanderParameters};
+use super::{ConfigError, ConfigErrorKind, TargetResolver};
+use super::steering_profile::instantiate;
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::rc::Rc;
+
+/// Steering setup of an agent, a priority list of behavior groups
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SteeringConfig<T: Real> {
+    /// limits shared by every behavior, accelerations are not limited if missing
+    pub limiter: Option<FullLimiter<T>>,
+    /// accelerations of a group smaller than this let the next group steer
+    pub epsilon: Option<T>,
+    /// groups in decreasing priority
+    pub groups: Vec<GroupConfig<T>>,
+}
+
+/// Behaviors whose accelerations are blended
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupConfig<T: Real> {
+    /// name of the group to make the file easier to read
+    pub name: Option<String>,
+    /// blended behaviors
+    pub behaviors: Vec<WeightedBehaviorConfig<T>>,
+}
+
+/// A behavior of a group with the weight of its acceleration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeightedBehaviorConfig<T: Real> {
+    /// scale of the acceleration, one if missing
+    #[serde(default = "one")]
+    pub weight: T,
+    /// blended behavior
+    pub behavior: BehaviorConfig<T>,
+}
+
+/// Behaviors which can be described in a file. Targets are names given to the
+/// `TargetResolver` when the setup is built.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum BehaviorConfig<T: Real> {
+    Seek { target: String },
+    Flee { target: String },
+    Arrive {
+        target: String,
+        parameters: ArriveParameters<T>,
+    },
+    Face {
+        target: String,
+        parameters: FaceParameters<T>,
+    },
+    Pursue {
+        target: String,
+        parameters: PursueParameters<T>,
+    },
+    Evade {
+        target: String,
+        parameters: EvadeParameters<T>,
+    },
+    Wander { parameters: WanderParameters<T> },
+}
+
+fn one<T: Real>() -> T {
+    T::one()
+}
+
+impl<T: Real + DeserializeOwned> SteeringConfig<T> {
+    /// Reads a setup written in RON
+    pub fn from_ron(text: &str) -> Result<SteeringConfig<T>, ConfigError> {
+        let mut deserializer = ron::Deserializer::from_str(text)
+            .map_err(|e| ConfigError::new(".", ConfigErrorKind::Syntax(e.to_string())))?;
+        let config = parse(&mut deserializer)?;
+        deserializer
+            .end()
+            .map_err(|e| ConfigError::new(".", ConfigErrorKind::Syntax(e.to_string())))?;
+        Ok(config)
+    }
+
+    /// Reads a setup written in JSON
+    pub fn from_json(text: &str) -> Result<SteeringConfig<T>, ConfigError> {
+        let mut deserializer = serde_json::Deserializer::from_str(text);
+        let config = parse(&mut deserializer)?;
+        deserializer
+            .end()
+            .map_err(|e| ConfigError::new(".", ConfigErrorKind::Syntax(e.to_string())))?;
+        Ok(config)
+    }
+
+    /// Reads a setup written in TOML
+    pub fn from_toml(text: &str) -> Result<SteeringConfig<T>, ConfigError> {
+        parse(toml::Deserializer::new(text))
+    }
+}
+
+fn parse<'de, T, D>(deserializer: D) -> Result<SteeringConfig<T>, ConfigError>
+where
+    T: Real + DeserializeOwned,
+    D: Deserializer<'de>,
+{
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        ConfigError::new(
+            e.path().to_string(),
+            ConfigErrorKind::Syntax(e.inner().to_string()),
+        )
+    })
+}
+
+impl<T: Real> SteeringConfig<T> {
+    /// Reads a setup from a file, format is chosen by the extension of the file
+    /// which is one of `ron`, `json` or `toml`
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<SteeringConfig<T>, ConfigError>
+    where
+        T: DeserializeOwned,
+    {
+        let path = path.as_ref();
+        let mut text = String::new();
+        File::open(path)
+            .and_then(|mut file| file.read_to_string(&mut text))
+            .map_err(|e| ConfigError::new(".", ConfigErrorKind::Io(e.to_string())))?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("ron") => SteeringConfig::from_ron(&text),
+            Some("json") => SteeringConfig::from_json(&text),
+            Some("toml") => SteeringConfig::from_toml(&text),
+            _ => Err(ConfigError::new(
+                ".",
+                ConfigErrorKind::Io(format!("unsupported file format {}", path.display())),
+            )),
+        }
+    }
+
+    /// Creates the runtime behaviors of the setup steering given owner. Targets
+    /// are looked up by the resolver, parameters are validated.
+    pub fn build<V: SteeringVector<T>, R: TargetResolver<T, V>>(
+        &self,
+        owner: Rc<RefCell<Steerable<T, V>>>,
+        resolver: &R,
+    ) -> Result<PrioritySteering<T, V>, ConfigError> {
+        instantiate(self, owner, resolver).map(|instance| instance.steering)
+    }
+
+    /// returns the epsilon of the priority steering, 0.001 if missing
+    pub fn get_epsilon(&self) -> T {
+        self.epsilon.unwrap_or_else(|| T::from_f32(0.001).unwrap())
+    }
+
+    /// Checks parameters of every behavior of the setup
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        for (group_index, group) in self.groups.iter().enumerate() {
+            for (index, weighted) in group.behaviors.iter().enumerate() {
+                weighted
+                    .behavior
+                    .validate(&behavior_path(group_index, index))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// returns the path of a behavior entry in the file
+pub fn behavior_path(group: usize, index: usize) -> String {
+    format!("groups[{}].behaviors[{}].behavior", group, index)
+}
+
+impl<T: Real> BehaviorConfig<T> {
+    /// returns the name of the behavior as it appears in files
+    pub fn name(&self) -> &'static str {
+        match *self {
+            BehaviorConfig::Seek { .. } => "Seek",
+            BehaviorConfig::Flee { .. } => "Flee",
+            BehaviorConfig::Arrive { .. } => "Arrive",
+            BehaviorConfig::Face { .. } => "Face",
+            BehaviorConfig::Pursue { .. } => "Pursue",
+            BehaviorConfig::Evade { .. } => "Evade",
+            BehaviorConfig::Wander { .. } => "Wander",
+        }
+    }
+
+    /// returns the name of the target, Wander has none
+    pub fn target(&self) -> Option<&str> {
+        match *self {
+            BehaviorConfig::Seek { ref target } |
+            BehaviorConfig::Flee { ref target } |
+            BehaviorConfig::Arrive { ref target, .. } |
+            BehaviorConfig::Face { ref target, .. } |
+            BehaviorConfig::Pursue { ref target, .. } |
+            BehaviorConfig::Evade { ref target, .. } => Some(target),
+            BehaviorConfig::Wander { .. } => None,
+        }
+    }
+
+    /// Checks parameters of the behavior, path is the location of this entry in the file
+    pub fn validate(&self, path: &str) -> Result<(), ConfigError> {
+        let result = match *self {
+            BehaviorConfig::Seek { .. } | BehaviorConfig::Flee { .. } => Ok(()),
+            BehaviorConfig::Arrive { ref parameters, .. } => parameters.validate(),
+            BehaviorConfig::Face { ref parameters, .. } => parameters.validate(),
+            BehaviorConfig::Pursue { ref parameters, .. } => parameters.validate(),
+            BehaviorConfig::Evade { ref parameters, .. } => parameters.validate(),
+            BehaviorConfig::Wander { ref parameters } => parameters.validate(),
+        };
+        result.map_err(|e| {
+            let reason = match e {
+                SteeringError::InvalidParameter(reason) => reason,
+                e => e.to_string(),
+            };
+            ConfigError::new(
+                format!("{}.{}.parameters", path, self.name()),
+                ConfigErrorKind::InvalidParameter(reason),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{ConfigError, ConfigErrorKind, TargetMap};
+    use super::SteeringConfig;
+    use {Location, Steerable, SteeringAcceleration, SteeringAccelerationCalculator,
+         StaticLocation};
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    struct Agent {
+        position: Vector2<f32>,
+        velocity: Vector2<f32>,
+    }
+
+    impl Location<f32, Vector2<f32>> for Agent {
+        fn get_position(&self) -> &Vector2<f32> {
+            &self.position
+        }
+
+        fn get_orientation(&self) -> f32 {
+            0.0
+        }
+    }
+
+    impl Steerable<f32, Vector2<f32>> for Agent {
+        fn get_linear_velocity(&self) -> &Vector2<f32> {
+            &self.velocity
+        }
+
+        fn get_angular_velocity(&self) -> f32 {
+            0.0
+        }
+
+        fn get_bounding_radius(&self) -> f32 {
+            1.0
+        }
+    }
+
+    const RON: &str = r#"(
+        limiter: Some((
+            max_linear_acceleration: 2.0,
+            max_linear_speed: 5.0,
+            max_angular_acceleration: 3.0,
+            max_angular_speed: 2.0,
+            zero_linear_speed_threshold: 0.001,
+        )),
+        groups: [
+            (name: Some("chase"), behaviors: [
+                (weight: 1.0, behavior: Seek(target: "player")),
+                (weight: 0.5, behavior: Arrive(target: "home", parameters: (
+                    tolerance: 0.5, deceleration_radius: 4.0, time_to_target: 0.1))),
+            ]),
+            (behaviors: [
+                (behavior: Wander(parameters: (wander_offset: 2.0, wander_radius: 1.0,
+                    wander_rate: 0.3, face_enabled: false, allign_tolerance: 0.01,
+                    deceleration_radius: 0.5, time_to_target: 0.1))),
+            ]),
+        ],
+    )"#;
+
+    fn targets() -> TargetMap<f32, Vector2<f32>> {
+        let mut targets = TargetMap::new();
+        targets.add_agent(
+            "player",
+            Rc::new(RefCell::new(Agent {
+                position: Vector2::new(10.0, 0.0),
+                velocity: Vector2::new(0.0, 1.0),
+            })),
+        );
+        targets.add_location(
+            "home",
+            Rc::new(RefCell::new(StaticLocation::new(Vector2::new(10.0, 0.0), 0.0))),
+        );
+        targets
+    }
+
+    fn owner() -> Rc<RefCell<Agent>> {
+        Rc::new(RefCell::new(Agent {
+            position: Vector2::new(0.0, 0.0),
+            velocity: Vector2::new(0.0, 0.0),
+        }))
+    }
+
+    #[test]
+    fn setup_from_ron_steers() {
+        let config: SteeringConfig<f32> = SteeringConfig::from_ron(RON).unwrap();
+        assert_eq!(2, config.groups.len());
+        assert_eq!(1.0, config.groups[1].behaviors[0].weight);
+
+        let mut steering = config.build(owner(), &targets()).unwrap();
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let sa = steering.calculate_steering(sa);
+        // seek at full acceleration plus arrive at half weight, truncated by the limiter
+        assert!((sa.borrow().linear - Vector2::new(2.0, 0.0)).norm() < 1e-5);
+        assert_eq!(Some(0), steering.get_selected_behavior_index());
+    }
+
+    #[test]
+    fn formats_describe_the_same_setup() {
+        let config: SteeringConfig<f32> = SteeringConfig::from_ron(RON).unwrap();
+        let json = r#"{
+            "limiter": {
+                "max_linear_acceleration": 2.0,
+                "max_linear_speed": 5.0,
+                "max_angular_acceleration": 3.0,
+                "max_angular_speed": 2.0,
+                "zero_linear_speed_threshold": 0.001
+            },
+            "groups": [
+                {"name": "chase", "behaviors": [
+                    {"weight": 1.0, "behavior": {"Seek": {"target": "player"}}},
+                    {"weight": 0.5, "behavior": {"Arrive": {"target": "home", "parameters": {
+                        "tolerance": 0.5, "deceleration_radius": 4.0, "time_to_target": 0.1}}}}
+                ]},
+                {"behaviors": [
+                    {"behavior": {"Wander": {"parameters": {"wander_offset": 2.0,
+                        "wander_radius": 1.0, "wander_rate": 0.3, "face_enabled": false,
+                        "allign_tolerance": 0.01, "deceleration_radius": 0.5,
+                        "time_to_target": 0.1}}}}
+                ]}
+            ]
+        }"#;
+        assert_eq!(config, SteeringConfig::from_json(json).unwrap());
+
+        let toml = r#"
+            [limiter]
+            max_linear_acceleration = 2.0
+            max_linear_speed = 5.0
+            max_angular_acceleration = 3.0
+            max_angular_speed = 2.0
+            zero_linear_speed_threshold = 0.001
+
+            [[groups]]
+            name = "chase"
+            [[groups.behaviors]]
+            weight = 1.0
+            behavior = { Seek = { target = "player" } }
+            [[groups.behaviors]]
+            weight = 0.5
+            [groups.behaviors.behavior.Arrive]
+            target = "home"
+            parameters = { tolerance = 0.5, deceleration_radius = 4.0, time_to_target = 0.1 }
+
+            [[groups]]
+            [[groups.behaviors]]
+            [groups.behaviors.behavior.Wander.parameters]
+            wander_offset = 2.0
+            wander_radius = 1.0
+            wander_rate = 0.3
+            face_enabled = false
+            allign_tolerance = 0.01
+            deceleration_radius = 0.5
+            time_to_target = 0.1
+        "#;
+        assert_eq!(config, SteeringConfig::from_toml(toml).unwrap());
+    }
+
+    #[test]
+    fn syntax_error_points_at_entry() {
+        let error = SteeringConfig::<f32>::from_json(
+            r#"{"groups": [{"behaviors": [{"behavior": {"Seek": {"target": 3}}}]}]}"#,
+        ).unwrap_err();
+        assert_eq!("groups[0].behaviors[0].behavior.Seek.target", error.path);
+
+        let error = SteeringConfig::<f32>::from_ron(
+            "(groups: [(behaviors: []), (behaviors: [(behavior: Jump(height: 2.0))])])",
+        ).unwrap_err();
+        assert_eq!("groups[1].behaviors[0].behavior", error.path);
+    }
+
+    #[test]
+    fn build_error_points_at_entry() {
+        let config: SteeringConfig<f32> = SteeringConfig::from_ron(
+            r#"(groups: [(behaviors: [
+                (behavior: Seek(target: "home")),
+                (behavior: Pursue(target: "home", parameters: (max_prediction_time: 1.0))),
+            ])])"#,
+        ).unwrap();
+        assert_eq!(
+            Err(ConfigError::new(
+                "groups[0].behaviors[1].behavior.Pursue.target",
+                ConfigErrorKind::UnknownTarget("home".to_string()),
+            )),
+            config.build(owner(), &targets()).map(|_| ())
+        );
+
+        let config: SteeringConfig<f32> = SteeringConfig::from_ron(
+            r#"(groups: [(behaviors: [(behavior: Arrive(target: "home", parameters: (
+                tolerance: 0.5, deceleration_radius: 4.0, time_to_target: 0.0)))])])"#,
+        ).unwrap();
+        let error = config.build(owner(), &targets()).map(|_| ()).unwrap_err();
+        assert_eq!("groups[0].behaviors[0].behavior.Arrive.parameters", error.path);
+        assert_eq!(
+            ConfigErrorKind::InvalidParameter(
+                "time_to_target must be a positive number".to_string()
+            ),
+            error.kind
+        );
+    }
+}
@@ -0,0 +1,311 @@
+use alga::general::Real;
+use {Arrive, BlendedSteering, BlendedSteeringBuilder, Evade, Face, Flee, FullLimiter, Limiter,
+     PrioritySteering, PrioritySteeringBuilder, Pursue, Seek, Steerable,
+     SteeringAccelerationCalculator, SteeringBehavior, SteeringVector, Wander};
+use super::{BehaviorConfig, ConfigError, ConfigErrorKind, SteeringConfig, TargetResolver};
+use super::steering_config::behavior_path;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A behavior created from configuration. It keeps the concrete type of the
+/// behavior so that its parameters can be replaced while it is running.
+#[derive(Clone)]
+pub enum ConfiguredBehavior<T: Real, V: SteeringVector<T>> {
+    Seek(Rc<RefCell<Seek<T, V>>>),
+    Flee(Rc<RefCell<Flee<T, V>>>),
+    Arrive(Rc<RefCell<Arrive<T, V>>>),
+    Face(Rc<RefCell<Face<T, V>>>),
+    Pursue(Rc<RefCell<Pursue<T, V>>>),
+    Evade(Rc<RefCell<Evade<T, V>>>),
+    Wander(Rc<RefCell<Wander<T, V>>>),
+}
+
+impl<T: Real, V: SteeringVector<T>> ConfiguredBehavior<T, V> {
+    /// Creates the behavior described by the configuration, path is the location
+    /// of the entry in the file
+    pub fn new<R: TargetResolver<T, V>>(
+        config: &BehaviorConfig<T>,
+        behavior: SteeringBehavior<T, V>,
+        resolver: &R,
+        path: &str,
+    ) -> Result<ConfiguredBehavior<T, V>, ConfigError> {
+        config.validate(path)?;
+        let unknown_target = |name: &String| {
+            ConfigError::new(
+                format!("{}.{}.target", path, config.name()),
+                ConfigErrorKind::UnknownTarget(name.clone()),
+            )
+        };
+        let location = |name: &String| {
+            resolver
+                .resolve_location(name)
+                .ok_or_else(|| unknown_target(name))
+        };
+        let agent = |name: &String| {
+            resolver
+                .resolve_agent(name)
+                .ok_or_else(|| unknown_target(name))
+        };
+
+        Ok(match *config {
+            BehaviorConfig::Seek { ref target } => {
+                ConfiguredBehavior::Seek(Rc::new(RefCell::new(Seek {
+                    behavior: RefCell::new(behavior),
+                    target: location(target)?,
+                })))
+            }
+            BehaviorConfig::Flee { ref target } => {
+                ConfiguredBehavior::Flee(Rc::new(RefCell::new(Flee {
+                    behavior: RefCell::new(behavior),
+                    target: location(target)?,
+                })))
+            }
+            BehaviorConfig::Arrive {
+                ref target,
+                ref parameters,
+            } => ConfiguredBehavior::Arrive(Rc::new(RefCell::new(
+                Arrive::with_parameters(behavior, location(target)?, parameters),
+            ))),
+            BehaviorConfig::Face {
+                ref target,
+                ref parameters,
+            } => ConfiguredBehavior::Face(Rc::new(RefCell::new(
+                Face::with_parameters(behavior, location(target)?, parameters),
+            ))),
+            BehaviorConfig::Pursue {
+                ref target,
+                ref parameters,
+            } => ConfiguredBehavior::Pursue(Rc::new(RefCell::new(
+                Pursue::with_parameters(behavior, agent(target)?, parameters),
+            ))),
+            BehaviorConfig::Evade {
+                ref target,
+                ref parameters,
+            } => ConfiguredBehavior::Evade(Rc::new(RefCell::new(
+                Evade::with_parameters(behavior, agent(target)?, parameters),
+            ))),
+            BehaviorConfig::Wander { ref parameters } => ConfiguredBehavior::Wander(
+                Rc::new(RefCell::new(Wander::with_parameters(behavior, parameters))),
+            ),
+        })
+    }
+
+    /// returns the behavior as a steering calculator
+    pub fn calculator(&self) -> Rc<RefCell<SteeringAccelerationCalculator<T, V>>> {
+        match *self {
+            ConfiguredBehavior::Seek(ref behavior) => behavior.clone(),
+            ConfiguredBehavior::Flee(ref behavior) => behavior.clone(),
+            ConfiguredBehavior::Arrive(ref behavior) => behavior.clone(),
+            ConfiguredBehavior::Face(ref behavior) => behavior.clone(),
+            ConfiguredBehavior::Pursue(ref behavior) => behavior.clone(),
+            ConfiguredBehavior::Evade(ref behavior) => behavior.clone(),
+            ConfiguredBehavior::Wander(ref behavior) => behavior.clone(),
+        }
+    }
+
+    /// Replaces parameters of the running behavior, internal state such as the
+    /// wander orientation is kept. Configuration must describe the same kind of
+    /// behavior.
+    fn set_parameters(&self, config: &BehaviorConfig<T>) {
+        match (self, config) {
+            (&ConfiguredBehavior::Arrive(ref behavior),
+             &BehaviorConfig::Arrive { ref parameters, .. }) => {
+                behavior.borrow_mut().set_parameters(parameters)
+            }
+            (&ConfiguredBehavior::Face(ref behavior),
+             &BehaviorConfig::Face { ref parameters, .. }) => {
+                behavior.borrow_mut().set_parameters(parameters)
+            }
+            (&ConfiguredBehavior::Pursue(ref behavior),
+             &BehaviorConfig::Pursue { ref parameters, .. }) => {
+                behavior.borrow_mut().set_parameters(parameters)
+            }
+            (&ConfiguredBehavior::Evade(ref behavior),
+             &BehaviorConfig::Evade { ref parameters, .. }) => {
+                behavior.borrow_mut().set_parameters(parameters)
+            }
+            (&ConfiguredBehavior::Wander(ref behavior),
+             &BehaviorConfig::Wander { ref parameters }) => {
+                behavior.borrow_mut().set_parameters(parameters)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Runtime objects created from a configuration
+pub struct Instance<T: Real, V: SteeringVector<T>> {
+    /// steering of the whole setup
+    pub steering: PrioritySteering<T, V>,
+    /// blended groups of the setup
+    pub groups: Vec<Rc<RefCell<BlendedSteering<T, V>>>>,
+    /// behaviors of every group
+    pub behaviors: Vec<Vec<ConfiguredBehavior<T, V>>>,
+    /// limiter shared by every behavior
+    pub limiter: Option<Rc<RefCell<FullLimiter<T>>>>,
+}
+
+/// Creates the runtime objects of the configuration steering given owner
+pub fn instantiate<T: Real, V: SteeringVector<T>, R: TargetResolver<T, V>>(
+    config: &SteeringConfig<T>,
+    owner: Rc<RefCell<Steerable<T, V>>>,
+    resolver: &R,
+) -> Result<Instance<T, V>, ConfigError> {
+    let limiter = config
+        .limiter
+        .clone()
+        .map(|limiter| Rc::new(RefCell::new(limiter)));
+    let behavior = SteeringBehavior {
+        enabled: true,
+        limiter: limiter
+            .clone()
+            .map(|limiter| limiter as Rc<RefCell<Limiter<T>>>),
+        owner: owner,
+    };
+
+    let mut groups = Vec::new();
+    let mut behaviors = Vec::new();
+    for (group_index, group) in config.groups.iter().enumerate() {
+        let mut blended = BlendedSteeringBuilder::new()
+            .behavior(RefCell::new(behavior.clone()))
+            .list(Vec::new())
+            .build()
+            .map_err(|e| ConfigError::new(".", ConfigErrorKind::InvalidParameter(e)))?;
+        let mut configured = Vec::new();
+        for (index, weighted) in group.behaviors.iter().enumerate() {
+            let item = ConfiguredBehavior::new(
+                &weighted.behavior,
+                behavior.clone(),
+                resolver,
+                &behavior_path(group_index, index),
+            )?;
+            blended.add(item.calculator(), weighted.weight);
+            configured.push(item);
+        }
+        groups.push(Rc::new(RefCell::new(blended)));
+        behaviors.push(configured);
+    }
+
+    let calculators = groups
+        .iter()
+        .map(|group| group.clone() as Rc<RefCell<SteeringAccelerationCalculator<T, V>>>)
+        .collect();
+    let steering = PrioritySteeringBuilder::new()
+        .behavior(RefCell::new(behavior))
+        .behaviors(calculators)
+        .epsilon(config.get_epsilon())
+        .build()
+        .map_err(|e| ConfigError::new(".", ConfigErrorKind::InvalidParameter(e)))?;
+    Ok(Instance {
+        steering: steering,
+        groups: groups,
+        behaviors: behaviors,
+        limiter: limiter,
+    })
+}
+
+/// Steering built from a configuration whose parameters can be changed while the
+/// behaviors are running
+pub struct SteeringProfile<T: Real, V: SteeringVector<T>> {
+    config: SteeringConfig<T>,
+    steering: Rc<RefCell<PrioritySteering<T, V>>>,
+    groups: Vec<Rc<RefCell<BlendedSteering<T, V>>>>,
+    behaviors: Vec<Vec<ConfiguredBehavior<T, V>>>,
+    limiter: Option<Rc<RefCell<FullLimiter<T>>>>,
+}
+
+impl<T: Real, V: SteeringVector<T>> SteeringProfile<T, V> {
+    /// Creates the behaviors of the configuration steering given owner
+    pub fn new<R: TargetResolver<T, V>>(
+        config: &SteeringConfig<T>,
+        owner: Rc<RefCell<Steerable<T, V>>>,
+        resolver: &R,
+    ) -> Result<SteeringProfile<T, V>, ConfigError> {
+        let instance = instantiate(config, owner, resolver)?;
+        Ok(SteeringProfile {
+            config: config.clone(),
+            steering: Rc::new(RefCell::new(instance.steering)),
+            groups: instance.groups,
+            behaviors: instance.behaviors,
+            limiter: instance.limiter,
+        })
+    }
+
+    /// returns the steering of the whole setup
+    pub fn get_steering(&self) -> Rc<RefCell<PrioritySteering<T, V>>> {
+        self.steering.clone()
+    }
+
+    /// returns the configuration currently applied
+    pub fn get_config(&self) -> &SteeringConfig<T> {
+        &self.config
+    }
+
+    /// returns the behavior at given index of given group
+    pub fn get_behavior(&self, group: usize, index: usize) -> Option<&ConfiguredBehavior<T, V>> {
+        self.behaviors.get(group).and_then(|behaviors| behaviors.get(index))
+    }
+
+    /// Applies weights, parameters and limits of the configuration to the running
+    /// behaviors. Nothing is changed if the configuration is invalid or describes
+    /// different behaviors or targets.
+    pub fn reconfigure(&mut self, config: &SteeringConfig<T>) -> Result<(), ConfigError> {
+        self.check_compatible(config)?;
+        config.validate()?;
+
+        if let (&Some(ref limiter), &Some(ref settings)) = (&self.limiter, &config.limiter) {
+            *limiter.borrow_mut() = settings.clone();
+        }
+        self.steering.borrow_mut().epsilon = config.get_epsilon();
+        for (group_index, group) in config.groups.iter().enumerate() {
+            let mut blended = self.groups[group_index].borrow_mut();
+            for (index, weighted) in group.behaviors.iter().enumerate() {
+                blended.list[index].weight = weighted.weight;
+                self.behaviors[group_index][index].set_parameters(&weighted.behavior);
+            }
+        }
+        self.config = config.clone();
+        Ok(())
+    }
+
+    /// Checks that the configuration describes the same behaviors and targets as the
+    /// one the behaviors were built from, so `reconfigure` can apply it
+    pub fn check_compatible(&self, config: &SteeringConfig<T>) -> Result<(), ConfigError> {
+        let incompatible = |path: String, reason: &str| {
+            Err(ConfigError::new(
+                path,
+                ConfigErrorKind::Incompatible(reason.to_string()),
+            ))
+        };
+        if self.config.limiter.is_some() != config.limiter.is_some() {
+            return incompatible("limiter".to_string(), "limiter was added or removed");
+        }
+        if self.config.groups.len() != config.groups.len() {
+            return incompatible("groups".to_string(), "number of groups changed");
+        }
+        let groups = self.config.groups.iter().zip(config.groups.iter());
+        for (group_index, (old, new)) in groups.enumerate() {
+            if old.behaviors.len() != new.behaviors.len() {
+                return incompatible(
+                    format!("groups[{}].behaviors", group_index),
+                    "number of behaviors changed",
+                );
+            }
+            let behaviors = old.behaviors.iter().zip(new.behaviors.iter());
+            for (index, (old, new)) in behaviors.enumerate() {
+                let path = behavior_path(group_index, index);
+                if old.behavior.name() != new.behavior.name() {
+                    return incompatible(path, "behavior changed");
+                }
+                if old.behavior.target() != new.behavior.target() {
+                    return incompatible(
+                        format!("{}.{}.target", path, new.behavior.name()),
+                        "target changed",
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use alga::general::Real;
+use nalgebra::Vector3;
+use {Location, Steerable, SteeringVector};
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Maps target names used in configuration files to agents and locations of the game
+pub trait TargetResolver<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// returns the location with given name, targets of Seek, Flee, Arrive and Face
+    fn resolve_location(&self, name: &str) -> Option<Rc<RefCell<Location<T, V>>>>;
+
+    /// returns the agent with given name, targets of Pursue and Evade
+    fn resolve_agent(&self, name: &str) -> Option<Rc<RefCell<Steerable<T, V>>>>;
+}
+
+/// Resolver looking up targets registered by name
+pub struct TargetMap<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    locations: HashMap<String, Rc<RefCell<Location<T, V>>>>,
+    agents: HashMap<String, Rc<RefCell<Steerable<T, V>>>>,
+}
+
+impl<T: Real, V: SteeringVector<T>> TargetMap<T, V> {
+    /// Creates an empty map
+    pub fn new() -> TargetMap<T, V> {
+        TargetMap {
+            locations: HashMap::new(),
+            agents: HashMap::new(),
+        }
+    }
+
+    /// Registers a location which can be used as target of Seek, Flee, Arrive and Face
+    pub fn add_location<L: Location<T, V> + 'static>(
+        &mut self,
+        name: &str,
+        location: Rc<RefCell<L>>,
+    ) {
+        self.locations.insert(name.to_string(), location);
+    }
+
+    /// Registers an agent which can be used as target of any behavior
+    pub fn add_agent<S: Steerable<T, V> + 'static>(&mut self, name: &str, agent: Rc<RefCell<S>>) {
+        self.locations.insert(name.to_string(), agent.clone());
+        self.agents.insert(name.to_string(), agent);
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> TargetResolver<T, V> for TargetMap<T, V> {
+    fn resolve_location(&self, name: &str) -> Option<Rc<RefCell<Location<T, V>>>> {
+        self.locations.get(name).cloned()
+    }
+
+    fn resolve_agent(&self, name: &str) -> Option<Rc<RefCell<Steerable<T, V>>>> {
+        self.agents.get(name).cloned()
+    }
+}
@@ -0,0 +1,325 @@
+//! Intercept solver finding where something moving at a constant speed meets a
+//! target moving with a constant velocity and optionally a constant acceleration.
+//! `Pursue` uses it in `PursueMode::Intercept`, turrets use it to lead projectiles.
+//! Projectiles inheriting the velocity of the shooter are handled by passing the
+//! target velocity relative to the shooter, gravity by subtracting it from the
+//! target acceleration.
+use alga::general::Real;
+use SteeringVector;
+
+/// Where and when an interceptor meets its target
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Intercept<T: Real, V: SteeringVector<T>> {
+    /// seconds from now until the interceptor meets the target
+    pub time: T,
+    /// position of the target when they meet
+    pub point: V,
+    /// unit direction the interceptor has to move in, zero if it already is at
+    /// the target
+    pub direction: V,
+}
+
+/// returns the earliest intercept of a target by something starting at `position`
+/// and moving in a straight line at `speed`, `None` if the target escapes. Without
+/// acceleration the intercept time solves a quadratic equation, with acceleration
+/// a quartic one.
+pub fn intercept<T: Real, V: SteeringVector<T>>(
+    position: &V,
+    speed: T,
+    target_position: &V,
+    target_velocity: &V,
+    target_acceleration: Option<&V>,
+) -> Option<Intercept<T, V>> {
+    let offset = *target_position - *position;
+    let time = intercept_time(&offset, speed, target_velocity, target_acceleration)?;
+    let point = predict(target_position, target_velocity, target_acceleration, time);
+    let direction = if time > T::zero() && speed > T::zero() {
+        (point - *position).scale(T::one() / (speed * time))
+    } else {
+        V::zero()
+    };
+    Some(Intercept {
+        time: time,
+        point: point,
+        direction: direction.normalize_or_zero(),
+    })
+}
+
+/// returns the earliest non-negative time at which something moving at `speed`
+/// from the origin meets a target at `offset`, `None` if the target escapes
+pub fn intercept_time<T: Real, V: SteeringVector<T>>(
+    offset: &V,
+    speed: T,
+    target_velocity: &V,
+    target_acceleration: Option<&V>,
+) -> Option<T> {
+    if offset.is_zero() {
+        return Some(T::zero());
+    }
+    // |offset + velocity t + acceleration t^2 / 2|^2 = (speed t)^2
+    let two = T::one() + T::one();
+    let mut coefficients = vec![
+        offset.dot(offset),
+        two * offset.dot(target_velocity),
+        target_velocity.dot(target_velocity) - speed * speed,
+    ];
+    if let Some(acceleration) = target_acceleration {
+        coefficients[2] += offset.dot(acceleration);
+        coefficients.push(target_velocity.dot(acceleration));
+        coefficients.push(acceleration.dot(acceleration) / (two * two));
+    }
+    real_roots(&coefficients)
+        .into_iter()
+        .filter(|time| *time >= T::zero())
+        .fold(None, |earliest: Option<T>, time| match earliest {
+            Some(earliest) if earliest <= time => Some(earliest),
+            _ => Some(time),
+        })
+}
+
+/// returns the position of a target after `time` seconds
+pub fn predict<T: Real, V: SteeringVector<T>>(
+    position: &V,
+    velocity: &V,
+    acceleration: Option<&V>,
+    time: T,
+) -> V {
+    let mut prediction = *position + velocity.scale(time);
+    if let Some(acceleration) = acceleration {
+        prediction += acceleration.scale(time * time / (T::one() + T::one()));
+    }
+    prediction
+}
+
+/// returns the real roots of the polynomial with given coefficients, the one of
+/// the constant term first. Roots of a quadratic are solved for directly, roots of
+/// higher degrees are bracketed between the roots of the derivative and bisected,
+/// roots of the derivative where the polynomial touches zero are double roots.
+fn real_roots<T: Real>(coefficients: &[T]) -> Vec<T> {
+    let degree = match coefficients.iter().rposition(|c| !c.is_zero()) {
+        Some(degree) => degree,
+        None => return Vec::new(),
+    };
+    let coefficients = &coefficients[..degree + 1];
+    match degree {
+        0 => Vec::new(),
+        1 => vec![-coefficients[0] / coefficients[1]],
+        2 => quadratic_roots(coefficients[2], coefficients[1], coefficients[0]),
+        _ => {
+            let derivative: Vec<T> = coefficients
+                .iter()
+                .enumerate()
+                .skip(1)
+                .map(|(power, c)| *c * T::from_usize(power).unwrap())
+                .collect();
+            // every root lies within the Cauchy bound
+            let bound = T::one() +
+                coefficients[..degree]
+                    .iter()
+                    .map(|c| (*c / coefficients[degree]).abs())
+                    .fold(T::zero(), |max, c| max.max(c));
+            let mut critical: Vec<T> = real_roots(&derivative)
+                .into_iter()
+                .filter(|x| -bound < *x && *x < bound)
+                .collect();
+            critical.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let mut points = vec![-bound];
+            points.extend(critical);
+            points.push(bound);
+
+            let mut roots: Vec<T> = Vec::new();
+            for pair in points.windows(2) {
+                let (low, high) = (pair[0], pair[1]);
+                let (f_low, f_high) = (evaluate(coefficients, low), evaluate(coefficients, high));
+                // double roots are roots of the derivative where the sign does not change
+                let root = if is_root(coefficients, low, f_low) {
+                    Some(low)
+                } else if (f_low < T::zero()) != (f_high < T::zero()) &&
+                    !is_root(coefficients, high, f_high)
+                {
+                    Some(bisect(coefficients, low, high, f_low))
+                } else {
+                    None
+                };
+                if let Some(root) = root {
+                    if roots.last().map_or(true, |last| *last != root) {
+                        roots.push(root);
+                    }
+                }
+            }
+            if is_root(coefficients, bound, evaluate(coefficients, bound)) {
+                roots.push(bound);
+            }
+            roots
+        }
+    }
+}
+
+/// returns the real roots of `a x^2 + b x + c` with a non zero `a`, avoiding the
+/// cancellation of the textbook formula
+fn quadratic_roots<T: Real>(a: T, b: T, c: T) -> Vec<T> {
+    let two = T::one() + T::one();
+    let discriminant = b * b - two * two * a * c;
+    if discriminant < T::zero() {
+        return Vec::new();
+    }
+    let root = discriminant.sqrt();
+    let q = if b < T::zero() {
+        (root - b) / two
+    } else {
+        -(b + root) / two
+    };
+    if q.is_zero() {
+        vec![T::zero()]
+    } else {
+        vec![q / a, c / q]
+    }
+}
+
+/// returns the value of a polynomial at x with Horner's scheme
+fn evaluate<T: Real>(coefficients: &[T], x: T) -> T {
+    coefficients.iter().rev().fold(T::zero(), |value, c| value * x + *c)
+}
+
+/// Tests whether the value of a polynomial at x is zero up to the rounding errors
+/// of its terms
+fn is_root<T: Real>(coefficients: &[T], x: T, value: T) -> bool {
+    let magnitude = coefficients
+        .iter()
+        .rev()
+        .fold(T::zero(), |magnitude, c| magnitude * x.abs() + c.abs());
+    value.abs() <= T::default_epsilon().sqrt() * magnitude
+}
+
+/// returns the root of a polynomial between two points where it has opposite signs
+fn bisect<T: Real>(coefficients: &[T], mut low: T, mut high: T, mut f_low: T) -> T {
+    let two = T::one() + T::one();
+    for _ in 0..200 {
+        let middle = (low + high) / two;
+        if middle <= low || middle >= high {
+            break;
+        }
+        let f_middle = evaluate(coefficients, middle);
+        if f_middle.is_zero() {
+            return middle;
+        }
+        if (f_middle < T::zero()) == (f_low < T::zero()) {
+            low = middle;
+            f_low = f_middle;
+        } else {
+            high = middle;
+        }
+    }
+    (low + high) / two
+}
+
+#[cfg(test)]
+mod test {
+    use super::{intercept, intercept_time, real_roots};
+    use nalgebra::{Vector2, Vector3};
+    use quickcheck::quickcheck;
+
+    #[test]
+    fn roots_of_polynomials() {
+        let roots = real_roots(&[-6.0f64, 11.0, -6.0, 1.0]);
+        assert_eq!(3, roots.len());
+        for (root, expected) in roots.iter().zip([1.0, 2.0, 3.0].iter()) {
+            assert!((root - expected).abs() < 1e-9);
+        }
+        // (x^2 - 1)(x^2 - 4)
+        let roots = real_roots(&[4.0f64, 0.0, -5.0, 0.0, 1.0]);
+        assert_eq!(4, roots.len());
+        assert!((roots[0] + 2.0).abs() < 1e-9 && (roots[3] - 2.0).abs() < 1e-9);
+        assert!(real_roots(&[1.0f64, 0.0, 1.0]).is_empty());
+
+        // (x - 0.1)^2 (x + 1) touches zero at 0.1 without changing its sign
+        let roots = real_roots(&[0.01f64, -0.19, 0.8, 1.0]);
+        assert_eq!(2, roots.len());
+        assert!((roots[0] + 1.0).abs() < 1e-9 && (roots[1] - 0.1).abs() < 1e-6);
+        // (x^2 - 0.3)^2
+        let roots = real_roots(&[0.09f64, 0.0, -0.6, 0.0, 1.0]);
+        assert_eq!(2, roots.len());
+        assert!((roots[0] + 0.3f64.sqrt()).abs() < 1e-6);
+        assert!((roots[1] - 0.3f64.sqrt()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn grazes_an_accelerating_target() {
+        // the interceptor just catches up with a target speeding away when
+        // (speed - velocity)^2 = 2 acceleration distance, it meets it only once
+        let direction = Vector2::new(0.6f64, 0.8);
+        let time = intercept_time(
+            &(direction * 0.7),
+            3.0,
+            &(direction * 1.3),
+            Some(&(direction * (2.89 / 1.4))),
+        ).unwrap();
+        assert!((time - 1.4 / 1.7).abs() < 1e-6);
+    }
+
+    #[test]
+    fn leads_a_moving_target() {
+        // target crossing at 3 while the interceptor covers 4 meets it after 1
+        let result = intercept(
+            &Vector2::new(0.0f32, 0.0),
+            4.0,
+            &Vector2::new(4.0, -3.0),
+            &Vector2::new(0.0, 3.0),
+            None,
+        ).unwrap();
+        assert!((result.time - 1.0).abs() < 1e-5);
+        assert!((result.point - Vector2::new(4.0, 0.0)).norm() < 1e-5);
+        assert!((result.direction - Vector2::new(1.0, 0.0)).norm() < 1e-5);
+
+        // a faster target moving away escapes
+        let offset = Vector2::new(4.0f32, 0.0);
+        assert_eq!(None, intercept_time(&offset, 1.0, &Vector2::new(2.0, 0.0), None));
+    }
+
+    #[test]
+    fn leads_an_accelerating_target() {
+        // a target starting at rest falls towards the interceptor below it
+        let time = intercept_time(
+            &Vector3::new(0.0f32, 0.0, 10.0),
+            5.0,
+            &Vector3::new(0.0, 0.0, 0.0),
+            Some(&Vector3::new(0.0, 0.0, -10.0)),
+        ).unwrap();
+        // 10 - 5 t^2 = 5 t
+        assert!((time - 1.0).abs() < 1e-5);
+
+        // a target speeding away is caught later than a cruising one
+        let offset = Vector2::new(10.0f32, 0.0);
+        let velocity = Vector2::new(1.0, 1.0);
+        let cruising = intercept_time(&offset, 4.0, &velocity, None).unwrap();
+        let accelerating =
+            intercept_time(&offset, 4.0, &velocity, Some(&Vector2::new(0.1, 0.0))).unwrap();
+        assert!(accelerating > cruising);
+    }
+
+    #[test]
+    fn intercepts_meet_the_target() {
+        fn prop(offset: (f32, f32), velocity: (f32, f32), acceleration: (f32, f32)) -> bool {
+            let clamp = |x: f32| if x.is_finite() { x % 10.0 } else { 0.0 };
+            let offset = Vector2::new(clamp(offset.0), clamp(offset.1));
+            // slower than the interceptor, so it always catches up without acceleration
+            let velocity = Vector2::new(clamp(velocity.0) / 2.0, clamp(velocity.1) / 2.0);
+            let acceleration = Vector2::new(clamp(acceleration.0), clamp(acceleration.1));
+            let origin = Vector2::new(0.0f64, 0.0);
+            let (offset, velocity, acceleration) = (
+                Vector2::new(offset.x as f64, offset.y as f64),
+                Vector2::new(velocity.x as f64, velocity.y as f64),
+                Vector2::new(acceleration.x as f64, acceleration.y as f64),
+            );
+            match intercept(&origin, 12.0, &offset, &velocity, Some(&acceleration)) {
+                Some(result) => {
+                    let travelled = result.direction * 12.0 * result.time;
+                    (travelled - result.point).norm() < 1e-6 * (1.0 + result.point.norm())
+                }
+                None => acceleration.norm() > 0.0,
+            }
+        }
+        quickcheck(prop as fn((f32, f32), (f32, f32), (f32, f32)) -> bool);
+    }
+}
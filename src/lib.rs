@@ -1,28 +1,108 @@
 //! This library implements real life like behaviors for your autonomous in game agents
 //! Implement Steerable trait for your agent, choose a behaviour, calculate steering and apply
-//! calculated acceleration to your agents velocity.
+//! calculated acceleration to your agents velocity. Agents can live on a plane or in space,
+//! every type is generic over the vector type which defaults to `nalgebra::Vector3`.
 extern crate alga;
 #[macro_use]
 extern crate derive_builder;
 extern crate nalgebra;
 extern crate num_traits;
+#[cfg(test)]
+extern crate quickcheck;
+#[cfg(any(feature = "config", all(test, feature = "serde")))]
+extern crate ron;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(any(feature = "config", all(test, feature = "serde")))]
+extern crate serde_json;
+#[cfg(feature = "config")]
+extern crate serde_path_to_error;
+#[cfg(feature = "config")]
+extern crate toml;
 
+pub use self::location::Location;
+pub use self::static_location::StaticLocation;
 pub use self::steerable::Steerable;
+pub use self::steering_vector::SteeringVector;
 pub use self::steering_behavior::SteeringBehavior;
 pub use self::steering_behavior::HasSteeringBehavior;
 pub use self::steering_behavior::IsEnabled;
 pub use self::limiter::Limiter;
+pub use self::path::Path;
+pub use self::proximity::Proximity;
+pub use proximities::RadiusProximity;
+pub use self::raycast_collision_detector::{Collision, Ray, RaycastCollisionDetector};
+pub use paths::LinePath;
+pub use paths::SplinePath;
+pub use paths::SpeedProfile;
+pub use limiters::{AngularAccelerationLimiter, AngularLimiter, AngularSpeedLimiter, FullLimiter,
+                   LinearAccelerationLimiter, LinearLimiter, LinearSpeedLimiter};
 pub use self::steering_acceleration::SteeringAcceleration;
 pub use self::steering_acceleration::SteeringAccelerationCalculator;
-pub use behavior::Seek;
-pub use behavior::Flee;
-pub use behavior::Pursue;
-pub use behavior::Arrive;
-pub use behavior::Evade;
+pub use self::steering_error::SteeringError;
+pub use self::steering_status::SteeringStatus;
+pub use behavior::{Seek, SeekBuilder};
+pub use behavior::{Flee, FleeBuilder};
+pub use behavior::{Pursue, PursueBuilder, PursueMode, PursueParameters};
+pub use behavior::{Arrive, ArriveBuilder, ArriveParameters};
+pub use behavior::{MatchVelocity, MatchVelocityBuilder};
+pub use behavior::{Evade, EvadeBuilder, EvadeParameters};
+pub use behavior::{Face, FaceBuilder, FaceParameters};
+pub use behavior::{Wander, WanderBuilder, WanderParameters};
+pub use behavior::{FollowPath, FollowPathBuilder};
+pub use behavior::{BehaviorAndWeight, BlendedSteering, BlendedSteeringBuilder};
+pub use behavior::{PrioritySteering, PrioritySteeringBuilder};
+pub use behavior::{SteeringState, SteeringStateMachine, SteeringStateMachineBuilder,
+                   SteeringTransition, TransitionGuard};
+pub use behavior::{Consideration, ResponseCurve, UtilityContext, UtilityOption, UtilitySelection,
+                   UtilitySteering, UtilitySteeringBuilder};
+pub use behavior::{Separation, SeparationBuilder};
+pub use behavior::{Cohesion, CohesionBuilder};
+pub use behavior::{CollisionAvoidance, CollisionAvoidanceBuilder};
+pub use behavior::{RayConfiguration, RaycastObstacleAvoidance, RaycastObstacleAvoidanceBuilder};
+pub use behavior::{Hide, HideBuilder};
+pub use behavior::{Containment, ContainmentBuilder};
+pub use behavior::{WallAvoidance, WallAvoidanceBuilder};
+pub use behavior::{FollowNavMesh, FollowNavMeshBuilder};
+pub use behavior::{FollowFlowField, FollowFlowFieldBuilder};
+pub use behavior::{Patrol, PatrolBuilder, PatrolMode, PatrolWaypoint};
+pub use three_d::{Face3d, Face3dBuilder, Face3dParameters, HasSteeringBehavior3d, Location3d,
+                  LookWhereYouAreGoing3d, LookWhereYouAreGoing3dBuilder,
+                  LookWhereYouAreGoing3dParameters, ReachOrientation3d,
+                  ReachOrientation3dBuilder, ReachOrientation3dParameters, StaticLocation3d,
+                  Steerable3d, SteeringAcceleration3d, SteeringAcceleration3dCalculator,
+                  SteeringBehavior3d};
+pub use three_d::shortest_arc;
+pub use three_d::{Banking, FlightAttitude, FlyingSteering};
 
+mod location;
+mod static_location;
 mod steerable;
+mod steering_vector;
 mod steering_behavior;
 mod limiter;
+mod path;
+mod proximity;
+mod raycast_collision_detector;
 mod steering_acceleration;
+mod steering_error;
+mod steering_status;
+mod validation;
+mod search;
 mod behavior;
 mod limiters;
+mod paths;
+mod proximities;
+mod three_d;
+pub mod vehicle;
+pub mod bt;
+pub mod spatial;
+pub mod obstacle;
+pub mod pathfinding;
+pub mod navmesh;
+pub mod intercept;
+#[cfg(feature = "config")]
+pub mod config;
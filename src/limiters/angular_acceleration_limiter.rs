@@ -1,7 +1,8 @@
 use alga::general::Real;
 use super::super::Limiter;
 
-#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AngularAccelerationLimiter<T: Real> {
     max_angular_acceleration: T,
 }
@@ -0,0 +1,13 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use SteeringVector;
+
+/// Anything that has a position and an orientation. Static targets such as
+/// waypoints only need to implement this trait instead of `Steerable`
+pub trait Location<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// returns the position of the location
+    fn get_position(self: &Self) -> &V;
+
+    /// returns the orientation of the location in radians
+    fn get_orientation(&self) -> T;
+}
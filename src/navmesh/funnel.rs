@@ -0,0 +1,85 @@
+use alga::general::Real;
+use SteeringVector;
+use super::cross;
+
+/// Simple stupid funnel algorithm, returns the shortest path from `start` to `goal`
+/// through given portals. Every portal is a pair of left and right end points as
+/// seen when passing it, the path bends only at portal end points.
+pub fn funnel<T: Real, V: SteeringVector<T>>(portals: &[(V, V)], start: V, goal: V) -> Vec<V> {
+    let mut points = Vec::with_capacity(portals.len() + 2);
+    points.push((start, start));
+    points.extend_from_slice(portals);
+    points.push((goal, goal));
+
+    let mut path = vec![start];
+    let (mut apex, mut left, mut right) = (start, start, start);
+    let (mut left_index, mut right_index) = (0, 0);
+    let mut index = 1;
+    while index < points.len() {
+        let (new_left, new_right) = points[index];
+
+        // narrow the funnel from the right
+        if cross(&apex, &right, &new_right) >= T::zero() {
+            if apex == right || cross(&apex, &left, &new_right) < T::zero() {
+                right = new_right;
+                right_index = index;
+            } else {
+                // the right side crossed the left one, the left end becomes a corner
+                path.push(left);
+                apex = left;
+                right = apex;
+                right_index = left_index;
+                index = left_index + 1;
+                continue;
+            }
+        }
+
+        // narrow the funnel from the left
+        if cross(&apex, &left, &new_left) <= T::zero() {
+            if apex == left || cross(&apex, &right, &new_left) > T::zero() {
+                left = new_left;
+                left_index = index;
+            } else {
+                path.push(right);
+                apex = right;
+                left = apex;
+                left_index = right_index;
+                index = right_index + 1;
+                continue;
+            }
+        }
+        index += 1;
+    }
+    if path[path.len() - 1] != goal {
+        path.push(goal);
+    }
+    path
+}
+
+#[cfg(test)]
+mod test {
+    use super::funnel;
+    use nalgebra::Vector2;
+
+    #[test]
+    fn bends_around_portal_corners() {
+        // corridor turning left around the corner at (1, 1)
+        let portals = vec![
+            (Vector2::new(1.0f32, 1.0), Vector2::new(1.0, 0.0)),
+            (Vector2::new(1.0, 1.0), Vector2::new(2.0, 1.0)),
+        ];
+        let path = funnel(&portals, Vector2::new(0.5, 0.5), Vector2::new(1.5, 3.0));
+        assert_eq!(
+            vec![
+                Vector2::new(0.5, 0.5),
+                Vector2::new(1.0, 1.0),
+                Vector2::new(1.5, 3.0),
+            ],
+            path
+        );
+
+        let straight = funnel(&portals, Vector2::new(0.9, 0.2), Vector2::new(1.2, 1.5));
+        assert_eq!(vec![Vector2::new(0.9, 0.2), Vector2::new(1.2, 1.5)], straight);
+        assert_eq!(2, funnel(&[], Vector2::new(0.0f32, 0.0), Vector2::new(1.0, 1.0)).len());
+    }
+}
@@ -0,0 +1,18 @@
+//! Navigation meshes of convex polygons. Polygons lie on the plane or, for agents in
+//! space, on the ground whose up axis is positive z, so several floors may overlap.
+mod nav_mesh;
+mod funnel;
+
+pub use self::nav_mesh::NavMesh;
+pub use self::funnel::funnel;
+
+use alga::general::Real;
+use SteeringVector;
+
+/// returns twice the signed area of the triangle projected onto the xy plane,
+/// positive if `c` is left of the line from `a` to `b`
+fn cross<T: Real, V: SteeringVector<T>>(a: &V, b: &V, c: &V) -> T {
+    let (ab_x, ab_y) = (b.component(0) - a.component(0), b.component(1) - a.component(1));
+    let (ac_x, ac_y) = (c.component(0) - a.component(0), c.component(1) - a.component(1));
+    ab_x * ac_y - ab_y * ac_x
+}
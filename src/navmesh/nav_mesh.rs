@@ -0,0 +1,408 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {LinePath, SteeringError, SteeringVector};
+use search::Open;
+use super::{cross, funnel};
+
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Polygon<T: Real, V: SteeringVector<T>> {
+    /// vertex indices in counter clockwise order
+    vertices: Vec<usize>,
+    /// polygon sharing the edge from vertex `i` to vertex `i + 1`
+    neighbors: Vec<Option<usize>>,
+    center: V,
+    /// plane normal, used to find the height of points on the polygon
+    normal: V,
+    phantom: PhantomData<T>,
+}
+
+/// Navigation mesh of convex polygons connected through shared edges, the portals.
+/// Polygons must use the same vertex indices for the edges they share.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct NavMesh<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    vertices: Vec<V>,
+    polygons: Vec<Polygon<T, V>>,
+}
+
+impl<T: Real, V: SteeringVector<T>> NavMesh<T, V> {
+    /// Creates a mesh from polygons given as lists of vertex indices. Clockwise
+    /// polygons are reversed, returns an error for polygons which are not convex
+    /// when seen from above, invalid indices and edges shared by more than two
+    /// polygons.
+    pub fn new(
+        vertices: Vec<V>,
+        polygons: Vec<Vec<usize>>,
+    ) -> Result<NavMesh<T, V>, SteeringError> {
+        let mut built = Vec::with_capacity(polygons.len());
+        let mut edges: HashMap<(usize, usize), (usize, usize)> = HashMap::new();
+        for (index, mut indices) in polygons.into_iter().enumerate() {
+            if indices.len() < 3 || indices.iter().any(|vertex| *vertex >= vertices.len()) {
+                return Err(invalid(index, "needs at least three valid vertex indices"));
+            }
+            let points: Vec<V> = indices.iter().map(|vertex| vertices[*vertex]).collect();
+            let mut area = T::zero();
+            for i in 1..points.len() - 1 {
+                area += cross(&points[0], &points[i], &points[i + 1]);
+            }
+            if !(area.abs() > T::default_epsilon()) {
+                return Err(invalid(index, "has no area when seen from above"));
+            }
+            if area < T::zero() {
+                indices.reverse();
+            }
+            let count = indices.len();
+            let vertex = |i: usize| &vertices[indices[i % count]];
+            if (0..count).any(|i| cross(vertex(i), vertex(i + 1), vertex(i + 2)) < T::zero()) {
+                return Err(invalid(index, "is not convex"));
+            }
+            let mut neighbors = vec![None; count];
+            for i in 0..count {
+                let (begin, end) = (indices[i], indices[(i + 1) % count]);
+                let key = if begin < end { (begin, end) } else { (end, begin) };
+                match edges.get(&key).cloned() {
+                    Some((other, other_edge)) => {
+                        if other == usize::max_value() {
+                            return Err(invalid(index, "shares an edge with two polygons"));
+                        }
+                        neighbors[i] = Some(other);
+                        let other_polygon: &mut Polygon<T, V> = &mut built[other];
+                        other_polygon.neighbors[other_edge] = Some(index);
+                        edges.insert(key, (usize::max_value(), 0));
+                    }
+                    None => {
+                        edges.insert(key, (index, i));
+                    }
+                }
+            }
+            let points: Vec<V> = indices.iter().map(|vertex| vertices[*vertex]).collect();
+            built.push(Polygon {
+                center: centroid(&points),
+                normal: normal(&points),
+                vertices: indices,
+                neighbors: neighbors,
+                phantom: PhantomData,
+            });
+        }
+        Ok(NavMesh {
+            vertices: vertices,
+            polygons: built,
+        })
+    }
+
+    /// Creates a mesh of triangles, every three indices form a triangle
+    pub fn from_triangles(
+        vertices: Vec<V>,
+        indices: &[usize],
+    ) -> Result<NavMesh<T, V>, SteeringError> {
+        if indices.len() % 3 != 0 {
+            return Err(SteeringError::InvalidParameter(
+                "number of triangle indices must be a multiple of three".to_string(),
+            ));
+        }
+        let triangles = indices.chunks(3).map(|triangle| triangle.to_vec()).collect();
+        NavMesh::new(vertices, triangles)
+    }
+
+    /// returns the vertices of the mesh
+    pub fn get_vertices(&self) -> &[V] {
+        &self.vertices
+    }
+
+    /// returns the number of polygons
+    pub fn len(&self) -> usize {
+        self.polygons.len()
+    }
+
+    /// Tests whether the mesh has no polygon
+    pub fn is_empty(&self) -> bool {
+        self.polygons.is_empty()
+    }
+
+    /// returns the vertex indices of a polygon in counter clockwise order
+    pub fn get_polygon(&self, polygon: usize) -> &[usize] {
+        &self.polygons[polygon].vertices
+    }
+
+    /// returns the neighbors of a polygon, the one at index `i` shares the edge
+    /// from vertex `i` to vertex `i + 1`
+    pub fn get_neighbors(&self, polygon: usize) -> &[Option<usize>] {
+        &self.polygons[polygon].neighbors
+    }
+
+    /// returns the mean of the vertices of a polygon
+    pub fn get_center(&self, polygon: usize) -> V {
+        self.polygons[polygon].center
+    }
+
+    /// Tests whether given point is inside the polygon when seen from above
+    pub fn contains(&self, polygon: usize, point: &V) -> bool {
+        let indices = &self.polygons[polygon].vertices;
+        (0..indices.len()).all(|i| {
+            let begin = &self.vertices[indices[i]];
+            let end = &self.vertices[indices[(i + 1) % indices.len()]];
+            cross(begin, end, point) >= T::zero()
+        })
+    }
+
+    /// returns the polygon below or above given point closest to it, `None` if the
+    /// point is off the mesh. Runs in linear time.
+    pub fn locate(&self, point: &V) -> Option<usize> {
+        let mut closest: Option<(usize, T)> = None;
+        for polygon in 0..self.polygons.len() {
+            if !self.contains(polygon, point) {
+                continue;
+            }
+            let distance = self.height_above(polygon, point).abs();
+            if closest.map_or(true, |closest| distance < closest.1) {
+                closest = Some((polygon, distance));
+            }
+        }
+        closest.map(|closest| closest.0)
+    }
+
+    /// returns the polygons an agent passes moving from `start` in the start
+    /// polygon to `goal` in the goal polygon. A* search moves between the middle
+    /// points of the portals.
+    pub fn find_corridor(
+        &self,
+        start_polygon: usize,
+        start: &V,
+        goal_polygon: usize,
+        goal: &V,
+    ) -> Option<Vec<usize>> {
+        let count = self.polygons.len();
+        if start_polygon >= count || goal_polygon >= count {
+            return None;
+        }
+        let mut costs: Vec<Option<T>> = vec![None; count];
+        let mut positions = vec![*start; count];
+        let mut parents: Vec<Option<usize>> = vec![None; count];
+        let mut closed = vec![false; count];
+        let mut open = BinaryHeap::new();
+        costs[start_polygon] = Some(T::zero());
+        open.push(Open {
+            priority: start.distance(goal),
+            index: start_polygon,
+        });
+
+        while let Some(Open { index, .. }) = open.pop() {
+            if closed[index] {
+                continue;
+            }
+            closed[index] = true;
+            if index == goal_polygon {
+                let mut corridor = vec![index];
+                let mut parent = parents[index];
+                while let Some(previous) = parent {
+                    corridor.push(previous);
+                    parent = parents[previous];
+                }
+                corridor.reverse();
+                return Some(corridor);
+            }
+            let cost = costs[index].unwrap();
+            let polygon = &self.polygons[index];
+            for (edge, neighbor) in polygon.neighbors.iter().enumerate() {
+                let neighbor = match *neighbor {
+                    Some(neighbor) if !closed[neighbor] => neighbor,
+                    _ => continue,
+                };
+                let (left, right) = self.portal_of_edge(index, edge);
+                let middle = (left + right).scale(T::one() / (T::one() + T::one()));
+                let mut total = cost + positions[index].distance(&middle);
+                if neighbor == goal_polygon {
+                    total += middle.distance(goal);
+                }
+                if costs[neighbor].map_or(false, |known| known <= total) {
+                    continue;
+                }
+                costs[neighbor] = Some(total);
+                positions[neighbor] = middle;
+                parents[neighbor] = Some(index);
+                open.push(Open {
+                    priority: if neighbor == goal_polygon {
+                        total
+                    } else {
+                        total + middle.distance(goal)
+                    },
+                    index: neighbor,
+                });
+            }
+        }
+        None
+    }
+
+    /// returns the left and right end points of the portals between consecutive
+    /// polygons of a corridor, as seen when moving along it
+    pub fn portals(&self, corridor: &[usize]) -> Vec<(V, V)> {
+        corridor
+            .windows(2)
+            .filter_map(|pair| {
+                let neighbors = &self.polygons[pair[0]].neighbors;
+                neighbors
+                    .iter()
+                    .position(|neighbor| *neighbor == Some(pair[1]))
+                    .map(|edge| self.portal_of_edge(pair[0], edge))
+            })
+            .collect()
+    }
+
+    /// returns the shortest path from start to goal through the corridor found
+    /// by `find_corridor`, `None` if either point is off the mesh or no corridor
+    /// connects them
+    pub fn find_path(&self, start: &V, goal: &V) -> Option<Vec<V>> {
+        let corridor = self.find_corridor(self.locate(start)?, start, self.locate(goal)?, goal)?;
+        Some(funnel(&self.portals(&corridor), *start, *goal))
+    }
+
+    /// returns a path `FollowPath` can follow from start to goal, see `find_path`
+    pub fn find_line_path(&self, start: &V, goal: &V) -> Option<LinePath<T, V>> {
+        let mut waypoints = self.find_path(start, goal)?;
+        if waypoints.len() < 2 {
+            waypoints.push(*goal);
+        }
+        LinePath::new(waypoints, true).ok()
+    }
+
+    /// returns the left and right end points of an edge seen from inside the polygon
+    fn portal_of_edge(&self, polygon: usize, edge: usize) -> (V, V) {
+        let indices = &self.polygons[polygon].vertices;
+        (
+            self.vertices[indices[(edge + 1) % indices.len()]],
+            self.vertices[indices[edge]],
+        )
+    }
+
+    /// returns the distance of a point above the plane of a polygon along the z
+    /// axis, zero on a plane
+    fn height_above(&self, polygon: usize, point: &V) -> T {
+        if V::dimension() < 3 {
+            return T::zero();
+        }
+        let polygon = &self.polygons[polygon];
+        let origin = self.vertices[polygon.vertices[0]];
+        let offset = *point - origin;
+        let normal = polygon.normal;
+        // the normal has a positive z component for polygons with an area from above
+        offset.dot(&normal) / normal.component(2)
+    }
+}
+
+fn invalid(polygon: usize, reason: &str) -> SteeringError {
+    SteeringError::InvalidParameter(format!("polygon {} {}", polygon, reason))
+}
+
+fn centroid<T: Real, V: SteeringVector<T>>(points: &[V]) -> V {
+    let mut sum = V::zero();
+    for point in points.iter() {
+        sum += *point;
+    }
+    sum.scale(T::one() / T::from_usize(points.len()).unwrap())
+}
+
+/// returns the normal of a counter clockwise polygon by Newell's method, zero on
+/// a plane
+fn normal<T: Real, V: SteeringVector<T>>(points: &[V]) -> V {
+    if V::dimension() < 3 {
+        return V::zero();
+    }
+    let mut normal = [T::zero(); 3];
+    for i in 0..points.len() {
+        let current = &points[i];
+        let next = &points[(i + 1) % points.len()];
+        for axis in 0..3 {
+            let (first, second) = ((axis + 1) % 3, (axis + 2) % 3);
+            normal[axis] += (current.component(first) - next.component(first)) *
+                (current.component(second) + next.component(second));
+        }
+    }
+    V::from_components(|axis| normal[axis])
+}
+
+#[cfg(test)]
+mod test {
+    use super::NavMesh;
+    use Path;
+    use nalgebra::{Vector2, Vector3};
+
+    /// L shaped mesh of three unit squares turning left at (1, 1)
+    fn corner() -> NavMesh<f32, Vector2<f32>> {
+        NavMesh::new(
+            vec![
+                Vector2::new(0.0f32, 0.0),
+                Vector2::new(1.0, 0.0),
+                Vector2::new(2.0, 0.0),
+                Vector2::new(0.0, 1.0),
+                Vector2::new(1.0, 1.0),
+                Vector2::new(2.0, 1.0),
+                Vector2::new(1.0, 2.0),
+                Vector2::new(2.0, 2.0),
+            ],
+            // the second square is clockwise
+            vec![vec![0, 1, 4, 3], vec![1, 4, 5, 2], vec![4, 5, 7, 6]],
+        ).unwrap()
+    }
+
+    #[test]
+    fn builds_adjacency() {
+        let mesh = corner();
+        assert_eq!(&[2, 5, 4, 1], mesh.get_polygon(1));
+        assert_eq!(&[None, Some(2), Some(0), None], mesh.get_neighbors(1));
+        assert_eq!(&[None, Some(1), None, None], mesh.get_neighbors(0));
+        assert_eq!(Vector2::new(1.5, 0.5), mesh.get_center(1));
+        assert_eq!(Some(2), mesh.locate(&Vector2::new(1.5, 1.5)));
+        assert_eq!(None, mesh.locate(&Vector2::new(0.5, 1.5)));
+
+        let vertices = vec![Vector2::new(0.0f32, 0.0), Vector2::new(1.0, 0.0)];
+        assert!(NavMesh::new(vertices.clone(), vec![vec![0, 1, 2]]).is_err());
+        assert!(NavMesh::from_triangles(vertices, &[0, 1]).is_err());
+        let concave = vec![
+            Vector2::new(0.0f32, 0.0),
+            Vector2::new(2.0, 0.0),
+            Vector2::new(1.0, 0.5),
+            Vector2::new(1.0, 2.0),
+        ];
+        assert!(NavMesh::new(concave, vec![vec![0, 1, 2, 3]]).is_err());
+    }
+
+    #[test]
+    fn string_pulled_path_around_a_corner() {
+        let mesh = corner();
+        let start = Vector2::new(0.5f32, 0.5);
+        let goal = Vector2::new(1.5, 1.8);
+        assert_eq!(
+            Some(vec![0, 1, 2]),
+            mesh.find_corridor(0, &start, 2, &goal)
+        );
+        assert_eq!(
+            Some(vec![start, Vector2::new(1.0, 1.0), goal]),
+            mesh.find_path(&start, &goal)
+        );
+        let path = mesh.find_line_path(&goal, &Vector2::new(1.2, 1.2)).unwrap();
+        assert_eq!(2, path.get_waypoints().len());
+        assert!(path.is_open());
+        assert!(mesh.find_path(&start, &Vector2::new(0.5, 1.5)).is_none());
+    }
+
+    #[test]
+    fn locates_points_on_overlapping_floors() {
+        let vertices = vec![
+            Vector3::new(0.0f32, 0.0, 0.0),
+            Vector3::new(4.0, 0.0, 0.0),
+            Vector3::new(0.0, 4.0, 0.0),
+            Vector3::new(0.0, 0.0, 3.0),
+            Vector3::new(4.0, 0.0, 3.0),
+            Vector3::new(0.0, 4.0, 5.0),
+        ];
+        let mesh = NavMesh::from_triangles(vertices, &[0, 1, 2, 3, 4, 5]).unwrap();
+        assert_eq!(Some(0), mesh.locate(&Vector3::new(1.0, 1.0, 0.5)));
+        assert_eq!(Some(1), mesh.locate(&Vector3::new(1.0, 1.0, 3.0)));
+        assert_eq!(None, mesh.locate(&Vector3::new(3.0, 3.0, 0.0)));
+    }
+}
@@ -0,0 +1,215 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {Collision, Ray, SteeringError, SteeringVector};
+use super::Obstacle;
+
+use std::marker::PhantomData;
+
+/// Axis aligned rectangle on a plane or box in space
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Aabb<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    min: V,
+    max: V,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Real, V: SteeringVector<T>> Aabb<T, V> {
+    /// Creates a box between given corners, every component of `min` must not be
+    /// greater than the same component of `max`
+    pub fn new(min: V, max: V) -> Result<Aabb<T, V>, SteeringError> {
+        if (0..V::dimension()).any(|axis| !(min.component(axis) <= max.component(axis))) {
+            return Err(SteeringError::InvalidParameter(
+                "min corner of a box must not be greater than its max corner".to_string(),
+            ));
+        }
+        Ok(Aabb {
+            min: min,
+            max: max,
+            phantom: PhantomData,
+        })
+    }
+
+    /// returns the corner with the smallest components
+    pub fn get_min(&self) -> V {
+        self.min
+    }
+
+    /// returns the corner with the largest components
+    pub fn get_max(&self) -> V {
+        self.max
+    }
+
+    /// Tests whether given point is inside the box or on its boundary
+    pub fn contains(&self, point: &V) -> bool {
+        (0..V::dimension()).all(|axis| {
+            let value = point.component(axis);
+            value >= self.min.component(axis) && value <= self.max.component(axis)
+        })
+    }
+
+    /// returns the distance of the point from the box along each axis, negative
+    /// inside the box
+    fn offsets(&self, point: &V) -> V {
+        let half = (self.max - self.min).scale(half());
+        let center = self.get_center();
+        V::from_components(|axis| {
+            (point.component(axis) - center.component(axis)).abs() - half.component(axis)
+        })
+    }
+}
+
+fn half<T: Real>() -> T {
+    T::one() / (T::one() + T::one())
+}
+
+impl<T: Real, V: SteeringVector<T>> Obstacle<T, V> for Aabb<T, V> {
+    fn get_center(&self) -> V {
+        (self.min + self.max).scale(half())
+    }
+
+    fn get_bounding_radius(&self) -> T {
+        self.min.distance(&self.max) * half()
+    }
+
+    fn closest_point(&self, point: &V) -> V {
+        if !self.contains(point) {
+            return V::from_components(|axis| {
+                let value = point.component(axis);
+                if value < self.min.component(axis) {
+                    self.min.component(axis)
+                } else if value > self.max.component(axis) {
+                    self.max.component(axis)
+                } else {
+                    value
+                }
+            });
+        }
+        // move the point to the face closest to it
+        let offsets = self.offsets(point);
+        let mut closest_axis = 0;
+        for axis in 1..V::dimension() {
+            if offsets.component(axis) > offsets.component(closest_axis) {
+                closest_axis = axis;
+            }
+        }
+        let center = self.get_center();
+        V::from_components(|axis| if axis != closest_axis {
+            point.component(axis)
+        } else if point.component(axis) < center.component(axis) {
+            self.min.component(axis)
+        } else {
+            self.max.component(axis)
+        })
+    }
+
+    fn signed_distance(&self, point: &V) -> T {
+        let offsets = self.offsets(point);
+        let mut outside = T::zero();
+        let mut inside = offsets.component(0);
+        for axis in 0..V::dimension() {
+            let offset = offsets.component(axis);
+            if offset > T::zero() {
+                outside += offset * offset;
+            }
+            if offset > inside {
+                inside = offset;
+            }
+        }
+        if inside > T::zero() {
+            outside.sqrt()
+        } else {
+            inside
+        }
+    }
+
+    fn intersect_ray(&self, ray: &Ray<T, V>) -> Option<Collision<T, V>> {
+        if self.contains(&ray.start) {
+            return None;
+        }
+        let direction = ray.end - ray.start;
+        let mut enter = T::zero();
+        let mut exit = T::one();
+        let mut enter_axis = None;
+        for axis in 0..V::dimension() {
+            let start = ray.start.component(axis);
+            let step = direction.component(axis);
+            let (min, max) = (self.min.component(axis), self.max.component(axis));
+            if step.is_zero() {
+                if start < min || start > max {
+                    return None;
+                }
+                continue;
+            }
+            let (mut near, mut far) = ((min - start) / step, (max - start) / step);
+            if near > far {
+                ::std::mem::swap(&mut near, &mut far);
+            }
+            if near > enter {
+                enter = near;
+                enter_axis = Some(axis);
+            }
+            if far < exit {
+                exit = far;
+            }
+            if enter > exit {
+                return None;
+            }
+        }
+        enter_axis.map(|enter_axis| {
+            let sign = if direction.component(enter_axis) > T::zero() {
+                -T::one()
+            } else {
+                T::one()
+            };
+            let normal = V::from_components(|axis| if axis == enter_axis {
+                sign
+            } else {
+                T::zero()
+            });
+            Collision::new(ray.start + direction.scale(enter), normal)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Aabb;
+    use super::super::Obstacle;
+    use Ray;
+    use nalgebra::{Vector2, Vector3};
+
+    #[test]
+    fn box_queries() {
+        let aabb = Aabb::new(Vector2::new(0.0f32, 0.0), Vector2::new(4.0, 2.0)).unwrap();
+        assert!(Aabb::new(Vector2::new(1.0f32, 0.0), Vector2::new(0.0, 1.0)).is_err());
+        assert_eq!(-0.5, aabb.signed_distance(&Vector2::new(1.0, 1.5)));
+        assert_eq!(5.0, aabb.signed_distance(&Vector2::new(7.0, 6.0)));
+        assert_eq!(Vector2::new(1.0, 2.0), aabb.closest_point(&Vector2::new(1.0, 1.5)));
+        assert_eq!(Vector2::new(4.0, 2.0), aabb.closest_point(&Vector2::new(7.0, 6.0)));
+
+        let collision = aabb.intersect_ray(
+            &Ray::new(Vector2::new(-2.0, 1.0), Vector2::new(2.0, 1.0)),
+        ).unwrap();
+        assert_eq!(Vector2::new(0.0, 1.0), collision.point);
+        assert_eq!(Vector2::new(-1.0, 0.0), collision.normal);
+        assert!(aabb.intersect_ray(&Ray::new(Vector2::new(1.0, 1.0), Vector2::new(9.0, 1.0)))
+            .is_none());
+        assert!(aabb.intersect_ray(&Ray::new(Vector2::new(-2.0, 3.0), Vector2::new(9.0, 3.0)))
+            .is_none());
+
+        let spot = aabb.hiding_spot(&Vector2::new(-8.0, 1.0), 1.0);
+        assert!((spot - Vector2::new(5.0, 1.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn box_in_space() {
+        let aabb = Aabb::new(Vector3::new(0.0f32, 0.0, 0.0), Vector3::new(2.0, 2.0, 2.0)).unwrap();
+        let collision = aabb.intersect_ray(
+            &Ray::new(Vector3::new(1.0, 1.0, 5.0), Vector3::new(1.0, 1.0, -5.0)),
+        ).unwrap();
+        assert_eq!(Vector3::new(1.0, 1.0, 2.0), collision.point);
+        assert_eq!(Vector3::new(0.0, 0.0, 1.0), collision.normal);
+        assert_eq!(3.0, aabb.signed_distance(&Vector3::new(1.0, 1.0, 5.0)));
+    }
+}
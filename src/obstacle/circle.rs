@@ -0,0 +1,89 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {Collision, Ray, SteeringVector};
+use super::Obstacle;
+
+/// Circle obstacle on a plane or sphere obstacle in space
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Circle<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// center of the circle
+    pub center: V,
+    /// radius of the circle
+    pub radius: T,
+}
+
+impl<T: Real, V: SteeringVector<T>> Circle<T, V> {
+    /// Creates a circle around given center
+    pub fn new(center: V, radius: T) -> Circle<T, V> {
+        Circle {
+            center: center,
+            radius: radius,
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Obstacle<T, V> for Circle<T, V> {
+    fn get_center(&self) -> V {
+        self.center
+    }
+
+    fn get_bounding_radius(&self) -> T {
+        self.radius
+    }
+
+    fn closest_point(&self, point: &V) -> V {
+        self.center + (*point - self.center).normalize_or_zero().scale(self.radius)
+    }
+
+    fn signed_distance(&self, point: &V) -> T {
+        point.distance(&self.center) - self.radius
+    }
+
+    fn intersect_ray(&self, ray: &Ray<T, V>) -> Option<Collision<T, V>> {
+        let direction = ray.end - ray.start;
+        let offset = ray.start - self.center;
+        let a = direction.norm_squared();
+        let c = offset.norm_squared() - self.radius * self.radius;
+        if a.is_zero() || c <= T::zero() {
+            return None;
+        }
+        let half_b = offset.dot(&direction);
+        let discriminant = half_b * half_b - a * c;
+        if discriminant < T::zero() {
+            return None;
+        }
+        let fraction = (-half_b - discriminant.sqrt()) / a;
+        if fraction < T::zero() || fraction > T::one() {
+            return None;
+        }
+        let point = ray.start + direction.scale(fraction);
+        Some(Collision::new(point, (point - self.center).normalize_or_zero()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Circle;
+    use super::super::Obstacle;
+    use Ray;
+    use nalgebra::Vector2;
+
+    #[test]
+    fn ray_enters_circle() {
+        let circle = Circle::new(Vector2::new(5.0f32, 0.0), 1.0);
+        let collision = circle
+            .intersect_ray(&Ray::new(Vector2::new(0.0, 0.0), Vector2::new(10.0, 0.0)))
+            .unwrap();
+        assert_eq!(Vector2::new(4.0, 0.0), collision.point);
+        assert_eq!(Vector2::new(-1.0, 0.0), collision.normal);
+        assert!(circle
+            .intersect_ray(&Ray::new(Vector2::new(0.0, 0.0), Vector2::new(3.0, 0.0)))
+            .is_none());
+        assert!(circle
+            .intersect_ray(&Ray::new(Vector2::new(5.0, 0.0), Vector2::new(10.0, 0.0)))
+            .is_none());
+        assert_eq!(-1.0, circle.signed_distance(&Vector2::new(5.0, 0.0)));
+        assert_eq!(Vector2::new(5.0, 1.0), circle.closest_point(&Vector2::new(5.0, 3.0)));
+    }
+}
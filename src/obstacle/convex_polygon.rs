@@ -0,0 +1,210 @@
+use nalgebra::Vector2;
+use alga::general::Real;
+use {Collision, Ray, SteeringError, SteeringVector};
+use super::{cross, Obstacle};
+
+/// Convex polygon on a plane
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConvexPolygon<T: Real> {
+    /// vertices in counter clockwise order
+    vertices: Vec<Vector2<T>>,
+    center: Vector2<T>,
+    bounding_radius: T,
+}
+
+impl<T: Real> ConvexPolygon<T> {
+    /// Creates a polygon of at least three vertices listed in either direction
+    pub fn new(mut vertices: Vec<Vector2<T>>) -> Result<ConvexPolygon<T>, SteeringError> {
+        if vertices.len() < 3 {
+            return Err(SteeringError::InvalidParameter(
+                "polygon needs at least three vertices".to_string(),
+            ));
+        }
+        let count = vertices.len();
+        let mut area = T::zero();
+        for index in 0..count {
+            area += cross(&vertices[index], &vertices[(index + 1) % count]);
+        }
+        if area < T::zero() {
+            vertices.reverse();
+        }
+        for index in 0..count {
+            let edge = vertices[(index + 1) % count] - vertices[index];
+            let next = vertices[(index + 2) % count] - vertices[(index + 1) % count];
+            if cross(&edge, &next) < T::zero() || area.is_zero() {
+                return Err(SteeringError::InvalidParameter(
+                    "polygon must be convex and not degenerate".to_string(),
+                ));
+            }
+        }
+        let mut center = Vector2::new(T::zero(), T::zero());
+        for vertex in vertices.iter() {
+            center += *vertex;
+        }
+        center = center.scale(T::one() / T::from_usize(count).unwrap());
+        let mut bounding_radius = T::zero();
+        for vertex in vertices.iter() {
+            let distance = SteeringVector::distance(vertex, &center);
+            if distance > bounding_radius {
+                bounding_radius = distance;
+            }
+        }
+        Ok(ConvexPolygon {
+            vertices: vertices,
+            center: center,
+            bounding_radius: bounding_radius,
+        })
+    }
+
+    /// returns the vertices in counter clockwise order
+    pub fn get_vertices(&self) -> &[Vector2<T>] {
+        &self.vertices
+    }
+
+    /// returns the ends of every edge and its unit normal pointing outwards
+    pub fn edges(&self) -> Vec<(Vector2<T>, Vector2<T>, Vector2<T>)> {
+        let count = self.vertices.len();
+        (0..count)
+            .map(|index| {
+                let begin = self.vertices[index];
+                let end = self.vertices[(index + 1) % count];
+                let edge = end - begin;
+                let normal = SteeringVector::normalize_or_zero(&Vector2::new(edge.y, -edge.x));
+                (begin, end, normal)
+            })
+            .collect()
+    }
+
+    /// Tests whether given point is inside the polygon or on its boundary
+    pub fn contains(&self, point: &Vector2<T>) -> bool {
+        self.edges().iter().all(|&(begin, _, normal)| {
+            SteeringVector::dot(&normal, &(*point - begin)) <= T::zero()
+        })
+    }
+}
+
+impl<T: Real> Obstacle<T, Vector2<T>> for ConvexPolygon<T> {
+    fn get_center(&self) -> Vector2<T> {
+        self.center
+    }
+
+    fn get_bounding_radius(&self) -> T {
+        self.bounding_radius
+    }
+
+    fn closest_point(&self, point: &Vector2<T>) -> Vector2<T> {
+        let mut closest: Option<(Vector2<T>, T)> = None;
+        for &(begin, end, _) in self.edges().iter() {
+            let candidate = Ray::new(begin, end).closest_point(point);
+            let square_distance = SteeringVector::norm_squared(&(candidate - *point));
+            if closest.map_or(true, |closest| square_distance < closest.1) {
+                closest = Some((candidate, square_distance));
+            }
+        }
+        closest.map_or(self.center, |closest| closest.0)
+    }
+
+    fn signed_distance(&self, point: &Vector2<T>) -> T {
+        let distance = SteeringVector::distance(point, &self.closest_point(point));
+        if self.contains(point) {
+            -distance
+        } else {
+            distance
+        }
+    }
+
+    fn intersect_ray(&self, ray: &Ray<T, Vector2<T>>) -> Option<Collision<T, Vector2<T>>> {
+        if self.contains(&ray.start) {
+            return None;
+        }
+        let direction = ray.end - ray.start;
+        let mut enter = (T::zero(), None);
+        let mut exit = T::one();
+        for &(begin, _, normal) in self.edges().iter() {
+            let denominator = SteeringVector::dot(&normal, &direction);
+            let numerator = SteeringVector::dot(&normal, &(begin - ray.start));
+            if denominator.is_zero() {
+                if numerator < T::zero() {
+                    return None;
+                }
+                continue;
+            }
+            let fraction = numerator / denominator;
+            if denominator < T::zero() {
+                if fraction >= enter.0 {
+                    enter = (fraction, Some(normal));
+                }
+            } else if fraction < exit {
+                exit = fraction;
+            }
+            if enter.0 > exit {
+                return None;
+            }
+        }
+        enter
+            .1
+            .map(|normal| Collision::new(ray.start + direction.scale(enter.0), normal))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ConvexPolygon;
+    use super::super::Obstacle;
+    use Ray;
+    use nalgebra::Vector2;
+
+    fn triangle() -> ConvexPolygon<f32> {
+        // clockwise on purpose
+        ConvexPolygon::new(vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(0.0, 4.0),
+            Vector2::new(4.0, 0.0),
+        ]).unwrap()
+    }
+
+    #[test]
+    fn rejects_concave_polygon() {
+        assert!(ConvexPolygon::new(vec![Vector2::new(0.0f32, 0.0), Vector2::new(1.0, 0.0)])
+            .is_err());
+        assert!(ConvexPolygon::new(vec![
+            Vector2::new(0.0f32, 0.0),
+            Vector2::new(4.0, 0.0),
+            Vector2::new(1.0, 1.0),
+            Vector2::new(0.0, 4.0),
+        ]).is_err());
+    }
+
+    #[test]
+    fn polygon_queries() {
+        let polygon = triangle();
+        assert_eq!(
+            &[
+                Vector2::new(4.0, 0.0),
+                Vector2::new(0.0, 4.0),
+                Vector2::new(0.0, 0.0),
+            ],
+            polygon.get_vertices()
+        );
+        assert_eq!(-1.0, polygon.signed_distance(&Vector2::new(1.0, 1.0)));
+        assert_eq!(2.0, polygon.signed_distance(&Vector2::new(-2.0, 1.0)));
+        assert_eq!(Vector2::new(0.0, 1.0), polygon.closest_point(&Vector2::new(-2.0, 1.0)));
+
+        let collision = polygon
+            .intersect_ray(&Ray::new(Vector2::new(1.0, -3.0), Vector2::new(1.0, 3.0)))
+            .unwrap();
+        assert_eq!(Vector2::new(1.0, 0.0), collision.point);
+        assert_eq!(Vector2::new(0.0, -1.0), collision.normal);
+        let collision = polygon
+            .intersect_ray(&Ray::new(Vector2::new(4.0, 4.0), Vector2::new(0.0, 0.0)))
+            .unwrap();
+        assert!((collision.point - Vector2::new(2.0, 2.0)).norm() < 1e-6);
+        assert!(polygon
+            .intersect_ray(&Ray::new(Vector2::new(5.0, -1.0), Vector2::new(5.0, 5.0)))
+            .is_none());
+        assert!(polygon
+            .intersect_ray(&Ray::new(Vector2::new(1.0, 1.0), Vector2::new(9.0, 1.0)))
+            .is_none());
+    }
+}
@@ -0,0 +1,96 @@
+//! Static obstacle shapes answering geometric queries without a physics engine.
+//! `Circle` and `Aabb` work on a plane and in space, `Segment` walls and
+//! `ConvexPolygon`s lie on a plane.
+mod circle;
+mod aabb;
+mod segment;
+mod convex_polygon;
+
+pub use self::circle::Circle;
+pub use self::aabb::Aabb;
+pub use self::segment::Segment;
+pub use self::convex_polygon::ConvexPolygon;
+
+use nalgebra::{Vector2, Vector3};
+use alga::general::Real;
+use {Collision, Ray, SteeringVector};
+
+use std::rc::Rc;
+
+/// Static shape agents should not run into
+pub trait Obstacle<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// returns the center of the bounding circle of the shape
+    fn get_center(&self) -> V;
+
+    /// returns the radius of the bounding circle of the shape
+    fn get_bounding_radius(&self) -> T;
+
+    /// returns the point of the shape boundary closest to given point
+    fn closest_point(&self, point: &V) -> V;
+
+    /// returns the distance between given point and the shape boundary, negative if
+    /// the point is inside the shape
+    fn signed_distance(&self, point: &V) -> T;
+
+    /// returns where the ray enters the shape, rays starting inside the shape do
+    /// not collide with it
+    fn intersect_ray(&self, ray: &Ray<T, V>) -> Option<Collision<T, V>>;
+
+    /// returns the point `distance_from_boundary` behind the shape as seen from the
+    /// hunter, on the line from the hunter through the center of the shape
+    fn hiding_spot(&self, hunter: &V, distance_from_boundary: T) -> V {
+        let center = self.get_center();
+        let direction = (center - *hunter).normalize_or_zero();
+        let outside = center + direction.scale(self.get_bounding_radius() + T::one());
+        match self.intersect_ray(&Ray::new(outside, center)) {
+            Some(collision) => collision.point + direction.scale(distance_from_boundary),
+            None => center + direction.scale(distance_from_boundary),
+        }
+    }
+}
+
+/// Collection of obstacles queried by behaviors such as `Hide`
+pub trait ObstacleSet<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// Calls given function for every obstacle whose boundary is within given
+    /// distance of the point
+    fn find_obstacles(&self, point: &V, radius: T, callback: &mut FnMut(&Obstacle<T, V>));
+}
+
+impl<T: Real, V: SteeringVector<T>> ObstacleSet<T, V> for Vec<Rc<Obstacle<T, V>>> {
+    fn find_obstacles(&self, point: &V, radius: T, callback: &mut FnMut(&Obstacle<T, V>)) {
+        for obstacle in self.iter() {
+            if obstacle.signed_distance(point) <= radius {
+                callback(&**obstacle);
+            }
+        }
+    }
+}
+
+/// Every obstacle can be indexed by the obstacle trees of the `spatial` module
+impl<T, V, O> ::spatial::Shape<T, V> for O
+where
+    T: Real,
+    V: SteeringVector<T>,
+    O: ?Sized + Obstacle<T, V>,
+{
+    fn get_center(&self) -> V {
+        Obstacle::get_center(self)
+    }
+
+    fn get_bounding_radius(&self) -> T {
+        Obstacle::get_bounding_radius(self)
+    }
+
+    fn signed_distance(&self, point: &V) -> T {
+        Obstacle::signed_distance(self, point)
+    }
+
+    fn intersect_ray(&self, ray: &Ray<T, V>) -> Option<Collision<T, V>> {
+        Obstacle::intersect_ray(self, ray)
+    }
+}
+
+/// returns the z component of the cross product of two planar vectors
+fn cross<T: Real>(first: &Vector2<T>, second: &Vector2<T>) -> T {
+    first.x * second.y - first.y * second.x
+}
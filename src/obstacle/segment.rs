@@ -0,0 +1,99 @@
+use nalgebra::Vector2;
+use alga::general::Real;
+use {Collision, Ray, SteeringVector};
+use super::{cross, Obstacle};
+
+/// Wall between two points of a plane. A wall has no inside, its signed distance is
+/// never negative and rays hit it from both sides.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Segment<T: Real> {
+    /// first end of the wall
+    pub begin: Vector2<T>,
+    /// second end of the wall
+    pub end: Vector2<T>,
+}
+
+impl<T: Real> Segment<T> {
+    /// Creates a wall between given points
+    pub fn new(begin: Vector2<T>, end: Vector2<T>) -> Segment<T> {
+        Segment {
+            begin: begin,
+            end: end,
+        }
+    }
+
+    /// returns the unit normal of the wall turned to the left when looking from
+    /// `begin` to `end`
+    pub fn get_normal(&self) -> Vector2<T> {
+        let direction = self.end - self.begin;
+        SteeringVector::normalize_or_zero(&Vector2::new(-direction.y, direction.x))
+    }
+}
+
+impl<T: Real> Obstacle<T, Vector2<T>> for Segment<T> {
+    fn get_center(&self) -> Vector2<T> {
+        (self.begin + self.end).scale(T::one() / (T::one() + T::one()))
+    }
+
+    fn get_bounding_radius(&self) -> T {
+        SteeringVector::distance(&self.begin, &self.end) / (T::one() + T::one())
+    }
+
+    fn closest_point(&self, point: &Vector2<T>) -> Vector2<T> {
+        Ray::new(self.begin, self.end).closest_point(point)
+    }
+
+    fn signed_distance(&self, point: &Vector2<T>) -> T {
+        SteeringVector::distance(point, &self.closest_point(point))
+    }
+
+    fn intersect_ray(&self, ray: &Ray<T, Vector2<T>>) -> Option<Collision<T, Vector2<T>>> {
+        let direction = ray.end - ray.start;
+        let wall = self.end - self.begin;
+        let denominator = cross(&direction, &wall);
+        if denominator.is_zero() {
+            return None;
+        }
+        let offset = self.begin - ray.start;
+        let fraction = cross(&offset, &wall) / denominator;
+        let wall_fraction = cross(&offset, &direction) / denominator;
+        if fraction < T::zero() || fraction > T::one() || wall_fraction < T::zero() ||
+            wall_fraction > T::one()
+        {
+            return None;
+        }
+        let mut normal = self.get_normal();
+        if SteeringVector::dot(&normal, &direction) > T::zero() {
+            normal = -normal;
+        }
+        Some(Collision::new(ray.start + direction.scale(fraction), normal))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Segment;
+    use super::super::Obstacle;
+    use Ray;
+    use nalgebra::Vector2;
+
+    #[test]
+    fn wall_queries() {
+        let wall = Segment::new(Vector2::new(0.0f32, 0.0), Vector2::new(0.0, 4.0));
+        assert_eq!(Vector2::new(-1.0, 0.0), wall.get_normal());
+        assert_eq!(3.0, wall.signed_distance(&Vector2::new(3.0, 2.0)));
+        assert_eq!(Vector2::new(0.0, 4.0), wall.closest_point(&Vector2::new(1.0, 7.0)));
+
+        let collision = wall
+            .intersect_ray(&Ray::new(Vector2::new(2.0, 1.0), Vector2::new(-2.0, 1.0)))
+            .unwrap();
+        assert_eq!(Vector2::new(0.0, 1.0), collision.point);
+        assert_eq!(Vector2::new(1.0, 0.0), collision.normal);
+        assert!(wall.intersect_ray(&Ray::new(Vector2::new(2.0, 5.0), Vector2::new(-2.0, 5.0)))
+            .is_none());
+
+        let spot = wall.hiding_spot(&Vector2::new(5.0, 2.0), 1.0);
+        assert!((spot - Vector2::new(-1.0, 2.0)).norm() < 1e-5);
+    }
+}
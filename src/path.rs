@@ -0,0 +1,34 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use SteeringVector;
+
+/// A path agents can follow. Points on the path are addressed by their distance
+/// from the start of the path measured along the path.
+pub trait Path<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// returns the distance along the path of the point of the path closest to
+    /// given position
+    fn calculate_distance(&self, position: &V) -> T;
+
+    /// returns the point at given distance along the path, distances beyond the
+    /// ends are clamped on open paths and wrapped around on closed ones
+    fn calculate_target_position(&self, distance: T) -> V;
+
+    /// returns the curvature at given distance along the path, the inverse of the
+    /// radius of the circle fitting the path there. Paths made of straight segments
+    /// have none.
+    fn calculate_curvature(&self, _distance: T) -> T {
+        T::zero()
+    }
+
+    /// returns the length of the path
+    fn get_length(&self) -> T;
+
+    /// tests whether the path has two ends, a closed path is a loop
+    fn is_open(&self) -> bool;
+
+    /// returns the first point of the path
+    fn get_start_point(&self) -> V;
+
+    /// returns the last point of the path
+    fn get_end_point(&self) -> V;
+}
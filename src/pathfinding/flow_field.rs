@@ -0,0 +1,575 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {SteeringError, SteeringVector};
+use search::Open;
+use super::{Grid, GridCell};
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::collections::HashSet;
+
+/// How a `FlowField` integrates the costs of the cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum IntegrationMethod {
+    /// cheapest paths over moves between neighbor cells, like `GridPathfinder`
+    Dijkstra,
+    /// arrival times of a wave front spreading from the goals, solving the eikonal
+    /// equation. Directions are not limited to the moves between neighbor cells.
+    Eikonal,
+}
+
+/// Builds a `FlowField` from a cost grid and the goal cells
+#[derive(Clone)]
+pub struct FlowFieldBuilder<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    grid: Option<Grid<T, V>>,
+    goals: Option<Vec<GridCell>>,
+    method: Option<IntegrationMethod>,
+    diagonal: Option<bool>,
+}
+
+impl<T: Real, V: SteeringVector<T>> FlowFieldBuilder<T, V> {
+    /// returns a builder with no fields set
+    pub fn new() -> FlowFieldBuilder<T, V> {
+        FlowFieldBuilder {
+            grid: None,
+            goals: None,
+            method: None,
+            diagonal: None,
+        }
+    }
+
+    /// Grid of cell costs
+    pub fn grid(&self, grid: Grid<T, V>) -> FlowFieldBuilder<T, V> {
+        let mut builder = self.clone();
+        builder.grid = Some(grid);
+        builder
+    }
+
+    /// Walkable cells the field leads to, at least one is needed
+    pub fn goals(&self, goals: Vec<GridCell>) -> FlowFieldBuilder<T, V> {
+        let mut builder = self.clone();
+        builder.goals = Some(goals);
+        builder
+    }
+
+    /// Integration method, `Eikonal` by default
+    pub fn method(&self, method: IntegrationMethod) -> FlowFieldBuilder<T, V> {
+        let mut builder = self.clone();
+        builder.method = Some(method);
+        builder
+    }
+
+    /// Allow diagonal moves for `Dijkstra` integration, true by default
+    pub fn diagonal(&self, diagonal: bool) -> FlowFieldBuilder<T, V> {
+        let mut builder = self.clone();
+        builder.diagonal = Some(diagonal);
+        builder
+    }
+
+    /// Integrates the costs and derives the directions
+    pub fn build(&self) -> Result<FlowField<T, V>, SteeringError> {
+        let grid = self.grid
+            .clone()
+            .ok_or_else(|| SteeringError::InvalidParameter("grid must be set".to_string()))?;
+        let goals = self.goals
+            .clone()
+            .ok_or_else(|| SteeringError::InvalidParameter("goals must be set".to_string()))?;
+        let (width, height, depth) = grid.get_size();
+        let mut field = FlowField {
+            grid: grid,
+            goals: Vec::new(),
+            method: self.method.unwrap_or(IntegrationMethod::Eikonal),
+            diagonal: self.diagonal.unwrap_or(true),
+            integration: vec![None; width * height * depth],
+            directions: vec![V::zero(); width * height * depth],
+        };
+        field.set_goals(goals)?;
+        Ok(field)
+    }
+}
+
+/// Directions towards the closest goal for every cell of a grid, shared by any
+/// number of agents. The integration field holds the cost of reaching a goal from
+/// every cell, directions descend it.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct FlowField<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    grid: Grid<T, V>,
+    goals: Vec<GridCell>,
+    method: IntegrationMethod,
+    diagonal: bool,
+    /// cost of reaching a goal from every cell, `None` if no goal can be reached
+    integration: Vec<Option<T>>,
+    directions: Vec<V>,
+}
+
+impl<T: Real, V: SteeringVector<T>> FlowField<T, V> {
+    /// returns the grid of cell costs
+    pub fn get_grid(&self) -> &Grid<T, V> {
+        &self.grid
+    }
+
+    /// returns the goal cells
+    pub fn get_goals(&self) -> &[GridCell] {
+        &self.goals
+    }
+
+    /// returns the integration method
+    pub fn get_method(&self) -> IntegrationMethod {
+        self.method
+    }
+
+    /// Tests whether given cell is a goal
+    pub fn is_goal(&self, cell: GridCell) -> bool {
+        self.goals.contains(&cell)
+    }
+
+    /// returns the cost of reaching a goal from given cell, `None` for blocked cells,
+    /// cells no goal can be reached from and cells outside the grid
+    pub fn get_integration(&self, cell: GridCell) -> Option<T> {
+        self.index(cell).and_then(|index| self.integration[index])
+    }
+
+    /// returns the unit direction of a cell, zero at goals and where no goal can be
+    /// reached
+    pub fn get_direction(&self, cell: GridCell) -> V {
+        self.index(cell)
+            .map_or(V::zero(), |index| self.directions[index])
+    }
+
+    /// returns the unit direction at given position, interpolated between the
+    /// directions of the surrounding cell centers. Interpolation is bilinear on a
+    /// plane and trilinear in space, cells no goal can be reached from are left
+    /// out.
+    pub fn sample(&self, position: &V) -> V {
+        if !position.is_finite() {
+            return V::zero();
+        }
+        let half = T::one() / (T::one() + T::one());
+        let origin = self.grid.get_origin();
+        let mut base = [0isize; 3];
+        let mut fraction = [T::zero(); 3];
+        for axis in 0..V::dimension() {
+            let coordinate = (position.component(axis) - origin.component(axis)) /
+                self.grid.get_cell_size() - half;
+            let floor = coordinate.floor();
+            fraction[axis] = coordinate - floor;
+            let floor: f64 = floor.to_subset().unwrap_or(-1.0);
+            base[axis] = floor.max(-1.0).min(::std::isize::MAX as f64) as isize;
+        }
+        let mut sum = V::zero();
+        let mut total = T::zero();
+        for corner in 0..(1 << V::dimension()) {
+            let mut weight = T::one();
+            let mut coordinates = base;
+            for axis in 0..V::dimension() {
+                if corner & (1 << axis) != 0 {
+                    weight *= fraction[axis];
+                    coordinates[axis] += 1;
+                } else {
+                    weight *= T::one() - fraction[axis];
+                }
+            }
+            if coordinates.iter().any(|coordinate| *coordinate < 0) {
+                continue;
+            }
+            let cell = GridCell::new(
+                coordinates[0] as usize,
+                coordinates[1] as usize,
+                coordinates[2] as usize,
+            );
+            if let Some(index) = self.index(cell) {
+                if self.integration[index].is_some() {
+                    sum += self.directions[index].scale(weight);
+                    total += weight;
+                }
+            }
+        }
+        if total > T::zero() {
+            sum.normalize_or_zero()
+        } else {
+            V::zero()
+        }
+    }
+
+    /// Replaces the goals and integrates the whole grid again, goals must be
+    /// walkable cells
+    pub fn set_goals(&mut self, goals: Vec<GridCell>) -> Result<(), SteeringError> {
+        if goals.is_empty() {
+            return Err(SteeringError::InvalidParameter(
+                "flow field needs at least one goal".to_string(),
+            ));
+        }
+        if let Some(goal) = goals.iter().find(|goal| !self.grid.is_walkable(**goal)) {
+            return Err(SteeringError::InvalidParameter(
+                format!("goal {:?} is not a walkable cell of the grid", goal),
+            ));
+        }
+        self.goals = goals;
+        for value in self.integration.iter_mut() {
+            *value = None;
+        }
+        let mut open = BinaryHeap::new();
+        for goal in self.goals.iter() {
+            let index = self.index(*goal).unwrap();
+            self.integration[index] = Some(T::zero());
+            open.push(Open {
+                priority: T::zero(),
+                index: index,
+            });
+        }
+        self.propagate(open, &mut Vec::new());
+        for index in 0..self.directions.len() {
+            self.directions[index] = self.direction(index);
+        }
+        Ok(())
+    }
+
+    /// Changes the cost multiplier of a cell, `None` blocks the cell. Only the cells
+    /// whose cost of reaching a goal may change are integrated again.
+    pub fn set_cost(&mut self, cell: GridCell, cost: Option<T>) -> Result<(), SteeringError> {
+        self.grid.set_cost(cell, cost)?;
+
+        // cells around the changed one may lose or gain diagonal moves, cells
+        // reached from them towards larger costs may depend on them
+        let mut region = HashSet::new();
+        let mut stack: Vec<GridCell> = self.block(cell);
+        while let Some(current) = stack.pop() {
+            let index = self.index(current).unwrap();
+            if !region.insert(index) {
+                continue;
+            }
+            let value = match self.integration[index] {
+                Some(value) => value,
+                None => continue,
+            };
+            for neighbor in self.block(current) {
+                let neighbor_value = self.integration[self.index(neighbor).unwrap()];
+                if neighbor_value.map_or(false, |neighbor_value| neighbor_value > value) {
+                    stack.push(neighbor);
+                }
+            }
+        }
+
+        for index in region.iter() {
+            self.integration[*index] = None;
+        }
+        let mut open = BinaryHeap::new();
+        for index in region.iter() {
+            let current = self.cell(*index);
+            let value = if self.is_goal(current) && self.grid.is_walkable(current) {
+                Some(T::zero())
+            } else {
+                self.solve(current)
+            };
+            if let Some(value) = value {
+                self.integration[*index] = Some(value);
+                open.push(Open {
+                    priority: value,
+                    index: *index,
+                });
+            }
+        }
+        let mut changed: Vec<usize> = region.into_iter().collect();
+        self.propagate(open, &mut changed);
+
+        let mut touched = HashSet::new();
+        for index in changed {
+            for neighbor in self.block(self.cell(index)) {
+                touched.insert(self.index(neighbor).unwrap());
+            }
+        }
+        for index in touched {
+            self.directions[index] = self.direction(index);
+        }
+        Ok(())
+    }
+
+    /// Lowers the integration of the cells around the open ones until no cell can be
+    /// reached cheaper, collects the indices of the lowered cells
+    fn propagate(&mut self, mut open: BinaryHeap<Open<T>>, changed: &mut Vec<usize>) {
+        while let Some(Open { priority, index }) = open.pop() {
+            if self.integration[index].map_or(true, |value| value < priority) {
+                continue;
+            }
+            for (neighbor, _) in self.neighbors(self.cell(index)) {
+                if self.is_goal(neighbor) {
+                    continue;
+                }
+                let neighbor_index = self.index(neighbor).unwrap();
+                let candidate = match self.solve(neighbor) {
+                    Some(candidate) => candidate,
+                    None => continue,
+                };
+                if self.integration[neighbor_index].map_or(true, |value| candidate < value) {
+                    self.integration[neighbor_index] = Some(candidate);
+                    changed.push(neighbor_index);
+                    open.push(Open {
+                        priority: candidate,
+                        index: neighbor_index,
+                    });
+                }
+            }
+        }
+    }
+
+    /// returns the cost of reaching a goal from a cell through its neighbors
+    fn solve(&self, cell: GridCell) -> Option<T> {
+        let cost = self.grid.get_cost(cell)?;
+        let two = T::one() + T::one();
+        match self.method {
+            IntegrationMethod::Dijkstra => {
+                let mut best: Option<T> = None;
+                for (neighbor, length) in self.neighbors(cell) {
+                    if let Some(value) = self.get_integration(neighbor) {
+                        let neighbor_cost = self.grid.get_cost(neighbor).unwrap();
+                        let candidate = value + length * (cost + neighbor_cost) / two;
+                        if best.map_or(true, |best| candidate < best) {
+                            best = Some(candidate);
+                        }
+                    }
+                }
+                best
+            }
+            IntegrationMethod::Eikonal => {
+                // lowest integration of the two neighbors along every axis
+                let mut values: Vec<T> = (0..3)
+                    .filter_map(|axis| self.upwind(cell, axis).map(|(_, value)| value))
+                    .collect();
+                if values.is_empty() {
+                    return None;
+                }
+                values.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+                let time = cost * self.grid.get_cell_size();
+                let mut value = values[0] + time;
+                if values.len() > 1 && value > values[1] {
+                    let difference = values[0] - values[1];
+                    value = (values[0] + values[1] +
+                        (two * time * time - difference * difference).sqrt()) /
+                        two;
+                    if values.len() > 2 && value > values[2] {
+                        let three = two + T::one();
+                        let sum = values[0] + values[1] + values[2];
+                        let squares = values.iter().fold(T::zero(), |sum, value| {
+                            sum + *value * *value
+                        });
+                        value = (sum + (sum * sum - three * (squares - time * time)).sqrt()) /
+                            three;
+                    }
+                }
+                Some(value)
+            }
+        }
+    }
+
+    /// returns the direction of a cell descending the integration field
+    fn direction(&self, index: usize) -> V {
+        let cell = self.cell(index);
+        let value = match self.integration[index] {
+            Some(value) if !self.is_goal(cell) => value,
+            _ => return V::zero(),
+        };
+        match self.method {
+            IntegrationMethod::Dijkstra => {
+                // steepest move towards a cheaper neighbor
+                let mut steepest: Option<(T, GridCell)> = None;
+                for (neighbor, length) in self.neighbors(cell) {
+                    if let Some(neighbor_value) = self.get_integration(neighbor) {
+                        let slope = (value - neighbor_value) / length;
+                        if slope > T::zero() && steepest.map_or(true, |steepest| slope > steepest.0)
+                        {
+                            steepest = Some((slope, neighbor));
+                        }
+                    }
+                }
+                steepest.map_or(V::zero(), |(_, neighbor)| {
+                    (self.grid.cell_center(neighbor) - self.grid.cell_center(cell)).normalize()
+                })
+            }
+            IntegrationMethod::Eikonal => {
+                let mut gradient = [T::zero(); 3];
+                for axis in 0..3 {
+                    if let Some((step, neighbor_value)) = self.upwind(cell, axis) {
+                        if neighbor_value < value {
+                            gradient[axis] = (value - neighbor_value) *
+                                T::from_i64(step as i64).unwrap();
+                        }
+                    }
+                }
+                V::from_components(|axis| gradient[axis]).normalize_or_zero()
+            }
+        }
+    }
+
+    /// returns the neighbor along an axis with the lower integration, as step
+    /// towards it and its integration
+    fn upwind(&self, cell: GridCell, axis: usize) -> Option<(isize, T)> {
+        let mut lowest: Option<(isize, T)> = None;
+        for step in [-1isize, 1].iter() {
+            let mut offset = [0isize; 3];
+            offset[axis] = *step;
+            let value = self.grid
+                .offset(cell, (offset[0], offset[1], offset[2]))
+                .and_then(|neighbor| self.get_integration(neighbor));
+            if let Some(value) = value {
+                if lowest.map_or(true, |lowest| value < lowest.1) {
+                    lowest = Some((*step, value));
+                }
+            }
+        }
+        lowest
+    }
+
+    /// returns the neighbors the integration moves between
+    fn neighbors(&self, cell: GridCell) -> Vec<(GridCell, T)> {
+        let diagonal = self.diagonal && self.method == IntegrationMethod::Dijkstra;
+        self.grid.neighbors(cell, diagonal, false)
+    }
+
+    /// returns the cell with every cell around it inside the grid
+    fn block(&self, cell: GridCell) -> Vec<GridCell> {
+        let mut block = Vec::new();
+        for z in -1..2 {
+            for y in -1..2 {
+                for x in -1..2isize {
+                    if let Some(neighbor) = self.grid.offset(cell, (x, y, z)) {
+                        block.push(neighbor);
+                    }
+                }
+            }
+        }
+        block
+    }
+
+    fn index(&self, cell: GridCell) -> Option<usize> {
+        let (width, height, depth) = self.grid.get_size();
+        if cell.x < width && cell.y < height && cell.z < depth {
+            Some(cell.x + width * (cell.y + height * cell.z))
+        } else {
+            None
+        }
+    }
+
+    fn cell(&self, index: usize) -> GridCell {
+        let (width, height, _) = self.grid.get_size();
+        GridCell::new(index % width, index / width % height, index / (width * height))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FlowFieldBuilder, IntegrationMethod};
+    use super::super::{Grid, GridCell};
+    use nalgebra::Vector2;
+    use quickcheck::quickcheck;
+
+    fn open_grid() -> Grid<f32, Vector2<f32>> {
+        Grid::new(6, 6, 1, 1.0f32, Vector2::new(0.0, 0.0)).unwrap()
+    }
+
+    #[test]
+    fn integrates_costs_towards_goals() {
+        let field = FlowFieldBuilder::new()
+            .grid(open_grid())
+            .goals(vec![GridCell::new(0, 0, 0)])
+            .method(IntegrationMethod::Dijkstra)
+            .build()
+            .unwrap();
+        assert_eq!(Some(2.0), field.get_integration(GridCell::new(2, 0, 0)));
+        assert_eq!(Some(2.0f32.sqrt()), field.get_integration(GridCell::new(1, 1, 0)));
+        assert_eq!(Vector2::new(-1.0, 0.0), field.get_direction(GridCell::new(2, 0, 0)));
+        assert_eq!(Vector2::new(0.0, 0.0), field.get_direction(GridCell::new(0, 0, 0)));
+        let sampled = field.sample(&Vector2::new(2.5, 1.0));
+        assert!(sampled.x < 0.0);
+        assert!((sampled.norm() - 1.0).abs() < 1e-5);
+
+        let field = FlowFieldBuilder::new()
+            .grid(open_grid())
+            .goals(vec![GridCell::new(0, 0, 0), GridCell::new(5, 5, 0)])
+            .build()
+            .unwrap();
+        assert_eq!(Some(3.0), field.get_integration(GridCell::new(3, 0, 0)));
+        // closer to the straight line distance than to the distance along the axes
+        let distance = field.get_integration(GridCell::new(4, 3, 0)).unwrap();
+        assert!(distance >= 5.0f32.sqrt() && distance < 2.6);
+        let direction = field.get_direction(GridCell::new(3, 0, 0));
+        assert!(direction.x < 0.0 && direction.y.abs() < 1e-5);
+        // the gradient is not limited to the moves between neighbors
+        let direction = field.get_direction(GridCell::new(3, 1, 0));
+        assert!(direction.y < 0.0 && direction.x < direction.y);
+
+        assert!(FlowFieldBuilder::new().grid(open_grid()).goals(vec![]).build().is_err());
+        assert!(FlowFieldBuilder::new()
+            .grid(open_grid())
+            .goals(vec![GridCell::new(6, 0, 0)])
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn walls_redirect_the_flow() {
+        let mut field = FlowFieldBuilder::new()
+            .grid(open_grid())
+            .goals(vec![GridCell::new(0, 2, 0)])
+            .method(IntegrationMethod::Dijkstra)
+            .build()
+            .unwrap();
+        assert_eq!(Vector2::new(-1.0, 0.0), field.get_direction(GridCell::new(2, 2, 0)));
+        for y in 0..5 {
+            field.set_cost(GridCell::new(1, y, 0), None).unwrap();
+        }
+        assert_eq!(None, field.get_integration(GridCell::new(1, 2, 0)));
+        assert_eq!(Vector2::new(0.0, 1.0), field.get_direction(GridCell::new(2, 2, 0)));
+        field.set_cost(GridCell::new(1, 5, 0), None).unwrap();
+        assert_eq!(None, field.get_integration(GridCell::new(2, 2, 0)));
+        assert_eq!(Vector2::new(0.0, 0.0), field.sample(&Vector2::new(4.0, 4.0)));
+        field.set_cost(GridCell::new(1, 2, 0), Some(1.0)).unwrap();
+        assert_eq!(Some(2.0), field.get_integration(GridCell::new(2, 2, 0)));
+    }
+
+    #[test]
+    fn incremental_updates_match_full_integration() {
+        fn prop(changes: Vec<(u8, u8, u8)>, eikonal: bool) -> bool {
+            let method = if eikonal {
+                IntegrationMethod::Eikonal
+            } else {
+                IntegrationMethod::Dijkstra
+            };
+            let goal = GridCell::new(0, 0, 0);
+            let mut field = FlowFieldBuilder::new()
+                .grid(open_grid())
+                .goals(vec![goal])
+                .method(method)
+                .build()
+                .unwrap();
+            for &(x, y, cost) in changes.iter() {
+                let cell = GridCell::new(x as usize % 6, y as usize % 6, 0);
+                if cell == goal {
+                    continue;
+                }
+                let cost = if cost % 4 == 0 {
+                    None
+                } else {
+                    Some((cost % 4) as f32)
+                };
+                field.set_cost(cell, cost).unwrap();
+            }
+            let rebuilt = FlowFieldBuilder::new()
+                .grid(field.get_grid().clone())
+                .goals(vec![goal])
+                .method(method)
+                .build()
+                .unwrap();
+            (0..36).all(|index| {
+                let cell = GridCell::new(index % 6, index / 6, 0);
+                match (field.get_integration(cell), rebuilt.get_integration(cell)) {
+                    (Some(a), Some(b)) => (a - b).abs() < 1e-4,
+                    (None, None) => true,
+                    _ => false,
+                }
+            })
+        }
+        quickcheck(prop as fn(Vec<(u8, u8, u8)>, bool) -> bool);
+    }
+}
@@ -0,0 +1,324 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {SteeringError, SteeringVector};
+use validation;
+
+/// Coordinates of a grid cell, `z` is always zero on planar grids
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GridCell {
+    /// column of the cell
+    pub x: usize,
+    /// row of the cell
+    pub y: usize,
+    /// layer of the cell
+    pub z: usize,
+}
+
+impl GridCell {
+    /// Creates cell coordinates
+    pub fn new(x: usize, y: usize, z: usize) -> GridCell {
+        GridCell { x: x, y: y, z: z }
+    }
+}
+
+/// Uniform grid of cells with a traversal cost each. Cell `(0, 0, 0)` starts at the
+/// origin and cells extend along the positive axes. Grids of planar agents have a
+/// single layer.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Grid<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    width: usize,
+    height: usize,
+    depth: usize,
+    cell_size: T,
+    origin: V,
+    /// cost multiplier of every cell, `None` for blocked cells
+    costs: Vec<Option<T>>,
+}
+
+impl<T: Real, V: SteeringVector<T>> Grid<T, V> {
+    /// Creates a grid whose cells all cost one, `depth` must be one for planar agents
+    pub fn new(
+        width: usize,
+        height: usize,
+        depth: usize,
+        cell_size: T,
+        origin: V,
+    ) -> Result<Grid<T, V>, SteeringError> {
+        validation::positive("cell_size", Some(cell_size))
+            .map_err(SteeringError::InvalidParameter)?;
+        if width == 0 || height == 0 || depth == 0 {
+            return Err(SteeringError::InvalidParameter(
+                "grid needs at least one cell along every axis".to_string(),
+            ));
+        }
+        if V::dimension() < 3 && depth != 1 {
+            return Err(SteeringError::InvalidParameter(
+                "grid of planar agents must have a single layer".to_string(),
+            ));
+        }
+        Ok(Grid {
+            width: width,
+            height: height,
+            depth: depth,
+            cell_size: cell_size,
+            origin: origin,
+            costs: vec![Some(T::one()); width * height * depth],
+        })
+    }
+
+    /// returns the number of columns, rows and layers
+    pub fn get_size(&self) -> (usize, usize, usize) {
+        (self.width, self.height, self.depth)
+    }
+
+    /// returns the edge length of a cell
+    pub fn get_cell_size(&self) -> T {
+        self.cell_size
+    }
+
+    /// returns the corner of the grid cells extend from
+    pub fn get_origin(&self) -> V {
+        self.origin
+    }
+
+    /// returns the cost multiplier of a cell, `None` if the cell is blocked or outside
+    /// the grid
+    pub fn get_cost(&self, cell: GridCell) -> Option<T> {
+        self.index(cell).and_then(|index| self.costs[index])
+    }
+
+    /// Sets the cost multiplier of a cell, `None` blocks the cell. Costs must be
+    /// positive, returns an error for invalid costs and cells outside the grid.
+    pub fn set_cost(&mut self, cell: GridCell, cost: Option<T>) -> Result<(), SteeringError> {
+        validation::positive("cost", cost).map_err(SteeringError::InvalidParameter)?;
+        match self.index(cell) {
+            Some(index) => {
+                self.costs[index] = cost;
+                Ok(())
+            }
+            None => Err(SteeringError::InvalidParameter(
+                format!("cell {:?} is outside the grid", cell),
+            )),
+        }
+    }
+
+    /// Tests whether a cell is inside the grid and not blocked
+    pub fn is_walkable(&self, cell: GridCell) -> bool {
+        self.get_cost(cell).is_some()
+    }
+
+    /// returns the lowest cost multiplier of walkable cells
+    pub fn get_min_cost(&self) -> Option<T> {
+        self.costs.iter().filter_map(|cost| *cost).fold(None, |min, cost| {
+            Some(min.map_or(cost, |min: T| if cost < min { cost } else { min }))
+        })
+    }
+
+    /// returns the cell containing given position
+    pub fn cell_at(&self, position: &V) -> Option<GridCell> {
+        let size = [self.width, self.height, self.depth];
+        let mut coordinates = [0usize; 3];
+        for axis in 0..V::dimension() {
+            let offset = (position.component(axis) - self.origin.component(axis)) / self.cell_size;
+            if !(offset >= T::zero()) {
+                return None;
+            }
+            let offset: f64 = offset.floor().to_subset().unwrap_or(::std::f64::MAX);
+            if offset >= size[axis] as f64 {
+                return None;
+            }
+            coordinates[axis] = offset as usize;
+        }
+        Some(GridCell::new(coordinates[0], coordinates[1], coordinates[2]))
+    }
+
+    /// returns the center of a cell
+    pub fn cell_center(&self, cell: GridCell) -> V {
+        let coordinates = [cell.x, cell.y, cell.z];
+        let half = T::one() / (T::one() + T::one());
+        V::from_components(|axis| {
+            let coordinate = T::from_usize(coordinates[axis]).unwrap();
+            self.origin.component(axis) + (coordinate + half) * self.cell_size
+        })
+    }
+
+    /// returns the cell moved by given offset, `None` if it leaves the grid
+    pub fn offset(&self, cell: GridCell, offset: (isize, isize, isize)) -> Option<GridCell> {
+        let moved = |value: usize, offset: isize, size: usize| {
+            let value = value as isize + offset;
+            if value >= 0 && (value as usize) < size {
+                Some(value as usize)
+            } else {
+                None
+            }
+        };
+        match (
+            moved(cell.x, offset.0, self.width),
+            moved(cell.y, offset.1, self.height),
+            moved(cell.z, offset.2, self.depth),
+        ) {
+            (Some(x), Some(y), Some(z)) => Some(GridCell::new(x, y, z)),
+            _ => None,
+        }
+    }
+
+    /// Tests whether the straight line between the centers of two cells only crosses
+    /// walkable cells. Lines passing exactly through a corner or an edge need all
+    /// cells touching it to be walkable.
+    pub fn has_line_of_sight(&self, from: GridCell, to: GridCell) -> bool {
+        let start = [from.x as f64 + 0.5, from.y as f64 + 0.5, from.z as f64 + 0.5];
+        let delta = [
+            to.x as f64 - from.x as f64,
+            to.y as f64 - from.y as f64,
+            to.z as f64 - from.z as f64,
+        ];
+        let mut current = [from.x as isize, from.y as isize, from.z as isize];
+        let target = [to.x as isize, to.y as isize, to.z as isize];
+        let mut step = [0isize; 3];
+        let mut next = [::std::f64::INFINITY; 3];
+        let mut increment = [::std::f64::INFINITY; 3];
+        for axis in 0..3 {
+            if delta[axis] > 0.0 {
+                step[axis] = 1;
+                increment[axis] = 1.0 / delta[axis];
+                next[axis] = (current[axis] as f64 + 1.0 - start[axis]) / delta[axis];
+            } else if delta[axis] < 0.0 {
+                step[axis] = -1;
+                increment[axis] = -1.0 / delta[axis];
+                next[axis] = (current[axis] as f64 - start[axis]) / delta[axis];
+            }
+        }
+        let walkable = |cell: [isize; 3]| {
+            cell.iter().all(|value| *value >= 0) &&
+                self.is_walkable(GridCell::new(
+                    cell[0] as usize,
+                    cell[1] as usize,
+                    cell[2] as usize,
+                ))
+        };
+        if !walkable(current) {
+            return false;
+        }
+        while current != target {
+            let t = next[0].min(next[1]).min(next[2]);
+            let crossing: Vec<usize> = (0..3)
+                .filter(|axis| (next[*axis] - t).abs() < 1e-9)
+                .collect();
+            // every cell around a corner or an edge the line passes through
+            for mask in 1..(1 << crossing.len()) {
+                let mut cell = current;
+                for (bit, axis) in crossing.iter().enumerate() {
+                    if mask & (1 << bit) != 0 {
+                        cell[*axis] += step[*axis];
+                    }
+                }
+                if !walkable(cell) {
+                    return false;
+                }
+            }
+            for axis in crossing.iter() {
+                current[*axis] += step[*axis];
+                next[*axis] += increment[*axis];
+            }
+        }
+        true
+    }
+
+    /// returns the walkable neighbors of a cell with the distance between the cell
+    /// centers. Neighbors share a face with the cell, with `diagonal` they may
+    /// share only an edge or a corner. Diagonal moves next to a blocked cell need
+    /// `corner_cutting`.
+    pub fn neighbors(
+        &self,
+        cell: GridCell,
+        diagonal: bool,
+        corner_cutting: bool,
+    ) -> Vec<(GridCell, T)> {
+        let layers = if self.depth > 1 { 1 } else { 0 };
+        let mut neighbors = Vec::new();
+        for z in -layers..layers + 1 {
+            for y in -1..2 {
+                for x in -1..2isize {
+                    let axes = (x != 0) as usize + (y != 0) as usize + (z != 0) as usize;
+                    if axes == 0 || (axes > 1 && !diagonal) {
+                        continue;
+                    }
+                    let neighbor = match self.offset(cell, (x, y, z)) {
+                        Some(neighbor) if self.is_walkable(neighbor) => neighbor,
+                        _ => continue,
+                    };
+                    if axes > 1 && !corner_cutting && !self.clears_corners(cell, (x, y, z)) {
+                        continue;
+                    }
+                    let length = T::from_usize(axes).unwrap().sqrt() * self.cell_size;
+                    neighbors.push((neighbor, length));
+                }
+            }
+        }
+        neighbors
+    }
+
+    /// Tests whether every cell sharing a face with both ends of a diagonal move is
+    /// walkable
+    fn clears_corners(&self, cell: GridCell, offset: (isize, isize, isize)) -> bool {
+        let components = [offset.0, offset.1, offset.2];
+        // every partial move, e.g. only along x, only along y, along x and z, ...
+        for mask in 1..7 {
+            let partial = [
+                if mask & 1 != 0 { components[0] } else { 0 },
+                if mask & 2 != 0 { components[1] } else { 0 },
+                if mask & 4 != 0 { components[2] } else { 0 },
+            ];
+            if partial == components || partial == [0, 0, 0] {
+                continue;
+            }
+            match self.offset(cell, (partial[0], partial[1], partial[2])) {
+                Some(corner) if self.is_walkable(corner) => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+
+    fn index(&self, cell: GridCell) -> Option<usize> {
+        if cell.x < self.width && cell.y < self.height && cell.z < self.depth {
+            Some(cell.x + self.width * (cell.y + self.height * cell.z))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Grid, GridCell};
+    use nalgebra::{Vector2, Vector3};
+
+    #[test]
+    fn cells_and_positions() {
+        let grid = Grid::new(4, 3, 1, 2.0f32, Vector2::new(-1.0, 0.0)).unwrap();
+        assert!(Grid::new(4, 3, 2, 2.0f32, Vector2::new(0.0, 0.0)).is_err());
+        assert!(Grid::new(4, 0, 1, 2.0f32, Vector2::new(0.0, 0.0)).is_err());
+        assert_eq!(Some(GridCell::new(1, 2, 0)), grid.cell_at(&Vector2::new(2.5, 5.0)));
+        assert_eq!(None, grid.cell_at(&Vector2::new(-1.5, 5.0)));
+        assert_eq!(None, grid.cell_at(&Vector2::new(2.5, 6.0)));
+        assert_eq!(Vector2::new(2.0, 5.0), grid.cell_center(GridCell::new(1, 2, 0)));
+
+        let grid = Grid::new(2, 2, 2, 1.0f32, Vector3::new(0.0, 0.0, 0.0)).unwrap();
+        assert_eq!(Some(GridCell::new(1, 0, 1)), grid.cell_at(&Vector3::new(1.5, 0.2, 1.9)));
+    }
+
+    #[test]
+    fn line_of_sight_does_not_squeeze_through_corners() {
+        let mut grid = Grid::new(5, 5, 1, 1.0f32, Vector2::new(0.0, 0.0)).unwrap();
+        assert!(grid.set_cost(GridCell::new(1, 1, 0), Some(0.0)).is_err());
+        assert!(grid.set_cost(GridCell::new(9, 1, 0), None).is_err());
+        grid.set_cost(GridCell::new(1, 0, 0), None).unwrap();
+        assert!(grid.has_line_of_sight(GridCell::new(0, 1, 0), GridCell::new(4, 3, 0)));
+        assert!(!grid.has_line_of_sight(GridCell::new(0, 1, 0), GridCell::new(1, 0, 0)));
+        assert!(!grid.has_line_of_sight(GridCell::new(0, 0, 0), GridCell::new(2, 2, 0)));
+        assert!(!grid.has_line_of_sight(GridCell::new(0, 0, 0), GridCell::new(3, 0, 0)));
+    }
+}
@@ -0,0 +1,272 @@
+use alga::general::Real;
+use {LinePath, SteeringVector};
+use super::{Grid, GridCell};
+use search::Open;
+
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+
+/// Search strategy of a `GridPathfinder`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SearchAlgorithm {
+    /// expands cells closest to the start first
+    Dijkstra,
+    /// expands cells with the lowest estimated total cost first, using the straight
+    /// line distance to the goal times the lowest cell cost as estimate
+    AStar,
+}
+
+/// Cells of a path found on a grid and the cost of moving along them
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridPath<T: Real> {
+    /// cells from the start to the goal
+    pub cells: Vec<GridCell>,
+    /// sum of the costs of the moves between the cells
+    pub cost: T,
+}
+
+/// Finds the cheapest paths over a weighted grid. Moving between neighbor cells
+/// costs the distance between their centers times the mean of their cost
+/// multipliers.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct GridPathfinder {
+    /// search strategy
+    pub algorithm: SearchAlgorithm,
+    /// allow moving to cells sharing only an edge or a corner
+    pub diagonal: bool,
+    /// allow diagonal moves next to blocked cells
+    pub corner_cutting: bool,
+    /// remove waypoints in line of sight of each other from found line paths
+    pub smoothing: bool,
+}
+
+impl GridPathfinder {
+    /// returns A* search with diagonal moves, without corner cutting and with
+    /// smoothing
+    pub fn new() -> GridPathfinder {
+        GridPathfinder {
+            algorithm: SearchAlgorithm::AStar,
+            diagonal: true,
+            corner_cutting: false,
+            smoothing: true,
+        }
+    }
+
+    /// returns the cheapest path between two walkable cells
+    pub fn find_path<T: Real, V: SteeringVector<T>>(
+        &self,
+        grid: &Grid<T, V>,
+        start: GridCell,
+        goal: GridCell,
+    ) -> Option<GridPath<T>> {
+        if !grid.is_walkable(start) || !grid.is_walkable(goal) {
+            return None;
+        }
+        let estimate_scale = match self.algorithm {
+            SearchAlgorithm::AStar => grid.get_min_cost().unwrap_or(T::zero()),
+            SearchAlgorithm::Dijkstra => T::zero(),
+        };
+        let goal_center = grid.cell_center(goal);
+        let estimate = |cell: GridCell| {
+            grid.cell_center(cell).distance(&goal_center) * estimate_scale
+        };
+
+        let mut cells = vec![start];
+        let mut indices = HashMap::new();
+        indices.insert(start, 0);
+        let mut costs = vec![T::zero()];
+        let mut parents: Vec<Option<usize>> = vec![None];
+        let mut closed = vec![false];
+        let mut open = BinaryHeap::new();
+        open.push(Open {
+            priority: estimate(start),
+            index: 0,
+        });
+
+        while let Some(Open { index, .. }) = open.pop() {
+            if closed[index] {
+                continue;
+            }
+            closed[index] = true;
+            let cell = cells[index];
+            if cell == goal {
+                let mut path = vec![cell];
+                let mut parent = parents[index];
+                while let Some(previous) = parent {
+                    path.push(cells[previous]);
+                    parent = parents[previous];
+                }
+                path.reverse();
+                return Some(GridPath {
+                    cells: path,
+                    cost: costs[index],
+                });
+            }
+            let cost = grid.get_cost(cell).unwrap();
+            for (neighbor, length) in grid.neighbors(cell, self.diagonal, self.corner_cutting) {
+                let neighbor_cost = grid.get_cost(neighbor).unwrap();
+                let total = costs[index] + length * (cost + neighbor_cost) / (T::one() + T::one());
+                let neighbor_index = *indices.entry(neighbor).or_insert_with(|| {
+                    cells.push(neighbor);
+                    costs.push(total);
+                    parents.push(None);
+                    closed.push(false);
+                    cells.len() - 1
+                });
+                if closed[neighbor_index] ||
+                    (parents[neighbor_index].is_some() && costs[neighbor_index] <= total)
+                {
+                    continue;
+                }
+                costs[neighbor_index] = total;
+                parents[neighbor_index] = Some(index);
+                open.push(Open {
+                    priority: total + estimate(neighbor),
+                    index: neighbor_index,
+                });
+            }
+        }
+        None
+    }
+
+    /// Removes cells of a path which can be skipped by moving in a straight line
+    /// between the cells before and after them
+    pub fn smooth<T: Real, V: SteeringVector<T>>(
+        &self,
+        grid: &Grid<T, V>,
+        cells: &[GridCell],
+    ) -> Vec<GridCell> {
+        if cells.len() < 3 {
+            return cells.to_vec();
+        }
+        let mut smoothed = vec![cells[0]];
+        let mut anchor = 0;
+        while anchor < cells.len() - 1 {
+            let mut furthest = anchor + 1;
+            for candidate in (anchor + 2..cells.len()).rev() {
+                if grid.has_line_of_sight(cells[anchor], cells[candidate]) {
+                    furthest = candidate;
+                    break;
+                }
+            }
+            smoothed.push(cells[furthest]);
+            anchor = furthest;
+        }
+        smoothed
+    }
+
+    /// returns a path `FollowPath` can follow from the start position to the goal
+    /// position, `None` if either position is not on a walkable cell or no path
+    /// connects them. Inner waypoints are cell centers.
+    pub fn find_line_path<T: Real, V: SteeringVector<T>>(
+        &self,
+        grid: &Grid<T, V>,
+        start: &V,
+        goal: &V,
+    ) -> Option<LinePath<T, V>> {
+        let path = self.find_path(grid, grid.cell_at(start)?, grid.cell_at(goal)?)?;
+        let cells = if self.smoothing {
+            self.smooth(grid, &path.cells)
+        } else {
+            path.cells
+        };
+        let mut waypoints: Vec<V> = cells.iter().map(|cell| grid.cell_center(*cell)).collect();
+        waypoints[0] = *start;
+        let last = waypoints.len() - 1;
+        if last == 0 {
+            waypoints.push(*goal);
+        } else {
+            waypoints[last] = *goal;
+        }
+        LinePath::new(waypoints, true).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GridPathfinder, SearchAlgorithm};
+    use super::super::{Grid, GridCell};
+    use Path;
+    use nalgebra::{Vector2, Vector3};
+
+    /// 5x5 grid with a wall at x = 2 leaving a gap at the top row
+    fn walled_grid() -> Grid<f32, Vector2<f32>> {
+        let mut grid = Grid::new(5, 5, 1, 1.0f32, Vector2::new(0.0, 0.0)).unwrap();
+        for y in 0..4 {
+            grid.set_cost(GridCell::new(2, y, 0), None).unwrap();
+        }
+        grid
+    }
+
+    #[test]
+    fn a_star_and_dijkstra_find_the_same_cost() {
+        let grid = walled_grid();
+        let mut pathfinder = GridPathfinder::new();
+        let start = GridCell::new(0, 0, 0);
+        let goal = GridCell::new(4, 0, 0);
+        let a_star = pathfinder.find_path(&grid, start, goal).unwrap();
+        pathfinder.algorithm = SearchAlgorithm::Dijkstra;
+        let dijkstra = pathfinder.find_path(&grid, start, goal).unwrap();
+        assert!((a_star.cost - dijkstra.cost).abs() < 1e-5);
+        assert_eq!(start, a_star.cells[0]);
+        assert_eq!(goal, *a_star.cells.last().unwrap());
+        assert!(a_star.cells.contains(&GridCell::new(2, 4, 0)));
+        // without corner cutting the path steps onto the row of the gap before and after it
+        assert!((a_star.cost - (8.0 + 2.0 * 2.0f32.sqrt())).abs() < 1e-5);
+
+        pathfinder.corner_cutting = true;
+        let cut = pathfinder.find_path(&grid, start, goal).unwrap();
+        assert!((cut.cost - (4.0 + 4.0 * 2.0f32.sqrt())).abs() < 1e-5);
+
+        pathfinder.diagonal = false;
+        let straight = pathfinder.find_path(&grid, start, goal).unwrap();
+        assert_eq!(12.0, straight.cost);
+    }
+
+    #[test]
+    fn costs_and_blocked_goals() {
+        let mut grid = Grid::new(3, 3, 1, 1.0f32, Vector2::new(0.0, 0.0)).unwrap();
+        grid.set_cost(GridCell::new(1, 1, 0), Some(10.0)).unwrap();
+        let mut pathfinder = GridPathfinder::new();
+        pathfinder.diagonal = false;
+        let path = pathfinder
+            .find_path(&grid, GridCell::new(0, 1, 0), GridCell::new(2, 1, 0))
+            .unwrap();
+        assert_eq!(4.0, path.cost);
+        grid.set_cost(GridCell::new(2, 1, 0), None).unwrap();
+        assert!(pathfinder
+            .find_path(&grid, GridCell::new(0, 1, 0), GridCell::new(2, 1, 0))
+            .is_none());
+    }
+
+    #[test]
+    fn smoothed_line_path() {
+        let grid = walled_grid();
+        let pathfinder = GridPathfinder::new();
+        let path = pathfinder
+            .find_line_path(&grid, &Vector2::new(0.2f32, 0.3), &Vector2::new(4.5, 0.5))
+            .unwrap();
+        let waypoints = path.get_waypoints();
+        assert_eq!(Vector2::new(0.2, 0.3), waypoints[0]);
+        assert_eq!(Vector2::new(4.5, 0.5), path.get_end_point());
+        assert!(waypoints.len() < 6);
+        assert!(path.is_open());
+        assert!(pathfinder
+            .find_line_path(&grid, &Vector2::new(2.5f32, 0.5), &Vector2::new(4.5, 0.5))
+            .is_none());
+    }
+
+    #[test]
+    fn paths_through_layers() {
+        let mut grid = Grid::new(3, 1, 3, 1.0f32, Vector3::new(0.0, 0.0, 0.0)).unwrap();
+        for x in 0..2 {
+            grid.set_cost(GridCell::new(x, 0, 1), None).unwrap();
+        }
+        let path = GridPathfinder::new()
+            .find_path(&grid, GridCell::new(0, 0, 0), GridCell::new(0, 0, 2))
+            .unwrap();
+        assert!(path.cells.contains(&GridCell::new(2, 0, 1)));
+    }
+}
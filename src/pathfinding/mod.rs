@@ -0,0 +1,9 @@
+//! Path planning over weighted grids producing paths for `FollowPath` and flow
+//! fields for `FollowFlowField`
+mod grid;
+mod grid_pathfinder;
+mod flow_field;
+
+pub use self::grid::{Grid, GridCell};
+pub use self::grid_pathfinder::{GridPath, GridPathfinder, SearchAlgorithm};
+pub use self::flow_field::{FlowField, FlowFieldBuilder, IntegrationMethod};
@@ -0,0 +1,174 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {Path, SteeringError, SteeringVector};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+struct Segment<T: Real, V: SteeringVector<T>> {
+    begin: V,
+    end: V,
+    length: T,
+    /// distance along the path where the segment begins
+    cumulative_length: T,
+}
+
+/// Path made of straight segments connecting a list of waypoints. A closed path
+/// also connects the last waypoint to the first one.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LinePath<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    segments: Vec<Segment<T, V>>,
+    open: bool,
+    length: T,
+}
+
+impl<T: Real, V: SteeringVector<T>> LinePath<T, V> {
+    /// Creates a path through given waypoints, at least two are needed
+    pub fn new(waypoints: Vec<V>, open: bool) -> Result<LinePath<T, V>, SteeringError> {
+        if waypoints.len() < 2 {
+            return Err(SteeringError::InvalidParameter(
+                "path needs at least two waypoints".to_string(),
+            ));
+        }
+        let mut ends: Vec<(V, V)> = waypoints.windows(2).map(|w| (w[0], w[1])).collect();
+        if !open {
+            ends.push((waypoints[waypoints.len() - 1], waypoints[0]));
+        }
+        let mut length = T::zero();
+        let mut segments = Vec::with_capacity(ends.len());
+        for (begin, end) in ends {
+            let segment_length = begin.distance(&end);
+            segments.push(Segment {
+                begin: begin,
+                end: end,
+                length: segment_length,
+                cumulative_length: length,
+            });
+            length += segment_length;
+        }
+        Ok(LinePath {
+            segments: segments,
+            open: open,
+            length: length,
+        })
+    }
+
+    /// returns the waypoints of the path
+    pub fn get_waypoints(&self) -> Vec<V> {
+        let mut waypoints: Vec<V> = self.segments.iter().map(|segment| segment.begin).collect();
+        if self.open {
+            waypoints.push(self.get_end_point());
+        }
+        waypoints
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Path<T, V> for LinePath<T, V> {
+    fn calculate_distance(&self, position: &V) -> T {
+        let mut closest_distance = T::zero();
+        let mut smallest_square_distance: Option<T> = None;
+        for segment in self.segments.iter() {
+            let direction = segment.end - segment.begin;
+            let square_length = direction.norm_squared();
+            let along = if square_length.is_zero() {
+                T::zero()
+            } else {
+                let projection = (*position - segment.begin).dot(&direction) / square_length;
+                projection.max(T::zero()).min(T::one())
+            };
+            let point = segment.begin + direction.scale(along);
+            let square_distance = (*position - point).norm_squared();
+            if smallest_square_distance.map_or(true, |smallest| square_distance < smallest) {
+                smallest_square_distance = Some(square_distance);
+                closest_distance = segment.cumulative_length + segment.length * along;
+            }
+        }
+        closest_distance
+    }
+
+    fn calculate_target_position(&self, distance: T) -> V {
+        let distance = if self.open {
+            distance.max(T::zero()).min(self.length)
+        } else if self.length.is_zero() {
+            T::zero()
+        } else {
+            let wrapped = distance % self.length;
+            if wrapped < T::zero() {
+                wrapped + self.length
+            } else {
+                wrapped
+            }
+        };
+        let segment = self.segments
+            .iter()
+            .find(|segment| segment.cumulative_length + segment.length >= distance)
+            .unwrap_or_else(|| &self.segments[self.segments.len() - 1]);
+        if segment.length.is_zero() {
+            return segment.begin;
+        }
+        let along = ((distance - segment.cumulative_length) / segment.length).min(T::one());
+        segment.begin + (segment.end - segment.begin).scale(along)
+    }
+
+    fn get_length(&self) -> T {
+        self.length
+    }
+
+    fn is_open(&self) -> bool {
+        self.open
+    }
+
+    fn get_start_point(&self) -> V {
+        self.segments[0].begin
+    }
+
+    fn get_end_point(&self) -> V {
+        self.segments[self.segments.len() - 1].end
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LinePath;
+    use Path;
+    use nalgebra::Vector2;
+
+    fn square(open: bool) -> LinePath<f32, Vector2<f32>> {
+        LinePath::new(
+            vec![
+                Vector2::new(0.0, 0.0),
+                Vector2::new(10.0, 0.0),
+                Vector2::new(10.0, 10.0),
+                Vector2::new(0.0, 10.0),
+            ],
+            open,
+        ).unwrap()
+    }
+
+    #[test]
+    fn distance_along_the_path() {
+        let path = square(true);
+        assert_eq!(30.0, path.get_length());
+        assert_eq!(5.0, path.calculate_distance(&Vector2::new(5.0, -3.0)));
+        assert_eq!(15.0, path.calculate_distance(&Vector2::new(12.0, 5.0)));
+        assert_eq!(0.0, path.calculate_distance(&Vector2::new(-4.0, -4.0)));
+        assert_eq!(Vector2::new(10.0, 5.0), path.calculate_target_position(15.0));
+        assert_eq!(Vector2::new(0.0, 10.0), path.calculate_target_position(42.0));
+        assert_eq!(Vector2::new(0.0, 0.0), path.calculate_target_position(-1.0));
+    }
+
+    #[test]
+    fn closed_path_wraps_around() {
+        let path = square(false);
+        assert_eq!(40.0, path.get_length());
+        assert_eq!(35.0, path.calculate_distance(&Vector2::new(-1.0, 5.0)));
+        assert_eq!(Vector2::new(5.0, 0.0), path.calculate_target_position(45.0));
+        assert_eq!(Vector2::new(0.0, 5.0), path.calculate_target_position(-5.0));
+        assert_eq!(path.get_start_point(), path.get_end_point());
+    }
+
+    #[test]
+    fn needs_two_waypoints() {
+        assert!(LinePath::<f32, Vector2<f32>>::new(vec![Vector2::new(0.0, 0.0)], true).is_err());
+    }
+}
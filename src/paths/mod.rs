@@ -0,0 +1,7 @@
+mod line_path;
+mod spline_path;
+mod speed_profile;
+
+pub use self::line_path::LinePath;
+pub use self::spline_path::SplinePath;
+pub use self::speed_profile::SpeedProfile;
@@ -0,0 +1,210 @@
+use alga::general::Real;
+use {Limiter, Path, SteeringError, SteeringVector};
+use validation;
+
+/// Speeds planned along a path, sampled at evenly spaced distances. Speeds are
+/// capped by the max speed and by the lateral acceleration curvature needs at that
+/// speed, change no faster than the max acceleration allows and reach zero at the
+/// end of an open path, so agents slow down before bends and stop at the end.
+/// Profiles plan travel towards the end of the path.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SpeedProfile<T: Real> {
+    speeds: Vec<T>,
+    spacing: T,
+    length: T,
+    open: bool,
+}
+
+impl<T: Real> SpeedProfile<T> {
+    /// Plans speeds along given path with samples at most `spacing` apart
+    pub fn new<V: SteeringVector<T>>(
+        path: &Path<T, V>,
+        spacing: T,
+        max_speed: T,
+        max_acceleration: T,
+        max_lateral_acceleration: T,
+    ) -> Result<SpeedProfile<T>, SteeringError> {
+        validation::positive("spacing", Some(spacing))
+            .and_then(|_| validation::positive("max_speed", Some(max_speed)))
+            .and_then(|_| validation::positive("max_acceleration", Some(max_acceleration)))
+            .and_then(|_| {
+                validation::positive("max_lateral_acceleration", Some(max_lateral_acceleration))
+            })
+            .map_err(SteeringError::InvalidParameter)?;
+        let length = path.get_length();
+        if !(length > T::zero()) {
+            return Err(SteeringError::InvalidParameter(
+                "path must not be empty".to_string(),
+            ));
+        }
+        let intervals: f64 = (length / spacing).ceil().to_subset().unwrap_or(1.0);
+        let intervals = (intervals as usize).max(1);
+        let spacing = length / T::from_usize(intervals).unwrap();
+        let open = path.is_open();
+        let count = if open { intervals + 1 } else { intervals };
+
+        // curvature of the neighborhood of a sample, bends between samples count too
+        let half = spacing / T::from_i64(2).unwrap();
+        let mut speeds: Vec<T> = (0..count)
+            .map(|index| {
+                let distance = spacing * T::from_usize(index).unwrap();
+                let curvature = [distance - half, distance, distance + half]
+                    .iter()
+                    .map(|distance| path.calculate_curvature(*distance).abs())
+                    .fold(T::zero(), |max, curvature| max.max(curvature));
+                if curvature > T::zero() {
+                    max_speed.min((max_lateral_acceleration / curvature).sqrt())
+                } else {
+                    max_speed
+                }
+            })
+            .collect();
+        if open {
+            speeds[count - 1] = T::zero();
+        }
+
+        // a closed path needs two laps to carry the slowest sample all the way around
+        let steps = if open { count } else { count * 2 };
+        let step = T::from_i64(2).unwrap() * max_acceleration * spacing;
+        for k in 1..steps {
+            let (previous, index) = ((k - 1) % count, k % count);
+            speeds[index] = speeds[index].min((speeds[previous] * speeds[previous] + step).sqrt());
+        }
+        for k in (0..steps - 1).rev() {
+            let (index, next) = (k % count, (k + 1) % count);
+            speeds[index] = speeds[index].min((speeds[next] * speeds[next] + step).sqrt());
+        }
+
+        Ok(SpeedProfile {
+            speeds: speeds,
+            spacing: spacing,
+            length: length,
+            open: open,
+        })
+    }
+
+    /// Plans speeds along given path within the max linear speed and acceleration
+    /// of a limiter
+    pub fn from_limiter<V: SteeringVector<T>>(
+        path: &Path<T, V>,
+        spacing: T,
+        limiter: &Limiter<T>,
+        max_lateral_acceleration: T,
+    ) -> Result<SpeedProfile<T>, SteeringError> {
+        SpeedProfile::new(
+            path,
+            spacing,
+            limiter.get_max_linear_speed(),
+            limiter.get_max_linear_acceleration(),
+            max_lateral_acceleration,
+        )
+    }
+
+    /// returns the planned speeds, the first one at the start of the path
+    pub fn get_speeds(&self) -> &[T] {
+        &self.speeds
+    }
+
+    /// returns the distance along the path between two samples
+    pub fn get_spacing(&self) -> T {
+        self.spacing
+    }
+
+    /// returns the length of the planned path
+    pub fn get_length(&self) -> T {
+        self.length
+    }
+
+    /// returns the planned speed at given distance along the path, distances beyond
+    /// the ends are clamped on open paths and wrapped around on closed ones. The
+    /// squared speed is interpolated between samples, which is exact while the
+    /// acceleration is constant.
+    pub fn speed_at(&self, distance: T) -> T {
+        let distance = if self.open {
+            distance.max(T::zero()).min(self.length)
+        } else {
+            let wrapped = distance % self.length;
+            if wrapped < T::zero() {
+                wrapped + self.length
+            } else {
+                wrapped
+            }
+        };
+        let position = distance / self.spacing;
+        let floor: f64 = position.floor().to_subset().unwrap_or(0.0);
+        let count = self.speeds.len();
+        let (index, next) = if self.open {
+            let index = (floor.max(0.0) as usize).min(count - 2);
+            (index, index + 1)
+        } else {
+            let index = (floor.max(0.0) as usize) % count;
+            (index, (index + 1) % count)
+        };
+        let fraction = (position - T::from_usize(index).unwrap()).max(T::zero()).min(T::one());
+        let (begin, end) = (self.speeds[index], self.speeds[next]);
+        (begin * begin + (end * end - begin * begin) * fraction).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SpeedProfile;
+    use super::super::{LinePath, SplinePath};
+    use Path;
+    use nalgebra::Vector2;
+
+    #[test]
+    fn stops_at_the_end_of_an_open_path() {
+        let path = LinePath::new(vec![Vector2::new(0.0f32, 0.0), Vector2::new(10.0, 0.0)], true)
+            .unwrap();
+        let profile = SpeedProfile::new(&path, 0.5, 4.0, 1.0, 1.0).unwrap();
+        assert_eq!(21, profile.get_speeds().len());
+        assert_eq!(4.0, profile.speed_at(0.0));
+        assert!((profile.speed_at(8.0) - 2.0).abs() < 1e-5);
+        assert!((profile.speed_at(9.75) - 0.5f32.sqrt()).abs() < 1e-5);
+        assert_eq!(0.0, profile.speed_at(12.0));
+    }
+
+    #[test]
+    fn slows_down_before_bends() {
+        let path = SplinePath::catmull_rom(
+            vec![
+                Vector2::new(0.0f32, 0.0),
+                Vector2::new(20.0, 0.0),
+                Vector2::new(20.0, 2.0),
+                Vector2::new(0.0, 2.0),
+            ],
+            true,
+        ).unwrap();
+        let profile = SpeedProfile::new(&path, 0.25, 10.0, 4.0, 4.0).unwrap();
+        // the hairpin is halfway along the path
+        let bend = path.get_length() / 2.0;
+        assert!(profile.speed_at(bend) < 3.0);
+        assert!(profile.speed_at(bend - 3.0) < 6.0);
+        assert_eq!(10.0, profile.speed_at(1.0));
+        // speeds change no faster than the acceleration allows
+        for pair in profile.get_speeds().windows(2) {
+            let change = (pair[1] * pair[1] - pair[0] * pair[0]).abs();
+            assert!(change <= 2.0 * 4.0 * profile.get_spacing() + 1e-3);
+        }
+    }
+
+    #[test]
+    fn closed_paths_wrap_around() {
+        let path = LinePath::new(
+            vec![
+                Vector2::new(0.0f32, 0.0),
+                Vector2::new(4.0, 0.0),
+                Vector2::new(4.0, 4.0),
+                Vector2::new(0.0, 4.0),
+            ],
+            false,
+        ).unwrap();
+        let profile = SpeedProfile::new(&path, 1.0, 3.0, 1.0, 1.0).unwrap();
+        assert_eq!(16, profile.get_speeds().len());
+        assert!(profile.get_speeds().iter().all(|speed| *speed == 3.0));
+        assert_eq!(3.0, profile.speed_at(-5.0));
+        assert_eq!(3.0, profile.speed_at(15.5));
+    }
+}
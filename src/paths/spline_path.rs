@@ -0,0 +1,399 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {Path, SteeringError, SteeringVector};
+
+/// Number of intervals per curve segment the arc length is measured over
+const SAMPLES: usize = 16;
+
+/// Smooth path made of cubic Bezier segments. Points on the path are addressed by
+/// their distance along it through an arc length table, or by a parameter which
+/// runs from `i` to `i + 1` along segment `i`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SplinePath<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// control points of every segment
+    segments: Vec<[V; 4]>,
+    open: bool,
+    /// points at every sample parameter
+    samples: Vec<V>,
+    /// distance along the path of every sample point
+    lengths: Vec<T>,
+}
+
+impl<T: Real, V: SteeringVector<T>> SplinePath<T, V> {
+    /// Creates a chain of cubic Bezier curves. Every curve begins with the last
+    /// point of the previous one and adds two control points and an end point, so
+    /// an open path needs `3 * n + 1` points. A closed path needs `3 * n` points,
+    /// its last curve ends at the first point.
+    pub fn bezier(points: Vec<V>, open: bool) -> Result<SplinePath<T, V>, SteeringError> {
+        let valid = if open {
+            points.len() >= 4 && (points.len() - 1) % 3 == 0
+        } else {
+            points.len() >= 3 && points.len() % 3 == 0
+        };
+        if !valid {
+            return Err(SteeringError::InvalidParameter(
+                "open bezier path needs 3 * n + 1 points, closed one 3 * n points".to_string(),
+            ));
+        }
+        let count = if open {
+            (points.len() - 1) / 3
+        } else {
+            points.len() / 3
+        };
+        let segments = (0..count)
+            .map(|i| {
+                [
+                    points[3 * i],
+                    points[3 * i + 1],
+                    points[3 * i + 2],
+                    points[(3 * i + 3) % points.len()],
+                ]
+            })
+            .collect();
+        Ok(SplinePath::from_segments(segments, open))
+    }
+
+    /// Creates a centripetal Catmull-Rom spline through given waypoints, at least
+    /// two are needed. Centripetal parameterization keeps the curve from looping or
+    /// overshooting between waypoints at uneven distances. Consecutive waypoints
+    /// must differ.
+    pub fn catmull_rom(waypoints: Vec<V>, open: bool) -> Result<SplinePath<T, V>, SteeringError> {
+        let count = waypoints.len();
+        if count < 2 {
+            return Err(SteeringError::InvalidParameter(
+                "path needs at least two waypoints".to_string(),
+            ));
+        }
+        let ends = if open { count - 1 } else { count };
+        if (0..ends).any(|i| waypoints[i] == waypoints[(i + 1) % count]) {
+            return Err(SteeringError::InvalidParameter(
+                "consecutive waypoints must differ".to_string(),
+            ));
+        }
+        let point = |i: isize| -> V {
+            if !open {
+                waypoints[((i % count as isize + count as isize) % count as isize) as usize]
+            } else if i < 0 {
+                // mirror the second waypoint at the first one
+                waypoints[0] + (waypoints[0] - waypoints[1])
+            } else if i as usize >= count {
+                waypoints[count - 1] + (waypoints[count - 1] - waypoints[count - 2])
+            } else {
+                waypoints[i as usize]
+            }
+        };
+        let three = T::one() + T::one() + T::one();
+        let segments = (0..ends as isize)
+            .map(|i| {
+                let (p0, p1, p2, p3) = (point(i - 1), point(i), point(i + 1), point(i + 2));
+                let (d0, d1, d2) = (knot(&p0, &p1), knot(&p1, &p2), knot(&p2, &p3));
+                let begin_tangent = (p2 - p1) +
+                    ((p1 - p0).scale(T::one() / d0) - (p2 - p0).scale(T::one() / (d0 + d1)))
+                        .scale(d1);
+                let end_tangent = (p2 - p1) +
+                    ((p3 - p2).scale(T::one() / d2) - (p3 - p1).scale(T::one() / (d1 + d2)))
+                        .scale(d1);
+                [
+                    p1,
+                    p1 + begin_tangent.scale(T::one() / three),
+                    p2 - end_tangent.scale(T::one() / three),
+                    p2,
+                ]
+            })
+            .collect();
+        Ok(SplinePath::from_segments(segments, open))
+    }
+
+    fn from_segments(segments: Vec<[V; 4]>, open: bool) -> SplinePath<T, V> {
+        let mut path = SplinePath {
+            segments: segments,
+            open: open,
+            samples: Vec::new(),
+            lengths: Vec::new(),
+        };
+        let sample_count = path.segments.len() * SAMPLES + 1;
+        let step = T::one() / T::from_usize(SAMPLES).unwrap();
+        path.samples = (0..sample_count)
+            .map(|k| path.point_at(T::from_usize(k).unwrap() * step))
+            .collect();
+        // three point Gauss-Legendre quadrature of the speed between the samples
+        let five = T::from_usize(5).unwrap();
+        let offset = (T::from_usize(3).unwrap() / five).sqrt();
+        let weights = [five, T::from_usize(8).unwrap(), five];
+        let nine = T::from_usize(9).unwrap();
+        let half_step = step / (T::one() + T::one());
+        let mut length = T::zero();
+        path.lengths.push(length);
+        for k in 0..sample_count - 1 {
+            let middle = (T::from_usize(k).unwrap() + T::one() / (T::one() + T::one())) * step;
+            for (node, weight) in [-offset, T::zero(), offset].iter().zip(weights.iter()) {
+                let speed = path.tangent_at(middle + *node * half_step).norm();
+                length += speed * *weight / nine * half_step;
+            }
+            path.lengths.push(length);
+        }
+        path
+    }
+
+    /// returns the number of curve segments
+    pub fn get_segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// returns the point at given parameter
+    pub fn point_at(&self, parameter: T) -> V {
+        let (segment, t) = self.split(parameter);
+        let [p0, p1, p2, p3] = self.segments[segment];
+        let s = T::one() - t;
+        let three = T::one() + T::one() + T::one();
+        p0.scale(s * s * s) + p1.scale(three * s * s * t) + p2.scale(three * s * t * t) +
+            p3.scale(t * t * t)
+    }
+
+    /// returns the derivative of the curve at given parameter, it points along the
+    /// path
+    pub fn tangent_at(&self, parameter: T) -> V {
+        let (segment, t) = self.split(parameter);
+        let [p0, p1, p2, p3] = self.segments[segment];
+        let s = T::one() - t;
+        let three = T::one() + T::one() + T::one();
+        ((p1 - p0).scale(s * s) + (p2 - p1).scale((three - T::one()) * s * t) +
+            (p3 - p2).scale(t * t))
+            .scale(three)
+    }
+
+    /// returns the curvature at given parameter, the inverse of the radius of the
+    /// circle fitting the curve there
+    pub fn curvature_at(&self, parameter: T) -> T {
+        let (segment, t) = self.split(parameter);
+        let [p0, p1, p2, p3] = self.segments[segment];
+        let six = T::from_usize(6).unwrap();
+        let first = self.tangent_at(parameter);
+        let second = ((p2 - p1 - p1 + p0).scale(T::one() - t) + (p3 - p2 - p2 + p1).scale(t))
+            .scale(six);
+        let speed_squared = first.norm_squared();
+        if speed_squared.is_zero() {
+            return T::zero();
+        }
+        // length of the cross product of both derivatives in any dimension
+        let dot = first.dot(&second);
+        let cross_squared = speed_squared * second.norm_squared() - dot * dot;
+        cross_squared.max(T::zero()).sqrt() / (speed_squared * speed_squared.sqrt())
+    }
+
+    /// returns the parameter of the point at given distance along the path
+    pub fn parameter_at(&self, distance: T) -> T {
+        let length = self.get_length();
+        let distance = if self.open || length.is_zero() {
+            distance.max(T::zero()).min(length)
+        } else {
+            let wrapped = distance % length;
+            if wrapped < T::zero() {
+                wrapped + length
+            } else {
+                wrapped
+            }
+        };
+        // last sample not farther than the distance
+        let (mut low, mut high) = (0, self.lengths.len() - 1);
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+            if self.lengths[middle] <= distance {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        let span = self.lengths[high] - self.lengths[low];
+        let fraction = if span.is_zero() {
+            T::zero()
+        } else {
+            ((distance - self.lengths[low]) / span).min(T::one())
+        };
+        (T::from_usize(low).unwrap() + fraction) / T::from_usize(SAMPLES).unwrap()
+    }
+
+    /// returns the distance along the path of the point at given parameter
+    pub fn distance_at(&self, parameter: T) -> T {
+        let position = self.clamp(parameter) * T::from_usize(SAMPLES).unwrap();
+        let low: f64 = position.floor().to_subset().unwrap_or(0.0);
+        let low = (low.max(0.0) as usize).min(self.lengths.len() - 2);
+        let fraction = position - T::from_usize(low).unwrap();
+        self.lengths[low] + (self.lengths[low + 1] - self.lengths[low]) * fraction
+    }
+
+    /// returns the parameter of the point of the path closest to given position
+    pub fn closest_parameter(&self, position: &V) -> T {
+        let mut closest = 0;
+        for (k, sample) in self.samples.iter().enumerate() {
+            if (*sample - *position).norm_squared() <
+                (self.samples[closest] - *position).norm_squared()
+            {
+                closest = k;
+            }
+        }
+        // bisect between the neighbor samples of the closest one where the offset
+        // from the position turns from pointing backwards along the path to forwards
+        let step = T::one() / T::from_usize(SAMPLES).unwrap();
+        let closest = T::from_usize(closest).unwrap() * step;
+        let mut low = self.clamp(closest - step);
+        let mut high = self.clamp(closest + step);
+        let two = T::one() + T::one();
+        for _ in 0..24 {
+            let middle = (low + high) / two;
+            let offset = self.point_at(middle) - *position;
+            if offset.dot(&self.tangent_at(middle)) < T::zero() {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+        (low + high) / two
+    }
+
+    /// returns the parameter clamped to the ends of the path
+    fn clamp(&self, parameter: T) -> T {
+        parameter
+            .max(T::zero())
+            .min(T::from_usize(self.segments.len()).unwrap())
+    }
+
+    /// returns the segment of a parameter and the parameter within the segment
+    fn split(&self, parameter: T) -> (usize, T) {
+        let parameter = self.clamp(parameter);
+        let segment: f64 = parameter.floor().to_subset().unwrap_or(0.0);
+        let segment = (segment.max(0.0) as usize).min(self.segments.len() - 1);
+        (segment, parameter - T::from_usize(segment).unwrap())
+    }
+}
+
+/// returns the knot interval of centripetal parameterization
+fn knot<T: Real, V: SteeringVector<T>>(from: &V, to: &V) -> T {
+    from.distance(to).sqrt()
+}
+
+impl<T: Real, V: SteeringVector<T>> Path<T, V> for SplinePath<T, V> {
+    fn calculate_distance(&self, position: &V) -> T {
+        self.distance_at(self.closest_parameter(position))
+    }
+
+    fn calculate_target_position(&self, distance: T) -> V {
+        self.point_at(self.parameter_at(distance))
+    }
+
+    fn calculate_curvature(&self, distance: T) -> T {
+        self.curvature_at(self.parameter_at(distance))
+    }
+
+    fn get_length(&self) -> T {
+        self.lengths[self.lengths.len() - 1]
+    }
+
+    fn is_open(&self) -> bool {
+        self.open
+    }
+
+    fn get_start_point(&self) -> V {
+        self.segments[0][0]
+    }
+
+    fn get_end_point(&self) -> V {
+        self.segments[self.segments.len() - 1][3]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SplinePath;
+    use {LinePath, Path};
+    use nalgebra::{Vector2, Vector3};
+
+    #[test]
+    fn straight_bezier() {
+        let path = SplinePath::bezier(
+            vec![
+                Vector2::new(0.0f32, 0.0),
+                Vector2::new(1.0, 0.0),
+                Vector2::new(2.0, 0.0),
+                Vector2::new(3.0, 0.0),
+            ],
+            true,
+        ).unwrap();
+        assert!((path.get_length() - 3.0).abs() < 1e-5);
+        assert!((path.calculate_target_position(1.5) - Vector2::new(1.5, 0.0)).norm() < 1e-5);
+        assert!((path.calculate_distance(&Vector2::new(1.0, 5.0)) - 1.0).abs() < 1e-4);
+        assert_eq!(0.0, path.calculate_curvature(1.0));
+        assert_eq!(Vector2::new(3.0, 0.0), path.calculate_target_position(7.0));
+        assert!(SplinePath::bezier(vec![Vector2::new(0.0f32, 0.0); 5], true).is_err());
+        assert!(SplinePath::bezier(vec![Vector2::new(0.0f32, 0.0); 4], false).is_err());
+        assert_eq!(0.0, LinePath::new(vec![Vector2::new(0.0f32, 0.0); 2], true)
+            .unwrap()
+            .calculate_curvature(0.0));
+    }
+
+    #[test]
+    fn circle_of_bezier_arcs() {
+        // four arcs approximating a circle of radius 10
+        let k = 10.0f32 * 0.552_284_8;
+        let path = SplinePath::bezier(
+            vec![
+                Vector3::new(10.0, 0.0, 1.0),
+                Vector3::new(10.0, k, 1.0),
+                Vector3::new(k, 10.0, 1.0),
+                Vector3::new(0.0, 10.0, 1.0),
+                Vector3::new(-k, 10.0, 1.0),
+                Vector3::new(-10.0, k, 1.0),
+                Vector3::new(-10.0, 0.0, 1.0),
+                Vector3::new(-10.0, -k, 1.0),
+                Vector3::new(-k, -10.0, 1.0),
+                Vector3::new(0.0, -10.0, 1.0),
+                Vector3::new(k, -10.0, 1.0),
+                Vector3::new(10.0, -k, 1.0),
+            ],
+            false,
+        ).unwrap();
+        let circumference = 20.0 * ::std::f32::consts::PI;
+        assert_eq!(4, path.get_segment_count());
+        assert!((path.get_length() - circumference).abs() < 0.01);
+        for i in 0..20 {
+            let distance = i as f32 * 3.7;
+            // the arcs bend a few percent more or less than the circle
+            assert!((path.calculate_curvature(distance) - 0.1).abs() < 0.005);
+            let point = path.calculate_target_position(distance);
+            let wrapped = distance % path.get_length();
+            assert!((path.calculate_distance(&(point * 1.2)) - wrapped).abs() < 0.01);
+        }
+        assert!((path.calculate_target_position(-circumference / 4.0) -
+            Vector3::new(0.0, -10.0, 1.0)).norm() < 0.01);
+    }
+
+    #[test]
+    fn catmull_rom_passes_through_waypoints() {
+        let waypoints = vec![
+            Vector2::new(0.0f32, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(9.0, 1.0),
+            Vector2::new(9.0, 8.0),
+        ];
+        let path = SplinePath::catmull_rom(waypoints.clone(), true).unwrap();
+        assert_eq!(3, path.get_segment_count());
+        for (i, waypoint) in waypoints.iter().enumerate() {
+            assert!((path.point_at(i as f32) - waypoint).norm() < 1e-5);
+        }
+        // tangents are continuous at the waypoints
+        let before = path.tangent_at(0.999_9).normalize();
+        let after = path.tangent_at(1.000_1).normalize();
+        assert!((before - after).norm() < 1e-2);
+        // the short first segment does not overshoot the second waypoint
+        for i in 0..11 {
+            assert!(path.point_at(i as f32 * 0.1).x <= 1.0 + 1e-5);
+        }
+
+        let closed = SplinePath::catmull_rom(waypoints, false).unwrap();
+        assert_eq!(4, closed.get_segment_count());
+        assert_eq!(closed.get_start_point(), closed.get_end_point());
+        assert!(SplinePath::catmull_rom(vec![Vector2::new(1.0f32, 1.0); 2], true).is_err());
+        assert!(SplinePath::catmull_rom(vec![Vector2::new(1.0f32, 1.0)], true).is_err());
+    }
+}
@@ -0,0 +1,3 @@
+mod radius_proximity;
+
+pub use self::radius_proximity::RadiusProximity;
@@ -0,0 +1,49 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {Proximity, Steerable, SteeringVector};
+use proximity::is_owner;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Proximity reporting agents of a list whose bounding circle overlaps a circle of
+/// given radius around the owner. Every query scans the whole list, use
+/// `spatial::SpatialHashProximity` for large groups.
+pub struct RadiusProximity<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// candidate neighbors
+    pub agents: Vec<Rc<RefCell<Steerable<T, V>>>>,
+    /// radius of the neighborhood
+    pub radius: T,
+}
+
+impl<T: Real, V: SteeringVector<T>> RadiusProximity<T, V> {
+    /// Creates a proximity of given agents
+    pub fn new(agents: Vec<Rc<RefCell<Steerable<T, V>>>>, radius: T) -> RadiusProximity<T, V> {
+        RadiusProximity {
+            agents: agents,
+            radius: radius,
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Proximity<T, V> for RadiusProximity<T, V> {
+    fn find_neighbors(
+        &self,
+        owner: &Steerable<T, V>,
+        callback: &mut FnMut(&Steerable<T, V>) -> bool,
+    ) -> usize {
+        let mut count = 0;
+        for agent in self.agents.iter() {
+            if is_owner(agent, owner) {
+                continue;
+            }
+            let agent = agent.borrow();
+            let range = self.radius + agent.get_bounding_radius();
+            let square_distance = (*agent.get_position() - *owner.get_position()).norm_squared();
+            if square_distance < range * range && callback(&*agent) {
+                count += 1;
+            }
+        }
+        count
+    }
+}
@@ -0,0 +1,27 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {Steerable, SteeringVector};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Source of the neighbors of an agent used by group behaviors such as
+/// Separation, Cohesion and CollisionAvoidance
+pub trait Proximity<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// Calls given function for every neighbor of the owner, the owner itself is
+    /// never reported. Returns the number of neighbors for which the function
+    /// returned true.
+    fn find_neighbors(
+        &self,
+        owner: &Steerable<T, V>,
+        callback: &mut FnMut(&Steerable<T, V>) -> bool,
+    ) -> usize;
+}
+
+/// Tests whether given agent is the owner without borrowing the agent
+pub fn is_owner<T: Real, V: SteeringVector<T>>(
+    agent: &Rc<RefCell<Steerable<T, V>>>,
+    owner: &Steerable<T, V>,
+) -> bool {
+    agent.as_ptr() as *const u8 == owner as *const Steerable<T, V> as *const u8
+}
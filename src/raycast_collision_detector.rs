@@ -0,0 +1,82 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use SteeringVector;
+
+use std::marker::PhantomData;
+
+/// Line segment cast from `start` to `end` to detect obstacles
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Ray<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// point the ray is cast from
+    pub start: V,
+    /// point the ray ends at
+    pub end: V,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Real, V: SteeringVector<T>> Ray<T, V> {
+    /// Creates a ray between given points
+    pub fn new(start: V, end: V) -> Ray<T, V> {
+        Ray {
+            start: start,
+            end: end,
+            phantom: PhantomData,
+        }
+    }
+
+    /// returns the length of the ray
+    pub fn get_length(&self) -> T {
+        self.start.distance(&self.end)
+    }
+
+    /// returns the point of the ray closest to given point
+    pub fn closest_point(&self, point: &V) -> V {
+        let direction = self.end - self.start;
+        let square_length = direction.norm_squared();
+        if square_length.is_zero() {
+            return self.start;
+        }
+        let mut fraction = (*point - self.start).dot(&direction) / square_length;
+        if fraction < T::zero() {
+            fraction = T::zero();
+        } else if fraction > T::one() {
+            fraction = T::one();
+        }
+        self.start + direction.scale(fraction)
+    }
+}
+
+/// Point where a ray hits an obstacle
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Collision<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// point of the obstacle surface hit first
+    pub point: V,
+    /// unit normal of the obstacle surface at the collision point
+    pub normal: V,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Real, V: SteeringVector<T>> Collision<T, V> {
+    /// Creates a collision at given point
+    pub fn new(point: V, normal: V) -> Collision<T, V> {
+        Collision {
+            point: point,
+            normal: normal,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// Finds obstacles along rays, implement it with your physics engine or use one of
+/// the obstacle trees of the `spatial` module
+pub trait RaycastCollisionDetector<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// Tests whether the ray hits any obstacle
+    fn collides(&self, ray: &Ray<T, V>) -> bool {
+        self.find_collision(ray).is_some()
+    }
+
+    /// returns the collision closest to the start of the ray
+    fn find_collision(&self, ray: &Ray<T, V>) -> Option<Collision<T, V>>;
+}
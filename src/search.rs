@@ -0,0 +1,35 @@
+use alga::general::Real;
+
+use std::cmp::Ordering;
+
+/// Entry of the open set of a best first search, ordered so that a `BinaryHeap`
+/// pops the lowest priority first
+pub struct Open<T: Real> {
+    /// estimated total cost of a path through the node
+    pub priority: T,
+    /// index of the node
+    pub index: usize,
+}
+
+impl<T: Real> PartialEq for Open<T> {
+    fn eq(&self, other: &Open<T>) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<T: Real> Eq for Open<T> {}
+
+impl<T: Real> PartialOrd for Open<T> {
+    fn partial_cmp(&self, other: &Open<T>) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Real> Ord for Open<T> {
+    fn cmp(&self, other: &Open<T>) -> Ordering {
+        other
+            .priority
+            .partial_cmp(&self.priority)
+            .unwrap_or(Ordering::Equal)
+    }
+}
@@ -0,0 +1,231 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {Collision, Ray, RaycastCollisionDetector, SteeringVector};
+use obstacle::{Obstacle, ObstacleSet};
+use super::{closer_collision, compare, widest_axis, Shape};
+
+use std::rc::Rc;
+
+/// Number of obstacles a leaf holds at most
+const LEAF_SIZE: usize = 4;
+
+struct Node<T: Real, V: SteeringVector<T>> {
+    center: V,
+    radius: T,
+    children: Option<(usize, usize)>,
+    obstacles: Vec<usize>,
+}
+
+/// Bounding volume hierarchy of a static obstacle set. Every node is bounded by a
+/// circle enclosing the bounding circles of its obstacles, so obstacles of very
+/// different sizes are handled well.
+pub struct Bvh<T, V = Vector3<T>, O: ?Sized = Shape<T, V>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+    O: Shape<T, V>,
+{
+    obstacles: Vec<Rc<O>>,
+    nodes: Vec<Node<T, V>>,
+    root: Option<usize>,
+}
+
+impl<T: Real, V: SteeringVector<T>, O: ?Sized + Shape<T, V>> Bvh<T, V, O> {
+    /// Builds the hierarchy, obstacles are addressed by their index in given list
+    pub fn new(obstacles: Vec<Rc<O>>) -> Bvh<T, V, O> {
+        let mut indices: Vec<usize> = (0..obstacles.len()).collect();
+        let mut nodes = Vec::new();
+        let root = if indices.is_empty() {
+            None
+        } else {
+            Some(build(&mut nodes, &obstacles, &mut indices))
+        };
+        Bvh {
+            obstacles: obstacles,
+            nodes: nodes,
+            root: root,
+        }
+    }
+
+    /// returns the obstacles of the hierarchy
+    pub fn get_obstacles(&self) -> &[Rc<O>] {
+        &self.obstacles
+    }
+
+    /// returns the number of obstacles
+    pub fn len(&self) -> usize {
+        self.obstacles.len()
+    }
+
+    /// Tests whether the hierarchy has no obstacle
+    pub fn is_empty(&self) -> bool {
+        self.obstacles.is_empty()
+    }
+
+    /// returns the index of the obstacle whose boundary is closest to given point
+    /// and its signed distance
+    pub fn nearest(&self, point: &V) -> Option<(usize, T)> {
+        let mut nearest = None;
+        if let Some(root) = self.root {
+            self.nearest_in(root, point, &mut nearest);
+        }
+        nearest
+    }
+
+    /// returns the indices of obstacles whose boundary is within given distance of
+    /// the point, in no particular order
+    pub fn query_radius(&self, point: &V, radius: T) -> Vec<usize> {
+        let mut found = Vec::new();
+        if let Some(root) = self.root {
+            self.radius_in(root, point, radius, &mut found);
+        }
+        found
+    }
+
+    /// returns the index of the obstacle hit first by the ray and the collision
+    pub fn raycast(&self, ray: &Ray<T, V>) -> Option<(usize, Collision<T, V>)> {
+        let mut closest = None;
+        if let Some(root) = self.root {
+            self.raycast_in(root, ray, &mut closest);
+        }
+        closest.map(|(index, _, collision)| (index, collision))
+    }
+
+    /// returns a lower bound of the distance between given point and the obstacles
+    /// of the node
+    fn bound(&self, node: usize, point: &V) -> T {
+        let node = &self.nodes[node];
+        point.distance(&node.center) - node.radius
+    }
+
+    fn nearest_in(&self, node: usize, point: &V, nearest: &mut Option<(usize, T)>) {
+        match self.nodes[node].children {
+            Some((first, second)) => {
+                let first_bound = self.bound(first, point);
+                let second_bound = self.bound(second, point);
+                let children = if first_bound <= second_bound {
+                    [(first, first_bound), (second, second_bound)]
+                } else {
+                    [(second, second_bound), (first, first_bound)]
+                };
+                for &(child, bound) in children.iter() {
+                    if nearest.map_or(true, |nearest| bound < nearest.1) {
+                        self.nearest_in(child, point, nearest);
+                    }
+                }
+            }
+            None => for &index in self.nodes[node].obstacles.iter() {
+                let distance = self.obstacles[index].signed_distance(point);
+                if nearest.map_or(true, |nearest| distance < nearest.1) {
+                    *nearest = Some((index, distance));
+                }
+            },
+        }
+    }
+
+    fn radius_in(&self, node: usize, point: &V, radius: T, found: &mut Vec<usize>) {
+        if self.bound(node, point) > radius {
+            return;
+        }
+        match self.nodes[node].children {
+            Some((first, second)) => {
+                self.radius_in(first, point, radius, found);
+                self.radius_in(second, point, radius, found);
+            }
+            None => for &index in self.nodes[node].obstacles.iter() {
+                if self.obstacles[index].signed_distance(point) <= radius {
+                    found.push(index);
+                }
+            },
+        }
+    }
+
+    fn raycast_in(
+        &self,
+        node: usize,
+        ray: &Ray<T, V>,
+        closest: &mut Option<(usize, T, Collision<T, V>)>,
+    ) {
+        let center = self.nodes[node].center;
+        if ray.closest_point(&center).distance(&center) > self.nodes[node].radius {
+            return;
+        }
+        match self.nodes[node].children {
+            Some((first, second)) => {
+                self.raycast_in(first, ray, closest);
+                self.raycast_in(second, ray, closest);
+            }
+            None => for &index in self.nodes[node].obstacles.iter() {
+                let collision = self.obstacles[index].intersect_ray(ray);
+                closer_collision(closest, ray, index, collision);
+            },
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> ObstacleSet<T, V> for Bvh<T, V, Obstacle<T, V>> {
+    fn find_obstacles(&self, point: &V, radius: T, callback: &mut FnMut(&Obstacle<T, V>)) {
+        for index in self.query_radius(point, radius) {
+            callback(&*self.obstacles[index]);
+        }
+    }
+}
+
+impl<T, V, O> RaycastCollisionDetector<T, V> for Bvh<T, V, O>
+where
+    T: Real,
+    V: SteeringVector<T>,
+    O: ?Sized + Shape<T, V>,
+{
+    fn find_collision(&self, ray: &Ray<T, V>) -> Option<Collision<T, V>> {
+        self.raycast(ray).map(|(_, collision)| collision)
+    }
+}
+
+/// Builds the subtree of given obstacles, splitting them at the median center along
+/// the axis the centers are spread the most
+fn build<T: Real, V: SteeringVector<T>, O: ?Sized + Shape<T, V>>(
+    nodes: &mut Vec<Node<T, V>>,
+    obstacles: &[Rc<O>],
+    indices: &mut [usize],
+) -> usize {
+    let centers: Vec<V> = indices.iter().map(|index| obstacles[*index].get_center()).collect();
+    let mut center = V::zero();
+    for point in centers.iter() {
+        center += *point;
+    }
+    center = center.scale(T::one() / T::from_usize(centers.len()).unwrap());
+    let mut radius = T::zero();
+    for index in indices.iter() {
+        let obstacle = &obstacles[*index];
+        let reach = obstacle.get_center().distance(&center) + obstacle.get_bounding_radius();
+        if reach > radius {
+            radius = reach;
+        }
+    }
+
+    let children = if indices.len() > LEAF_SIZE {
+        let axis = widest_axis(&centers);
+        indices.sort_by(|a, b| {
+            compare(
+                &obstacles[*a].get_center().component(axis),
+                &obstacles[*b].get_center().component(axis),
+            )
+        });
+        let (first, second) = indices.split_at_mut(indices.len() / 2);
+        Some((build(nodes, obstacles, first), build(nodes, obstacles, second)))
+    } else {
+        None
+    };
+    nodes.push(Node {
+        center: center,
+        radius: radius,
+        children: children,
+        obstacles: if children.is_none() {
+            indices.to_vec()
+        } else {
+            Vec::new()
+        },
+    });
+    nodes.len() - 1
+}
@@ -0,0 +1,289 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {Collision, Ray, RaycastCollisionDetector, SteeringVector};
+use obstacle::{Obstacle, ObstacleSet};
+use super::{closer_collision, compare, widest_axis, Shape};
+
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+struct Node<T: Real> {
+    obstacle: usize,
+    axis: usize,
+    split: T,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+/// KD-tree over the centers of a static obstacle set. Queries are answered in
+/// logarithmic time for obstacles of similar size, a few large obstacles among many
+/// small ones are better served by a `Bvh`.
+pub struct KdTree<T, V = Vector3<T>, O: ?Sized = Shape<T, V>>
+where
+    T: Real,
+    V: SteeringVector<T>,
+    O: Shape<T, V>,
+{
+    obstacles: Vec<Rc<O>>,
+    nodes: Vec<Node<T>>,
+    root: Option<usize>,
+    max_bounding_radius: T,
+    phantom: PhantomData<V>,
+}
+
+impl<T: Real, V: SteeringVector<T>, O: ?Sized + Shape<T, V>> KdTree<T, V, O> {
+    /// Builds the tree, obstacles are addressed by their index in given list
+    pub fn new(obstacles: Vec<Rc<O>>) -> KdTree<T, V, O> {
+        let mut max_bounding_radius = T::zero();
+        for obstacle in obstacles.iter() {
+            if obstacle.get_bounding_radius() > max_bounding_radius {
+                max_bounding_radius = obstacle.get_bounding_radius();
+            }
+        }
+        let centers: Vec<V> = obstacles.iter().map(|obstacle| obstacle.get_center()).collect();
+        let mut indices: Vec<usize> = (0..obstacles.len()).collect();
+        let mut nodes = Vec::with_capacity(obstacles.len());
+        let root = build(&mut nodes, &centers, &mut indices);
+        KdTree {
+            obstacles: obstacles,
+            nodes: nodes,
+            root: root,
+            max_bounding_radius: max_bounding_radius,
+            phantom: PhantomData,
+        }
+    }
+
+    /// returns the obstacles of the tree
+    pub fn get_obstacles(&self) -> &[Rc<O>] {
+        &self.obstacles
+    }
+
+    /// returns the number of obstacles
+    pub fn len(&self) -> usize {
+        self.obstacles.len()
+    }
+
+    /// Tests whether the tree has no obstacle
+    pub fn is_empty(&self) -> bool {
+        self.obstacles.is_empty()
+    }
+
+    /// returns the index of the obstacle whose boundary is closest to given point
+    /// and its signed distance
+    pub fn nearest(&self, point: &V) -> Option<(usize, T)> {
+        let mut nearest = None;
+        self.nearest_in(self.root, point, &mut nearest);
+        nearest
+    }
+
+    /// returns the indices of obstacles whose boundary is within given distance of
+    /// the point, in no particular order
+    pub fn query_radius(&self, point: &V, radius: T) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.radius_in(self.root, point, radius, &mut found);
+        found
+    }
+
+    /// returns the index of the obstacle hit first by the ray and the collision
+    pub fn raycast(&self, ray: &Ray<T, V>) -> Option<(usize, Collision<T, V>)> {
+        let mut closest = None;
+        self.raycast_in(self.root, ray, &mut closest);
+        closest.map(|(index, _, collision)| (index, collision))
+    }
+
+    fn nearest_in(&self, node: Option<usize>, point: &V, nearest: &mut Option<(usize, T)>) {
+        let node = match node {
+            Some(node) => &self.nodes[node],
+            None => return,
+        };
+        let distance = self.obstacles[node.obstacle].signed_distance(point);
+        if nearest.map_or(true, |nearest| distance < nearest.1) {
+            *nearest = Some((node.obstacle, distance));
+        }
+        let offset = point.component(node.axis) - node.split;
+        let (near, far) = if offset < T::zero() {
+            (node.left, node.right)
+        } else {
+            (node.right, node.left)
+        };
+        self.nearest_in(near, point, nearest);
+        // obstacles beyond the split plane are at least this far away
+        let bound = offset.abs() - self.max_bounding_radius;
+        if nearest.map_or(true, |nearest| bound < nearest.1) {
+            self.nearest_in(far, point, nearest);
+        }
+    }
+
+    fn radius_in(&self, node: Option<usize>, point: &V, radius: T, found: &mut Vec<usize>) {
+        let node = match node {
+            Some(node) => &self.nodes[node],
+            None => return,
+        };
+        if self.obstacles[node.obstacle].signed_distance(point) <= radius {
+            found.push(node.obstacle);
+        }
+        let value = point.component(node.axis);
+        let reach = radius + self.max_bounding_radius;
+        if value - reach <= node.split {
+            self.radius_in(node.left, point, radius, found);
+        }
+        if value + reach >= node.split {
+            self.radius_in(node.right, point, radius, found);
+        }
+    }
+
+    fn raycast_in(
+        &self,
+        node: Option<usize>,
+        ray: &Ray<T, V>,
+        closest: &mut Option<(usize, T, Collision<T, V>)>,
+    ) {
+        let node = match node {
+            Some(node) => &self.nodes[node],
+            None => return,
+        };
+        let collision = self.obstacles[node.obstacle].intersect_ray(ray);
+        closer_collision(closest, ray, node.obstacle, collision);
+        let start = ray.start.component(node.axis);
+        let end = ray.end.component(node.axis);
+        let (min, max) = if start < end { (start, end) } else { (end, start) };
+        if min - self.max_bounding_radius <= node.split {
+            self.raycast_in(node.left, ray, closest);
+        }
+        if max + self.max_bounding_radius >= node.split {
+            self.raycast_in(node.right, ray, closest);
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> ObstacleSet<T, V> for KdTree<T, V, Obstacle<T, V>> {
+    fn find_obstacles(&self, point: &V, radius: T, callback: &mut FnMut(&Obstacle<T, V>)) {
+        for index in self.query_radius(point, radius) {
+            callback(&*self.obstacles[index]);
+        }
+    }
+}
+
+impl<T, V, O> RaycastCollisionDetector<T, V> for KdTree<T, V, O>
+where
+    T: Real,
+    V: SteeringVector<T>,
+    O: ?Sized + Shape<T, V>,
+{
+    fn find_collision(&self, ray: &Ray<T, V>) -> Option<Collision<T, V>> {
+        self.raycast(ray).map(|(_, collision)| collision)
+    }
+}
+
+/// Builds the subtree of given obstacles split at the median center along the axis
+/// the centers are spread the most
+fn build<T: Real, V: SteeringVector<T>>(
+    nodes: &mut Vec<Node<T>>,
+    centers: &[V],
+    indices: &mut [usize],
+) -> Option<usize> {
+    if indices.is_empty() {
+        return None;
+    }
+    let points: Vec<V> = indices.iter().map(|index| centers[*index]).collect();
+    let axis = widest_axis(&points);
+    indices.sort_by(|a, b| {
+        compare(&centers[*a].component(axis), &centers[*b].component(axis))
+    });
+    let median = indices.len() / 2;
+    let obstacle = indices[median];
+    let (left_indices, rest) = indices.split_at_mut(median);
+    let left = build(nodes, centers, left_indices);
+    let right = build(nodes, centers, &mut rest[1..]);
+    nodes.push(Node {
+        obstacle: obstacle,
+        axis: axis,
+        split: centers[obstacle].component(axis),
+        left: left,
+        right: right,
+    });
+    Some(nodes.len() - 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::KdTree;
+    use super::super::Bvh;
+    use obstacle::{Circle, Obstacle};
+    use {Ray, RaycastCollisionDetector};
+    use nalgebra::Vector2;
+    use quickcheck::quickcheck;
+    use std::rc::Rc;
+
+    fn circles(seeds: &[(i8, i8, u8)]) -> Vec<Rc<Obstacle<f32, Vector2<f32>>>> {
+        seeds
+            .iter()
+            .map(|&(x, y, r)| {
+                Rc::new(Circle::new(
+                    Vector2::new(x as f32 * 0.5, y as f32 * 0.5),
+                    (r % 16) as f32 * 0.25 + 0.1,
+                )) as Rc<Obstacle<f32, Vector2<f32>>>
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_obstacles() {
+        let tree = KdTree::new(circles(&[(0, 0, 3), (20, 0, 3), (0, 20, 3), (20, 20, 15)]));
+        let point = Vector2::new(9.0f32, 9.0);
+        assert_eq!(3, tree.nearest(&point).unwrap().0);
+        let mut near = tree.query_radius(&Vector2::new(0.0, 0.0), 5.0);
+        near.sort();
+        assert_eq!(vec![0], near);
+        let ray = Ray::new(Vector2::new(-5.0f32, 0.0), Vector2::new(20.0, 0.0));
+        let (index, collision) = tree.raycast(&ray).unwrap();
+        assert_eq!(0, index);
+        assert!((collision.point - Vector2::new(-0.85, 0.0)).norm() < 1e-6);
+        assert!(!tree.collides(&Ray::new(Vector2::new(3.0, 3.0), Vector2::new(5.0, 5.0))));
+    }
+
+    #[test]
+    fn trees_match_brute_force() {
+        fn prop(seeds: Vec<(i8, i8, u8)>, point: (i8, i8), end: (i8, i8), radius: u8) -> bool {
+            let obstacles = circles(&seeds);
+            let kd_tree = KdTree::new(obstacles.clone());
+            let bvh = Bvh::new(obstacles.clone());
+            let point = Vector2::new(point.0 as f32 * 0.5, point.1 as f32 * 0.5);
+            let end = Vector2::new(end.0 as f32 * 0.5, end.1 as f32 * 0.5);
+            let radius = radius as f32 * 0.1;
+
+            let distances: Vec<f32> = obstacles
+                .iter()
+                .map(|obstacle| obstacle.signed_distance(&point))
+                .collect();
+            let nearest = distances.iter().cloned().fold(None, |nearest: Option<f32>, d| {
+                Some(nearest.map_or(d, |nearest| nearest.min(d)))
+            });
+            let mut within: Vec<usize> = (0..obstacles.len())
+                .filter(|index| distances[*index] <= radius)
+                .collect();
+
+            let ray = Ray::new(point, end);
+            let hit = obstacles
+                .iter()
+                .filter_map(|obstacle| obstacle.intersect_ray(&ray))
+                .map(|collision| (collision.point - point).norm())
+                .fold(None, |closest: Option<f32>, d| {
+                    Some(closest.map_or(d, |closest| closest.min(d)))
+                });
+
+            let mut kd_within = kd_tree.query_radius(&point, radius);
+            let mut bvh_within = bvh.query_radius(&point, radius);
+            kd_within.sort();
+            bvh_within.sort();
+            within.sort();
+            let kd_hit = kd_tree.find_collision(&ray).map(|c| (c.point - point).norm());
+            let bvh_hit = bvh.find_collision(&ray).map(|c| (c.point - point).norm());
+            kd_tree.nearest(&point).map(|nearest| nearest.1) == nearest &&
+                bvh.nearest(&point).map(|nearest| nearest.1) == nearest &&
+                kd_within == within && bvh_within == within && kd_hit == hit &&
+                bvh_hit == hit
+        }
+        quickcheck(prop as fn(Vec<(i8, i8, u8)>, (i8, i8), (i8, i8), u8) -> bool);
+    }
+}
@@ -0,0 +1,59 @@
+//! Spatial indices answering neighborhood queries over large groups of agents and
+//! static obstacle sets
+mod spatial_hash;
+mod spatial_hash_proximity;
+mod shape;
+mod kd_tree;
+mod bvh;
+
+pub use self::spatial_hash::{AgentId, SpatialHash};
+pub use self::spatial_hash_proximity::SpatialHashProximity;
+pub use self::shape::Shape;
+pub use self::kd_tree::KdTree;
+pub use self::bvh::Bvh;
+
+use alga::general::Real;
+use {Collision, Ray, SteeringVector};
+
+use std::cmp::Ordering;
+
+fn compare<T: Real>(first: &T, second: &T) -> Ordering {
+    first.partial_cmp(second).unwrap_or(Ordering::Equal)
+}
+
+/// returns the axis along which given points are spread the most
+fn widest_axis<T: Real, V: SteeringVector<T>>(points: &[V]) -> usize {
+    let mut widest = (0, T::zero());
+    for axis in 0..V::dimension() {
+        let mut min = points[0].component(axis);
+        let mut max = min;
+        for point in points.iter() {
+            let value = point.component(axis);
+            if value < min {
+                min = value;
+            }
+            if value > max {
+                max = value;
+            }
+        }
+        if max - min > widest.1 {
+            widest = (axis, max - min);
+        }
+    }
+    widest.0
+}
+
+/// Keeps the collision closest to the start of the ray
+fn closer_collision<T: Real, V: SteeringVector<T>>(
+    closest: &mut Option<(usize, T, Collision<T, V>)>,
+    ray: &Ray<T, V>,
+    index: usize,
+    collision: Option<Collision<T, V>>,
+) {
+    if let Some(collision) = collision {
+        let square_distance = (collision.point - ray.start).norm_squared();
+        if closest.as_ref().map_or(true, |closest| square_distance < closest.1) {
+            *closest = Some((index, square_distance, collision));
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {Collision, Ray, SteeringVector};
+
+/// Static shape stored in a `KdTree` or a `Bvh`. Trees prune their queries with the
+/// bounding circle of the shape and ask the shape itself for exact answers.
+pub trait Shape<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// returns the center of the bounding circle of the shape
+    fn get_center(&self) -> V;
+
+    /// returns the radius of the bounding circle of the shape
+    fn get_bounding_radius(&self) -> T;
+
+    /// returns the distance between given point and the shape boundary, negative if
+    /// the point is inside the shape
+    fn signed_distance(&self, point: &V) -> T;
+
+    /// returns where the ray enters the shape, rays starting inside the shape do
+    /// not collide with it
+    fn intersect_ray(&self, ray: &Ray<T, V>) -> Option<Collision<T, V>>;
+}
@@ -0,0 +1,444 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {Steerable, SteeringError, SteeringVector};
+use validation;
+use super::compare;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+type Cell = (i64, i64, i64);
+
+/// Handle of an agent inserted into a `SpatialHash`, handles of removed agents are
+/// reused by later insertions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AgentId(usize);
+
+struct Entry<T: Real, V: SteeringVector<T>> {
+    agent: Rc<RefCell<Steerable<T, V>>>,
+    position: V,
+    bounding_radius: T,
+    cell: Cell,
+}
+
+/// Uniform grid bucketing agents by their position. Positions are read from
+/// `Steerable::get_position` when an agent is inserted or updated, queries use
+/// these recorded positions so call `update` or `update_all` after moving agents.
+/// Planar agents use a single layer of cells.
+pub struct SpatialHash<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    cell_size: T,
+    cells: HashMap<Cell, Vec<AgentId>>,
+    entries: Vec<Option<Entry<T, V>>>,
+    free: Vec<usize>,
+    len: usize,
+    max_bounding_radius: T,
+}
+
+impl<T: Real, V: SteeringVector<T>> SpatialHash<T, V> {
+    /// Creates an empty hash, cell size should be close to the usual query radius
+    pub fn new(cell_size: T) -> Result<SpatialHash<T, V>, SteeringError> {
+        validation::positive("cell_size", Some(cell_size))
+            .map_err(SteeringError::InvalidParameter)?;
+        Ok(SpatialHash {
+            cell_size: cell_size,
+            cells: HashMap::new(),
+            entries: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+            max_bounding_radius: T::zero(),
+        })
+    }
+
+    /// returns the edge length of a cell
+    pub fn get_cell_size(&self) -> T {
+        self.cell_size
+    }
+
+    /// returns the number of agents in the hash
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Tests whether there is no agent in the hash
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// returns the largest bounding radius of agents ever inserted or updated
+    pub fn get_max_bounding_radius(&self) -> T {
+        self.max_bounding_radius
+    }
+
+    /// Inserts an agent at its current position
+    pub fn insert(&mut self, agent: Rc<RefCell<Steerable<T, V>>>) -> AgentId {
+        let (position, bounding_radius) = {
+            let agent = agent.borrow();
+            (*agent.get_position(), agent.get_bounding_radius())
+        };
+        let cell = self.cell_of(&position);
+        let entry = Entry {
+            agent: agent,
+            position: position,
+            bounding_radius: bounding_radius,
+            cell: cell,
+        };
+        let id = match self.free.pop() {
+            Some(index) => {
+                self.entries[index] = Some(entry);
+                AgentId(index)
+            }
+            None => {
+                self.entries.push(Some(entry));
+                AgentId(self.entries.len() - 1)
+            }
+        };
+        self.cells.entry(cell).or_insert_with(Vec::new).push(id);
+        self.len += 1;
+        if bounding_radius > self.max_bounding_radius {
+            self.max_bounding_radius = bounding_radius;
+        }
+        id
+    }
+
+    /// Removes an agent, returns `None` if there is no agent with given id
+    pub fn remove(&mut self, id: AgentId) -> Option<Rc<RefCell<Steerable<T, V>>>> {
+        let entry = match self.entries.get_mut(id.0) {
+            Some(entry) => entry.take(),
+            None => None,
+        };
+        entry.map(|entry| {
+            self.remove_from_cell(entry.cell, id);
+            self.free.push(id.0);
+            self.len -= 1;
+            entry.agent
+        })
+    }
+
+    /// Records the current position of an agent, returns false if there is no agent
+    /// with given id
+    pub fn update(&mut self, id: AgentId) -> bool {
+        let (old_cell, new_cell, bounding_radius) = match self.entries.get_mut(id.0) {
+            Some(&mut Some(ref mut entry)) => {
+                {
+                    let agent = entry.agent.borrow();
+                    entry.position = *agent.get_position();
+                    entry.bounding_radius = agent.get_bounding_radius();
+                }
+                let old_cell = entry.cell;
+                entry.cell = cell_of(self.cell_size, &entry.position);
+                (old_cell, entry.cell, entry.bounding_radius)
+            }
+            _ => return false,
+        };
+        if old_cell != new_cell {
+            self.remove_from_cell(old_cell, id);
+            self.cells.entry(new_cell).or_insert_with(Vec::new).push(id);
+        }
+        if bounding_radius > self.max_bounding_radius {
+            self.max_bounding_radius = bounding_radius;
+        }
+        true
+    }
+
+    /// Records the current positions of all agents
+    pub fn update_all(&mut self) {
+        for index in 0..self.entries.len() {
+            self.update(AgentId(index));
+        }
+    }
+
+    /// returns the agent with given id
+    pub fn get(&self, id: AgentId) -> Option<&Rc<RefCell<Steerable<T, V>>>> {
+        self.entry(id).map(|entry| &entry.agent)
+    }
+
+    /// returns the recorded position of the agent with given id
+    pub fn get_position(&self, id: AgentId) -> Option<V> {
+        self.entry(id).map(|entry| entry.position)
+    }
+
+    /// returns the recorded bounding radius of the agent with given id
+    pub fn get_bounding_radius(&self, id: AgentId) -> Option<T> {
+        self.entry(id).map(|entry| entry.bounding_radius)
+    }
+
+    /// returns the agents whose recorded position is within given radius of the
+    /// center, in no particular order
+    pub fn query_radius(&self, center: &V, radius: T) -> Vec<AgentId> {
+        let mut found = Vec::new();
+        self.for_each_within(center, radius, |id| found.push(id));
+        found
+    }
+
+    /// Calls given function for every agent whose recorded position is within given
+    /// radius of the center
+    pub fn for_each_within<F: FnMut(AgentId)>(&self, center: &V, radius: T, mut function: F) {
+        let square_radius = radius * radius;
+        let min = cell_of_offset(self.cell_size, center, -radius);
+        let max = cell_of_offset(self.cell_size, center, radius);
+        let mut visit = |ids: &Vec<AgentId>| for id in ids.iter() {
+            if let Some(entry) = self.entry(*id) {
+                if (entry.position - *center).norm_squared() <= square_radius {
+                    function(*id);
+                }
+            }
+        };
+        let cell_count = (max.0 - min.0 + 1) as f64 * (max.1 - min.1 + 1) as f64 *
+            (max.2 - min.2 + 1) as f64;
+        if cell_count > self.cells.len() as f64 {
+            for (cell, ids) in self.cells.iter() {
+                if contains(min, max, *cell) {
+                    visit(ids);
+                }
+            }
+        } else {
+            for x in min.0..max.0 + 1 {
+                for y in min.1..max.1 + 1 {
+                    for z in min.2..max.2 + 1 {
+                        if let Some(ids) = self.cells.get(&(x, y, z)) {
+                            visit(ids);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// returns at most `k` agents closest to the center ordered by distance.
+    /// Searches rings of cells around the center until no unvisited cell can hold a
+    /// closer agent.
+    pub fn k_nearest(&self, center: &V, k: usize) -> Vec<AgentId> {
+        let mut candidates: Vec<(T, AgentId)> = Vec::new();
+        if k == 0 || self.is_empty() {
+            return Vec::new();
+        }
+        let origin = self.cell_of(center);
+        let planar = V::dimension() < 3;
+        let mut visited = 0;
+        let mut ring = 0i64;
+        loop {
+            let side = (2 * ring + 1) as f64;
+            let ring_cells = if planar {
+                side * side
+            } else {
+                side * side * side
+            };
+            if ring_cells > self.cells.len() as f64 {
+                // the ring is larger than the occupied part of the grid, finish by
+                // scanning the occupied cells not visited yet
+                for (cell, ids) in self.cells.iter() {
+                    if chebyshev(origin, *cell) >= ring {
+                        self.collect(ids, center, &mut candidates);
+                    }
+                }
+                break;
+            }
+            let z_range = if planar { 0 } else { ring };
+            for x in -ring..ring + 1 {
+                for y in -ring..ring + 1 {
+                    for z in -z_range..z_range + 1 {
+                        if x.abs() != ring && y.abs() != ring && z.abs() != ring {
+                            continue;
+                        }
+                        let cell = (origin.0 + x, origin.1 + y, origin.2 + z);
+                        if let Some(ids) = self.cells.get(&cell) {
+                            visited += ids.len();
+                            self.collect(ids, center, &mut candidates);
+                        }
+                    }
+                }
+            }
+            if visited >= self.len {
+                break;
+            }
+            if visited >= k {
+                // unvisited agents are at least `ring` cells away from the center
+                let reach = self.cell_size * convert(ring);
+                candidates.sort_by(|a, b| compare(&a.0, &b.0));
+                if candidates[k - 1].0 <= reach * reach {
+                    break;
+                }
+            }
+            ring += 1;
+        }
+        candidates.sort_by(|a, b| compare(&a.0, &b.0));
+        candidates.truncate(k);
+        candidates.into_iter().map(|candidate| candidate.1).collect()
+    }
+
+    fn collect(&self, ids: &Vec<AgentId>, center: &V, candidates: &mut Vec<(T, AgentId)>) {
+        for id in ids.iter() {
+            if let Some(entry) = self.entry(*id) {
+                candidates.push(((entry.position - *center).norm_squared(), *id));
+            }
+        }
+    }
+
+    fn entry(&self, id: AgentId) -> Option<&Entry<T, V>> {
+        match self.entries.get(id.0) {
+            Some(&Some(ref entry)) => Some(entry),
+            _ => None,
+        }
+    }
+
+    fn cell_of(&self, position: &V) -> Cell {
+        cell_of(self.cell_size, position)
+    }
+
+    fn remove_from_cell(&mut self, cell: Cell, id: AgentId) {
+        let empty = match self.cells.get_mut(&cell) {
+            Some(ids) => {
+                if let Some(index) = ids.iter().position(|other| *other == id) {
+                    ids.swap_remove(index);
+                }
+                ids.is_empty()
+            }
+            None => false,
+        };
+        if empty {
+            self.cells.remove(&cell);
+        }
+    }
+}
+
+fn cell_of<T: Real, V: SteeringVector<T>>(cell_size: T, position: &V) -> Cell {
+    cell_of_offset(cell_size, position, T::zero())
+}
+
+/// returns the cell containing the position moved by given offset along every axis
+fn cell_of_offset<T: Real, V: SteeringVector<T>>(cell_size: T, position: &V, offset: T) -> Cell {
+    let coordinate = |axis: usize| if axis < V::dimension() {
+        let value: f64 = ((position.component(axis) + offset) / cell_size)
+            .floor()
+            .to_subset()
+            .unwrap_or(0.0);
+        value as i64
+    } else {
+        0
+    };
+    (coordinate(0), coordinate(1), coordinate(2))
+}
+
+fn contains(min: Cell, max: Cell, cell: Cell) -> bool {
+    cell.0 >= min.0 && cell.0 <= max.0 && cell.1 >= min.1 && cell.1 <= max.1 &&
+        cell.2 >= min.2 && cell.2 <= max.2
+}
+
+fn chebyshev(first: Cell, second: Cell) -> i64 {
+    let x = (first.0 - second.0).abs();
+    let y = (first.1 - second.1).abs();
+    let z = (first.2 - second.2).abs();
+    x.max(y).max(z)
+}
+
+fn convert<T: Real>(value: i64) -> T {
+    T::from_i64(value).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::SpatialHash;
+    use behavior::test_common::{TestSteerable, TestSteerable2d};
+    use {Steerable, SteeringVector};
+    use nalgebra::{Vector2, Vector3};
+    use quickcheck::quickcheck;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn agent_at(x: f32, y: f32, z: f32) -> Rc<RefCell<TestSteerable>> {
+        let mut agent = TestSteerable::new();
+        agent.set_position(Vector3::new(x, y, z));
+        Rc::new(RefCell::new(agent))
+    }
+
+    #[test]
+    fn rejects_invalid_cell_size() {
+        assert!(SpatialHash::<f32>::new(0.0).is_err());
+        assert!(SpatialHash::<f32>::new(-1.0).is_err());
+    }
+
+    #[test]
+    fn insert_update_remove() {
+        let mut hash = SpatialHash::new(1.0f32).unwrap();
+        let first = agent_at(0.5, 0.5, 0.5);
+        let first_id = hash.insert(first.clone());
+        let second_id = hash.insert(agent_at(5.0, 5.0, 5.0));
+        assert_eq!(2, hash.len());
+        assert_eq!(vec![first_id], hash.query_radius(&Vector3::new(0.0, 0.0, 0.0), 1.0));
+
+        first.borrow_mut().set_position(Vector3::new(4.5, 5.0, 5.0));
+        assert!(hash.update(first_id));
+        assert!(hash.query_radius(&Vector3::new(0.0, 0.0, 0.0), 1.0).is_empty());
+        assert_eq!(2, hash.query_radius(&Vector3::new(5.0, 5.0, 5.0), 1.0).len());
+
+        assert!(hash.remove(second_id).is_some());
+        assert!(hash.remove(second_id).is_none());
+        assert!(!hash.update(second_id));
+        assert_eq!(1, hash.len());
+        assert_eq!(vec![first_id], hash.query_radius(&Vector3::new(5.0, 5.0, 5.0), 1.0));
+
+        let third_id = hash.insert(agent_at(-3.0, 0.0, 0.0));
+        assert_eq!(second_id, third_id);
+        assert_eq!(Some(Vector3::new(-3.0, 0.0, 0.0)), hash.get_position(third_id));
+    }
+
+    #[test]
+    fn planar_agents() {
+        let mut hash = SpatialHash::new(2.0f32).unwrap();
+        for i in 0..10 {
+            let agent = TestSteerable2d::new(Vector2::new(i as f32, -(i as f32)));
+            hash.insert(Rc::new(RefCell::new(agent)) as Rc<RefCell<Steerable<f32, Vector2<f32>>>>);
+        }
+        let nearest = hash.k_nearest(&Vector2::new(3.2f32, -3.2), 3);
+        let positions: Vec<Vector2<f32>> = nearest
+            .iter()
+            .map(|id| hash.get_position(*id).unwrap())
+            .collect();
+        assert_eq!(
+            vec![
+                Vector2::new(3.0, -3.0),
+                Vector2::new(4.0, -4.0),
+                Vector2::new(2.0, -2.0),
+            ],
+            positions
+        );
+        assert_eq!(3, hash.query_radius(&Vector2::new(0.0, 0.0), 3.0).len());
+    }
+
+    #[test]
+    fn queries_match_brute_force() {
+        fn prop(points: Vec<(i8, i8, i8)>, center: (i8, i8, i8), radius: u8, k: u8) -> bool {
+            let scale = |value: i8| value as f32 * 0.37;
+            let position = |point: (i8, i8, i8)| {
+                Vector3::new(scale(point.0), scale(point.1), scale(point.2))
+            };
+            let mut hash = SpatialHash::new(3.0f32).unwrap();
+            for point in points.iter() {
+                let position = position(*point);
+                hash.insert(agent_at(position.x, position.y, position.z));
+            }
+            let center = position(center);
+            let radius = radius as f32 * 0.1;
+            let k = k as usize % 8;
+
+            let mut distances: Vec<f32> = points
+                .iter()
+                .map(|point| position(*point).distance(&center))
+                .collect();
+            let expected = distances.iter().filter(|distance| **distance <= radius).count();
+            if hash.query_radius(&center, radius).len() != expected {
+                return false;
+            }
+
+            distances.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            distances.truncate(k);
+            let found: Vec<f32> = hash.k_nearest(&center, k)
+                .iter()
+                .map(|id| hash.get_position(*id).unwrap().distance(&center))
+                .collect();
+            found == distances
+        }
+        quickcheck(prop as fn(Vec<(i8, i8, i8)>, (i8, i8, i8), u8, u8) -> bool);
+    }
+}
@@ -0,0 +1,102 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {Proximity, Steerable, SteeringVector};
+use proximity::is_owner;
+use super::SpatialHash;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Proximity reporting agents of a spatial hash whose bounding circle overlaps a
+/// circle of given radius around the owner. Same neighbors as `RadiusProximity`
+/// reports, measured at the positions recorded by the hash.
+pub struct SpatialHashProximity<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// spatial hash holding candidate neighbors, usually shared by a whole group
+    pub hash: Rc<RefCell<SpatialHash<T, V>>>,
+    /// radius of the neighborhood
+    pub radius: T,
+}
+
+impl<T: Real, V: SteeringVector<T>> SpatialHashProximity<T, V> {
+    /// Creates a proximity of agents in given hash
+    pub fn new(hash: Rc<RefCell<SpatialHash<T, V>>>, radius: T) -> SpatialHashProximity<T, V> {
+        SpatialHashProximity {
+            hash: hash,
+            radius: radius,
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Proximity<T, V> for SpatialHashProximity<T, V> {
+    fn find_neighbors(
+        &self,
+        owner: &Steerable<T, V>,
+        callback: &mut FnMut(&Steerable<T, V>) -> bool,
+    ) -> usize {
+        let hash = self.hash.borrow();
+        let center = *owner.get_position();
+        let mut count = 0;
+        let reach = self.radius + hash.get_max_bounding_radius();
+        hash.for_each_within(&center, reach, |id| {
+            let agent = match hash.get(id) {
+                Some(agent) => agent,
+                None => return,
+            };
+            if is_owner(agent, owner) {
+                return;
+            }
+            let range = self.radius + hash.get_bounding_radius(id).unwrap_or(T::zero());
+            let position = hash.get_position(id).unwrap_or(center);
+            if (position - center).norm_squared() < range * range && callback(&*agent.borrow()) {
+                count += 1;
+            }
+        });
+        count
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SpatialHashProximity;
+    use super::super::SpatialHash;
+    use super::super::super::{Proximity, RadiusProximity, Steerable};
+    use behavior::test_common::TestSteerable2d;
+    use nalgebra::Vector2;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn same_neighbors_as_radius_proximity() {
+        let agents: Vec<Rc<RefCell<Steerable<f32, Vector2<f32>>>>> = (0..50)
+            .map(|i| {
+                let position = Vector2::new((i % 7) as f32 * 1.3, (i / 7) as f32 * 0.9);
+                Rc::new(RefCell::new(TestSteerable2d::new(position))) as
+                    Rc<RefCell<Steerable<f32, Vector2<f32>>>>
+            })
+            .collect();
+        let mut hash = SpatialHash::new(2.0f32).unwrap();
+        for agent in agents.iter() {
+            hash.insert(agent.clone());
+        }
+        let hashed = SpatialHashProximity::new(Rc::new(RefCell::new(hash)), 2.5);
+        let naive = RadiusProximity::new(agents.clone(), 2.5);
+        for agent in agents.iter() {
+            let owner = agent.borrow();
+            let mut hashed_positions = Vec::new();
+            let hashed_count = hashed.find_neighbors(&*owner, &mut |neighbor| {
+                hashed_positions.push(*neighbor.get_position());
+                true
+            });
+            let mut naive_positions = Vec::new();
+            let naive_count = naive.find_neighbors(&*owner, &mut |neighbor| {
+                naive_positions.push(*neighbor.get_position());
+                true
+            });
+            assert_eq!(naive_count, hashed_count);
+            assert_eq!(naive_positions.len(), hashed_positions.len());
+            for position in naive_positions.iter() {
+                assert!(hashed_positions.contains(position));
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {Location, SteeringVector};
+
+/// A fixed point in space that can be used as a behavior target
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StaticLocation<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// position of the location
+    pub position: V,
+    /// orientation of the location in radians
+    pub orientation: T,
+}
+
+impl<T: Real, V: SteeringVector<T>> StaticLocation<T, V> {
+    /// Creates a location at given position with given orientation
+    pub fn new(position: V, orientation: T) -> StaticLocation<T, V> {
+        StaticLocation {
+            position: position,
+            orientation: orientation,
+        }
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Location<T, V> for StaticLocation<T, V> {
+    fn get_position(&self) -> &V {
+        &self.position
+    }
+
+    fn get_orientation(&self) -> T {
+        self.orientation
+    }
+}
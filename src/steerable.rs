@@ -1,10 +1,11 @@
 use nalgebra::Vector3;
 use alga::general::Real;
+use {Location, SteeringVector};
 
 /// Steerable agent interface
-pub trait Steerable<T: Real> {
+pub trait Steerable<T: Real, V: SteeringVector<T> = Vector3<T>>: Location<T, V> {
     /// returns the linear velocity vector of the agent
-    fn get_linear_velocity(self: &Self) -> &Vector3<T>;
+    fn get_linear_velocity(self: &Self) -> &V;
 
     /// returns angular velocity of the agent
     fn get_angular_velocity(self: &Self) -> T;
@@ -12,7 +13,9 @@ pub trait Steerable<T: Real> {
     /// returns bounding circle radius of the agent
     fn get_bounding_radius(self: &Self) -> T;
 
-    fn get_position(self: &Self) -> &Vector3<T>;
-
-    fn get_orientation(&self) -> T;
+    /// returns the linear acceleration of the agent, pursuers intercepting the
+    /// agent take it into account. Agents which do not track it report none.
+    fn get_linear_acceleration(self: &Self) -> V {
+        V::zero()
+    }
 }
@@ -1,8 +1,7 @@
-use nalgebra::{distance_squared, Point3, Vector3};
+use nalgebra::Vector3;
 use alga::general::Real;
-use alga::general::AbstractModule;
-use num_traits::identities::Zero;
-use IsEnabled;
+use {IsEnabled, SteeringError, SteeringStatus, SteeringVector};
+use steering_vector::is_finite;
 
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -10,26 +9,24 @@ use std::rc::Rc;
 /// Represents result of a steering behaviour computation. User can aggregate
 /// more than one behaviour result into single acceleration struct.
 #[derive(Debug, PartialEq)]
-pub struct SteeringAcceleration<T: Real> {
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SteeringAcceleration<T: Real, V: SteeringVector<T> = Vector3<T>> {
     /// linear acceleration component
-    pub linear: Vector3<T>,
+    pub linear: V,
     /// angular acceleration component
     pub angular: T,
 }
 
 
-impl<T: Real> SteeringAcceleration<T> {
-    pub fn default() -> SteeringAcceleration<T> {
+impl<T: Real, V: SteeringVector<T>> SteeringAcceleration<T, V> {
+    pub fn default() -> SteeringAcceleration<T, V> {
         SteeringAcceleration {
-            linear: Vector3::zero(),
+            linear: V::zero(),
             angular: T::zero(),
         }
     }
     /// Creates a steering acceleration struct using given linear and angular components
-    pub fn new(
-        linear_acceleration: Vector3<T>,
-        angular_acceleration: T,
-    ) -> SteeringAcceleration<T> {
+    pub fn new(linear_acceleration: V, angular_acceleration: T) -> SteeringAcceleration<T, V> {
         SteeringAcceleration {
             linear: linear_acceleration,
             angular: angular_acceleration,
@@ -41,15 +38,20 @@ impl<T: Real> SteeringAcceleration<T> {
         self.angular.is_zero() && self.linear.is_zero()
     }
 
+    /// Tests whether both linear and angular acceleration components are finite numbers
+    pub fn is_finite(&self) -> bool {
+        is_finite(self.angular) && self.linear.is_finite()
+    }
+
     /// Sets both compononents to zero
     pub fn set_zero(&mut self) -> &mut Self {
         self.angular = T::zero();
-        self.linear = Vector3::zero();
+        self.linear = V::zero();
         self
     }
 
     ///
-    pub fn add(&mut self, other: SteeringAcceleration<T>) -> &mut Self {
+    pub fn add(&mut self, other: SteeringAcceleration<T, V>) -> &mut Self {
         self.angular += other.angular;
         self.linear += other.linear;
         self
@@ -58,21 +60,20 @@ impl<T: Real> SteeringAcceleration<T> {
     ///
     pub fn scl(&mut self, scale: T) -> &mut Self {
         self.angular *= scale;
-        self.linear = self.linear.multiply_by(scale);
+        self.linear = self.linear.scale(scale);
         self
     }
 
     ///
-    pub fn mul_add(&mut self, other: SteeringAcceleration<T>, scale: T) -> &mut Self {
+    pub fn mul_add(&mut self, other: SteeringAcceleration<T, V>, scale: T) -> &mut Self {
         self.angular += other.angular * scale;
-        self.linear += other.linear.multiply_by(scale);
+        self.linear += other.linear.scale(scale);
         self
     }
 
     ///
     pub fn calculate_square_magnitude(&self) -> T {
-        distance_squared(&Point3::from_coordinates(self.linear), &Point3::origin()) +
-            self.angular * self.angular
+        self.linear.norm_squared() + self.angular * self.angular
     }
 
     ///
@@ -81,11 +82,12 @@ impl<T: Real> SteeringAcceleration<T> {
     }
 }
 
-pub trait SteeringAccelerationCalculator<T: Real>: IsEnabled<T> {
+pub trait SteeringAccelerationCalculator<T: Real, V: SteeringVector<T> = Vector3<T>>
+    : IsEnabled<T, V> {
     fn calculate_steering(
         &mut self,
-        steering_acceleration: Rc<RefCell<SteeringAcceleration<T>>>,
-    ) -> Rc<RefCell<SteeringAcceleration<T>>> {
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>> {
         if self.is_enabled() {
             self.calculate_real_steering(steering_acceleration.clone());
             steering_acceleration
@@ -95,10 +97,47 @@ pub trait SteeringAccelerationCalculator<T: Real>: IsEnabled<T> {
         }
     }
 
+    /// Checks that parameters of the behavior make sense
+    fn validate(&self) -> Result<(), SteeringError> {
+        Ok(())
+    }
+
+    /// returns how far the behavior got towards its goal, behaviors without a goal
+    /// are always in progress
+    fn get_status(&self) -> SteeringStatus {
+        SteeringStatus::InProgress
+    }
+
+    /// Calculates steering like `calculate_steering` does and returns the status
+    /// of the behavior alongside the acceleration
+    fn calculate_steering_with_status(
+        &mut self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> (Rc<RefCell<SteeringAcceleration<T, V>>>, SteeringStatus) {
+        let steering_acceleration = self.calculate_steering(steering_acceleration);
+        (steering_acceleration, self.get_status())
+    }
+
+    /// Calculates steering like `calculate_steering` does but fails instead of
+    /// returning an acceleration which is not a finite number or calculating with
+    /// invalid parameters
+    fn try_calculate_steering(
+        &mut self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Result<Rc<RefCell<SteeringAcceleration<T, V>>>, SteeringError> {
+        self.validate()?;
+        let steering_acceleration = self.calculate_steering(steering_acceleration);
+        if steering_acceleration.borrow().is_finite() {
+            Ok(steering_acceleration)
+        } else {
+            Err(SteeringError::NonFiniteAcceleration)
+        }
+    }
+
     fn calculate_real_steering(
         &self,
-        steering_acceleration: Rc<RefCell<SteeringAcceleration<T>>>,
-    ) -> Rc<RefCell<SteeringAcceleration<T>>>;
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T, V>>>,
+    ) -> Rc<RefCell<SteeringAcceleration<T, V>>>;
     // fn set_enabled(&mut self, is_enabled : bool);
 }
 
@@ -1,41 +1,42 @@
 use limiter::Limiter;
+use nalgebra::Vector3;
 use alga::general::Real;
 
 use std::cell::RefCell;
 use std::cell::RefMut;
 use std::rc::Rc;
 
-use Steerable;
+use {Steerable, SteeringVector};
 /// Common properties of steering behaviors
 #[builder(pattern = "immutable")]
 #[derive(Builder, Clone)]
-pub struct SteeringBehavior<T>
+pub struct SteeringBehavior<T, V = Vector3<T>>
 where
     T: Real,
+    V: SteeringVector<T>,
 {
     /// is this behavior enabled
     pub enabled: bool,
     /// limitations on speed and velocity calculations
     pub limiter: Option<Rc<RefCell<Limiter<T>>>>,
 
-    pub owner: Rc<RefCell<Steerable<T>>>,
-
-    pub target: Rc<RefCell<Steerable<T>>>,
+    /// agent whose steering is calculated
+    pub owner: Rc<RefCell<Steerable<T, V>>>,
 }
 
 
-pub trait HasSteeringBehavior<T: Real> {
-    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T>>;
+pub trait HasSteeringBehavior<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior<T, V>>;
 }
 
-pub trait IsEnabled<T> {
+pub trait IsEnabled<T, V = Vector3<T>> {
     fn is_enabled(&mut self) -> bool;
     fn set_enabled(&mut self, value: bool);
 }
 
-impl<T: Real, U> IsEnabled<T> for U
+impl<T: Real, V: SteeringVector<T>, U> IsEnabled<T, V> for U
 where
-    U: HasSteeringBehavior<T>,
+    U: HasSteeringBehavior<T, V>,
 {
     fn is_enabled(&mut self) -> bool {
         self.get_steering_behavior().enabled
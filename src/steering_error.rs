@@ -0,0 +1,35 @@
+use std::error::Error;
+use std::fmt;
+
+/// Reasons a steering calculation can fail
+#[derive(Debug, Clone, PartialEq)]
+pub enum SteeringError {
+    /// Calculation produced an acceleration which is not a finite number. It is caused
+    /// by a degenerate behavior parameter such as zero time to target or by a non finite
+    /// position or velocity of owner or target.
+    NonFiniteAcceleration,
+    /// A behavior parameter has a value which makes no sense, such as a negative radius
+    InvalidParameter(String),
+}
+
+impl fmt::Display for SteeringError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SteeringError::NonFiniteAcceleration => {
+                write!(f, "calculated steering acceleration is not a finite number")
+            }
+            SteeringError::InvalidParameter(ref reason) => {
+                write!(f, "invalid behavior parameter: {}", reason)
+            }
+        }
+    }
+}
+
+impl Error for SteeringError {
+    fn description(&self) -> &str {
+        match *self {
+            SteeringError::NonFiniteAcceleration => "non finite steering acceleration",
+            SteeringError::InvalidParameter(_) => "invalid behavior parameter",
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use alga::general::Real;
+use SteeringVector;
+
+/// Progress of a behavior towards its goal, reported alongside the acceleration so
+/// game logic knows when to trigger the next action
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum SteeringStatus {
+    /// behavior is still steering towards its goal or has no goal at all
+    InProgress,
+    /// owner is within tolerance of the target position
+    Arrived,
+    /// owner looks at the target within tolerance
+    Aligned,
+    /// target position is not a finite number, e.g. the target was removed
+    TargetLost,
+    /// owner reached the end of the path it follows
+    PathFinished,
+}
+
+/// returns `TargetLost` if given target position is not a finite number, otherwise
+/// the status returned by given function. Works for `Vector3` targets of 3D
+/// behaviors too.
+pub fn target_status<T, V, F>(target_position: &V, status: F) -> SteeringStatus
+where
+    T: Real,
+    V: SteeringVector<T>,
+    F: FnOnce() -> SteeringStatus,
+{
+    if target_position.is_finite() {
+        status()
+    } else {
+        SteeringStatus::TargetLost
+    }
+}
+
+/// Combines statuses of blended behaviors: a lost target is reported first,
+/// otherwise a goal is only reached when every behavior reached the same one
+pub fn combined_status<I: Iterator<Item = SteeringStatus>>(statuses: I) -> SteeringStatus {
+    let mut combined = None;
+    let mut all_same = true;
+    for status in statuses {
+        if status == SteeringStatus::TargetLost {
+            return status;
+        }
+        match combined {
+            None => combined = Some(status),
+            Some(first) if first != status => all_same = false,
+            _ => {}
+        }
+    }
+    match combined {
+        Some(status) if all_same => status,
+        _ => SteeringStatus::InProgress,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::combined_status;
+
+    #[test]
+    fn combined_status_needs_every_behavior() {
+        use super::SteeringStatus::*;
+        assert_eq!(Arrived, combined_status(vec![Arrived, Arrived].into_iter()));
+        assert_eq!(InProgress, combined_status(vec![Arrived, Aligned].into_iter()));
+        assert_eq!(TargetLost, combined_status(vec![Arrived, TargetLost].into_iter()));
+        assert_eq!(InProgress, combined_status(Vec::new().into_iter()));
+    }
+}
@@ -0,0 +1,225 @@
+use nalgebra::{Vector2, Vector3};
+use alga::general::Real;
+use num_traits::identities::Zero;
+
+use std::fmt::Debug;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+/// Vector operations needed by steering calculations. Implemented for `Vector2`
+/// to steer agents on a plane and for `Vector3` to steer agents in space. In both
+/// cases scalar orientations are measured around the z axis, an orientation of zero
+/// looks along the positive y axis.
+pub trait SteeringVector<T: Real>
+    : Copy
+    + Debug
+    + PartialEq
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+    + SubAssign
+    + 'static {
+    /// returns the zero vector
+    fn zero() -> Self;
+
+    /// tests whether all components are zero
+    fn is_zero(&self) -> bool;
+
+    /// returns this vector multiplied by given scalar
+    fn scale(&self, scale: T) -> Self;
+
+    /// returns the dot product of two vectors
+    fn dot(&self, other: &Self) -> T;
+
+    /// returns the squared length of the vector
+    fn norm_squared(&self) -> T {
+        self.dot(self)
+    }
+
+    /// returns the length of the vector
+    fn norm(&self) -> T {
+        self.norm_squared().sqrt()
+    }
+
+    /// returns unit vector with the same direction
+    fn normalize(&self) -> Self {
+        self.scale(T::one() / self.norm())
+    }
+
+    /// returns unit vector with the same direction or zero vector if this vector is
+    /// too short to have a direction
+    fn normalize_or_zero(&self) -> Self {
+        let norm = self.norm();
+        if norm > T::default_epsilon() {
+            self.scale(T::one() / norm)
+        } else {
+            Self::zero()
+        }
+    }
+
+    /// tests whether all components are finite numbers
+    fn is_finite(&self) -> bool;
+
+    /// returns the distance between two points
+    fn distance(&self, other: &Self) -> T {
+        (*other - *self).norm()
+    }
+
+    /// returns the orientation in radians that this vector is pointing to
+    fn vector_to_angle(&self) -> T;
+
+    /// returns the unit vector pointing to given orientation in radians
+    fn angle_to_vector(angle: T) -> Self;
+
+    /// returns the number of components of the vector
+    fn dimension() -> usize;
+
+    /// returns the component along given axis, zero for axes beyond the dimension
+    fn component(&self, axis: usize) -> T;
+
+    /// returns the vector whose components are returned by given function for each
+    /// axis
+    fn from_components<F: FnMut(usize) -> T>(component: F) -> Self;
+}
+
+/// Tests whether given scalar is neither infinite nor NaN
+pub fn is_finite<T: Real>(value: T) -> bool {
+    // comparisons with NaN are false, so this rejects NaN as well as infinities
+    value.abs() <= T::max_value()
+}
+
+impl<T: Real> SteeringVector<T> for Vector2<T> {
+    fn zero() -> Self {
+        Vector2::zeros()
+    }
+
+    fn is_zero(&self) -> bool {
+        Zero::is_zero(self)
+    }
+
+    fn is_finite(&self) -> bool {
+        is_finite(self.x) && is_finite(self.y)
+    }
+
+    fn scale(&self, scale: T) -> Self {
+        *self * scale
+    }
+
+    fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    fn vector_to_angle(&self) -> T {
+        (-self.x).atan2(self.y)
+    }
+
+    fn angle_to_vector(angle: T) -> Self {
+        Vector2::new(-angle.sin(), angle.cos())
+    }
+
+    fn dimension() -> usize {
+        2
+    }
+
+    fn component(&self, axis: usize) -> T {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            _ => T::zero(),
+        }
+    }
+
+    fn from_components<F: FnMut(usize) -> T>(mut component: F) -> Self {
+        let x = component(0);
+        Vector2::new(x, component(1))
+    }
+}
+
+impl<T: Real> SteeringVector<T> for Vector3<T> {
+    fn zero() -> Self {
+        Vector3::zeros()
+    }
+
+    fn is_zero(&self) -> bool {
+        Zero::is_zero(self)
+    }
+
+    fn is_finite(&self) -> bool {
+        is_finite(self.x) && is_finite(self.y) && is_finite(self.z)
+    }
+
+    fn scale(&self, scale: T) -> Self {
+        *self * scale
+    }
+
+    fn dot(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    fn vector_to_angle(&self) -> T {
+        (-self.x).atan2(self.y)
+    }
+
+    fn angle_to_vector(angle: T) -> Self {
+        Vector3::new(-angle.sin(), angle.cos(), T::zero())
+    }
+
+    fn dimension() -> usize {
+        3
+    }
+
+    fn component(&self, axis: usize) -> T {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            2 => self.z,
+            _ => T::zero(),
+        }
+    }
+
+    fn from_components<F: FnMut(usize) -> T>(mut component: F) -> Self {
+        let x = component(0);
+        let y = component(1);
+        Vector3::new(x, y, component(2))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SteeringVector;
+    use nalgebra::{Vector2, Vector3};
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn vector_to_angle() {
+        assert_eq!(0.0f32, Vector2::new(0.0f32, 1.0).vector_to_angle());
+        assert_eq!(FRAC_PI_2, Vector2::new(-1.0f32, 0.0).vector_to_angle());
+        assert_eq!(-FRAC_PI_2, Vector3::new(1.0f32, 0.0, 0.0).vector_to_angle());
+    }
+
+    #[test]
+    fn angle_to_vector() {
+        let vector: Vector2<f32> = SteeringVector::angle_to_vector(FRAC_PI_2);
+        assert!((vector - Vector2::new(-1.0f32, 0.0)).norm() < 1e-6);
+        let vector: Vector3<f32> = SteeringVector::angle_to_vector(0.0f32);
+        assert_eq!(Vector3::new(0.0f32, 1.0, 0.0), vector);
+    }
+
+    #[test]
+    fn angle_round_trip() {
+        let angle = 0.75f32;
+        let vector: Vector2<f32> = SteeringVector::angle_to_vector(angle);
+        assert!((vector.vector_to_angle() - angle).abs() < 1e-6);
+    }
+
+    #[test]
+    fn components() {
+        let vector = Vector2::new(1.0f32, 2.0);
+        assert_eq!(2, <Vector2<f32> as SteeringVector<f32>>::dimension());
+        assert_eq!(2.0, vector.component(1));
+        assert_eq!(0.0, vector.component(2));
+        assert_eq!(3.0, Vector3::new(1.0f32, 2.0, 3.0).component(2));
+        let scaled: Vector3<f32> = SteeringVector::from_components(|axis| axis as f32 * 2.0);
+        assert_eq!(Vector3::new(0.0, 2.0, 4.0), scaled);
+    }
+}
@@ -0,0 +1,266 @@
+use nalgebra::{Unit, UnitQuaternion, Vector3};
+use alga::general::Real;
+use super::shortest_arc;
+use {Steerable, SteeringAcceleration, SteeringAccelerationCalculator};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Desired attitude of a flying agent
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlightAttitude<T: Real> {
+    /// roll angle in radians, positive values roll towards right
+    pub bank: T,
+    /// pitch angle in radians, positive values raise the nose
+    pub pitch: T,
+    /// full orientation combining heading, pitch and bank
+    pub orientation: UnitQuaternion<T>,
+}
+
+/// Derives the attitude of a flying agent from its motion. Heading follows the
+/// horizontal velocity like LookWhereYouAreGoing does, pitch follows the vertical
+/// velocity and bank follows the lateral acceleration as in a coordinated turn.
+#[derive(Debug, Clone)]
+pub struct Banking<T: Real> {
+    /// Largest roll angle in radians
+    pub max_bank_angle: T,
+    /// Largest roll change in radians per second
+    pub roll_rate: T,
+    /// Largest pitch angle in radians
+    pub max_pitch_angle: T,
+    /// Gravitational acceleration used to derive the bank of a coordinated turn
+    pub gravity: T,
+    /// Up axis of the world
+    pub up: Vector3<T>,
+    /// Forward axis of the agent in its local frame
+    pub forward: Vector3<T>,
+    bank: T,
+    heading: Vector3<T>,
+}
+
+impl<T: Real> Banking<T> {
+    /// Creates a banking calculator with given bank limits. World up axis is positive z,
+    /// forward axis of the agent is positive y.
+    pub fn new(max_bank_angle: T, roll_rate: T) -> Banking<T> {
+        Banking {
+            max_bank_angle: max_bank_angle,
+            roll_rate: roll_rate,
+            max_pitch_angle: T::frac_pi_2(),
+            gravity: T::from_f32(9.81).unwrap(),
+            up: Vector3::z(),
+            forward: Vector3::y(),
+            bank: T::zero(),
+            heading: Vector3::y(),
+        }
+    }
+
+    /// returns current bank angle in radians
+    pub fn get_bank(&self) -> T {
+        self.bank
+    }
+
+    /// Calculates the attitude for given velocity and linear acceleration. Bank angle
+    /// changes at most `roll_rate * delta_time` radians between consecutive calls.
+    pub fn update(
+        &mut self,
+        velocity: &Vector3<T>,
+        acceleration: &Vector3<T>,
+        delta_time: T,
+    ) -> FlightAttitude<T> {
+        let up = self.up.normalize();
+        let vertical_speed = velocity.dot(&up);
+        let horizontal_velocity = velocity - up * vertical_speed;
+        let horizontal_speed = horizontal_velocity.norm();
+        if horizontal_speed > T::default_epsilon() {
+            self.heading = horizontal_velocity / horizontal_speed;
+        } else {
+            // keep the previous heading, levelled in case it ran along the up axis
+            self.heading = level(&self.heading, &up)
+                .or_else(|| level(&self.forward, &up))
+                .unwrap_or_else(|| shortest_arc(&Vector3::z(), &up) * Vector3::y());
+        }
+
+        let pitch = clamp(
+            vertical_speed.atan2(horizontal_speed),
+            self.max_pitch_angle,
+        );
+
+        let right = self.heading.cross(&up);
+        let lateral_acceleration = acceleration.dot(&right);
+        let desired_bank = clamp(
+            (lateral_acceleration / self.gravity).atan(),
+            self.max_bank_angle,
+        );
+        self.bank += clamp(desired_bank - self.bank, self.roll_rate * delta_time);
+
+        let yaw = shortest_arc(&self.forward, &self.heading);
+        let pitch_rotation = UnitQuaternion::from_axis_angle(&Unit::new_normalize(right), pitch);
+        let roll = UnitQuaternion::from_axis_angle(
+            &Unit::new_normalize(pitch_rotation * self.heading),
+            self.bank,
+        );
+
+        FlightAttitude {
+            bank: self.bank,
+            pitch: pitch,
+            orientation: roll * pitch_rotation * yaw,
+        }
+    }
+}
+
+/// returns the unit direction of the part of given vector perpendicular to the up
+/// axis, `None` if the vector runs along the axis
+fn level<T: Real>(direction: &Vector3<T>, up: &Vector3<T>) -> Option<Vector3<T>> {
+    let level = direction - up * direction.dot(up);
+    let norm = level.norm();
+    if norm > T::default_epsilon() {
+        Some(level / norm)
+    } else {
+        None
+    }
+}
+
+fn clamp<T: Real>(value: T, limit: T) -> T {
+    if value > limit {
+        limit
+    } else if value < -limit {
+        -limit
+    } else {
+        value
+    }
+}
+
+/// Runs a steering behavior for a flying agent and derives the attitude the agent
+/// should have while following the calculated acceleration
+pub struct FlyingSteering<T: Real> {
+    /// flying agent
+    pub owner: Rc<RefCell<Steerable<T>>>,
+    /// behavior producing the acceleration of the agent
+    pub steering: Rc<RefCell<SteeringAccelerationCalculator<T>>>,
+    /// attitude calculator
+    pub banking: Banking<T>,
+}
+
+impl<T: Real> FlyingSteering<T> {
+    /// Calculates the steering acceleration of the wrapped behavior together with
+    /// the desired attitude of the owner
+    pub fn calculate_steering(
+        &mut self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration<T>>>,
+        delta_time: T,
+    ) -> (Rc<RefCell<SteeringAcceleration<T>>>, FlightAttitude<T>) {
+        let steering_acceleration = self.steering
+            .borrow_mut()
+            .calculate_steering(steering_acceleration);
+        let attitude = self.banking.update(
+            self.owner.borrow().get_linear_velocity(),
+            &steering_acceleration.borrow().linear,
+            delta_time,
+        );
+        (steering_acceleration, attitude)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Banking, FlyingSteering};
+    use behavior::Seek;
+    use behavior::test_common::TestSteerable;
+    use {SteeringAcceleration, SteeringBehavior, StaticLocation};
+    use nalgebra::Vector3;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_level_flight() {
+        let mut banking = Banking::new(0.5f32, 1.0);
+        let attitude = banking.update(
+            &Vector3::new(0.0, 10.0, 0.0),
+            &Vector3::new(0.0, 0.0, 0.0),
+            0.1,
+        );
+        assert_eq!(0.0, attitude.bank);
+        assert_eq!(0.0, attitude.pitch);
+        assert!((attitude.orientation * Vector3::y() - Vector3::y()).norm() < 1e-6);
+    }
+
+    #[test]
+    fn test_bank_into_turn() {
+        let mut banking = Banking::new(0.5f32, 1.0);
+        let right_turn = Vector3::new(100.0, 0.0, 0.0);
+        let attitude = banking.update(&Vector3::new(0.0, 10.0, 0.0), &right_turn, 0.1);
+        assert!((attitude.bank - 0.1).abs() < 1e-6);
+        for _ in 0..10 {
+            banking.update(&Vector3::new(0.0, 10.0, 0.0), &right_turn, 0.1);
+        }
+        assert_eq!(0.5, banking.get_bank());
+        let attitude = banking.update(&Vector3::new(0.0, 10.0, 0.0), &right_turn, 0.1);
+        assert!((attitude.orientation * Vector3::z()).x > 0.0);
+    }
+
+    #[test]
+    fn test_pitch_from_climb() {
+        let mut banking = Banking::new(0.5f32, 1.0);
+        let attitude = banking.update(
+            &Vector3::new(0.0, 10.0, 10.0),
+            &Vector3::new(0.0, 0.0, 0.0),
+            0.1,
+        );
+        assert!((attitude.pitch - ::std::f32::consts::FRAC_PI_4).abs() < 1e-6);
+        let nose = attitude.orientation * Vector3::y();
+        assert!((nose - Vector3::new(0.0, 1.0, 1.0).normalize()).norm() < 1e-6);
+    }
+
+    #[test]
+    fn test_up_along_forward() {
+        // the initial heading runs along the up axis, there is no level direction to keep
+        let mut banking = Banking::new(0.5f32, 1.0);
+        banking.up = Vector3::y();
+        let attitude = banking.update(
+            &Vector3::new(0.0, 0.0, 0.0),
+            &Vector3::new(1.0, 0.0, 0.0),
+            0.1,
+        );
+        assert!(attitude.orientation.coords.iter().all(|c| c.is_finite()));
+        assert_eq!(0.0, attitude.pitch);
+        let nose = attitude.orientation * Vector3::y();
+        assert!(nose.y.abs() < 1e-6);
+
+        // climbing straight up points the nose up
+        let attitude = banking.update(
+            &Vector3::new(0.0, 10.0, 0.0),
+            &Vector3::new(0.0, 0.0, 0.0),
+            0.1,
+        );
+        assert!(attitude.orientation.coords.iter().all(|c| c.is_finite()));
+        let nose = attitude.orientation * Vector3::y();
+        assert!((nose - Vector3::y()).norm() < 1e-6);
+    }
+
+    #[test]
+    fn test_flying_steering() {
+        // flying along x with the target to its right
+        let owner = Rc::new(RefCell::new(TestSteerable::new()));
+        let target = StaticLocation::new(Vector3::new(-50.0, 40.0, 0.0), 0.0);
+        let seek = Seek {
+            behavior: RefCell::new(SteeringBehavior {
+                enabled: true,
+                limiter: None,
+                owner: owner.clone(),
+            }),
+            target: Rc::new(RefCell::new(target)),
+        };
+        let mut flying = FlyingSteering {
+            owner: owner,
+            steering: Rc::new(RefCell::new(seek)),
+            banking: Banking::new(0.5f32, 1.0),
+        };
+        let sa = Rc::new(RefCell::new(SteeringAcceleration::default()));
+        let (sa, attitude) = flying.calculate_steering(sa, 0.1);
+        assert_eq!(Vector3::new(0.0, -1.0, 0.0), sa.borrow().linear);
+        assert!((attitude.bank - 0.1).abs() < 1e-6);
+        assert_eq!(0.1, flying.banking.get_bank());
+        let nose = attitude.orientation * Vector3::y();
+        assert!(nose.x > 0.99);
+    }
+}
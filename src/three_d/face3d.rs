@@ -0,0 +1,283 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use validation;
+use {SteeringError, SteeringStatus};
+use steering_status::target_status;
+use num_traits::identities::Zero;
+use super::{HasSteeringBehavior3d, Location3d, SteeringAcceleration3d,
+            SteeringAcceleration3dCalculator, SteeringBehavior3d};
+use super::reach_orientation3d::{reach_orientation, shortest_arc};
+
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+///Face behavior rotates owner so that its forward axis points towards the target.
+///Rotation follows the shortest arc, so the owner turns left or right, up or down
+///whichever is closer. It only produces an angular acceleration component.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct Face3d<T>
+where
+    T: Real,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior3d<T>>,
+    /// Location to look at
+    pub target: Rc<RefCell<Location3d<T>>>,
+    /// Forward axis of the owner in its local frame, positive y axis by default
+    #[builder(default = "Vector3::y()")]
+    pub forward: Vector3<T>,
+    /// Stop rotating if remaining angle is smaller than this
+    pub allign_tolerance: T,
+    /// Reduce the angular speed if remaining angle is smaller than this
+    pub deceleration_radius: T,
+    /// How quick should we face the target
+    pub time_to_target: T,
+}
+
+/// Tunable parameters of `Face3d` which can be loaded from data files. Owner and
+/// target are not part of them, they are supplied when the behavior is created.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Face3dParameters<T: Real> {
+    /// Forward axis of the owner in its local frame
+    pub forward: Vector3<T>,
+    /// Stop rotating if remaining angle is smaller than this
+    pub allign_tolerance: T,
+    /// Reduce the angular speed if remaining angle is smaller than this
+    pub deceleration_radius: T,
+    /// How quick should we reach the goal orientation
+    pub time_to_target: T,
+}
+
+impl<T: Real> Face3dBuilder<T> {
+    fn validate(&self) -> Result<(), String> {
+        validation::direction("forward", self.forward)?;
+        validation::arrival(
+            "allign_tolerance",
+            self.allign_tolerance,
+            self.deceleration_radius,
+            self.time_to_target,
+        )
+    }
+}
+
+impl<T: Real> Face3d<T> {
+    /// Creates the behavior using parameters loaded from a data file
+    pub fn with_parameters(
+        behavior: SteeringBehavior3d<T>,
+        target: Rc<RefCell<Location3d<T>>>,
+        parameters: &Face3dParameters<T>,
+    ) -> Face3d<T> {
+        Face3d {
+            behavior: RefCell::new(behavior),
+            target: target,
+            forward: parameters.forward,
+            allign_tolerance: parameters.allign_tolerance,
+            deceleration_radius: parameters.deceleration_radius,
+            time_to_target: parameters.time_to_target,
+        }
+    }
+
+    /// returns the tunable parameters of the behavior
+    pub fn parameters(&self) -> Face3dParameters<T> {
+        Face3dParameters {
+            forward: self.forward,
+            allign_tolerance: self.allign_tolerance,
+            deceleration_radius: self.deceleration_radius,
+            time_to_target: self.time_to_target,
+        }
+    }
+
+    /// Replaces the tunable parameters of the behavior
+    pub fn set_parameters(&mut self, parameters: &Face3dParameters<T>) {
+        self.forward = parameters.forward;
+        self.allign_tolerance = parameters.allign_tolerance;
+        self.deceleration_radius = parameters.deceleration_radius;
+        self.time_to_target = parameters.time_to_target;
+    }
+}
+
+impl<T: Real> HasSteeringBehavior3d<T> for Face3d<T> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior3d<T>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real> SteeringAcceleration3dCalculator<T> for Face3d<T> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        validation::direction("forward", Some(self.forward))
+            .and_then(|_| {
+                validation::arrival(
+                    "allign_tolerance",
+                    Some(self.allign_tolerance),
+                    Some(self.deceleration_radius),
+                    Some(self.time_to_target),
+                )
+            })
+            .map_err(SteeringError::InvalidParameter)
+    }
+
+    fn get_status(&self) -> SteeringStatus {
+        let target = self.target.borrow();
+        target_status(target.get_position(), || {
+            let behavior = self.behavior.borrow();
+            let owner = behavior.owner.borrow();
+            let to_target = target.get_position() - owner.get_position();
+            if to_target.is_zero() ||
+                (*owner.get_orientation() * self.forward).angle(&to_target) <=
+                    self.allign_tolerance
+            {
+                SteeringStatus::Aligned
+            } else {
+                SteeringStatus::InProgress
+            }
+        })
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration3d<T>>>,
+    ) -> Rc<RefCell<SteeringAcceleration3d<T>>> {
+        let behavior = self.behavior.borrow();
+        let owner_orientation = *behavior.owner.borrow().get_orientation();
+        let to_target = self.target.borrow().get_position() -
+            behavior.owner.borrow().get_position();
+
+        let threshold = match behavior.limiter {
+            Some(ref lim) => lim.borrow().get_zero_linear_speed_threshold(),
+            None => T::zero(),
+        };
+        if to_target.norm_squared() < threshold || to_target.is_zero() {
+            steering_acceleration.borrow_mut().set_zero();
+            return steering_acceleration;
+        }
+
+        let target_orientation = shortest_arc(&(owner_orientation * self.forward), &to_target) *
+            owner_orientation;
+        reach_orientation(
+            &behavior,
+            &mut steering_acceleration.borrow_mut(),
+            &target_orientation,
+            self.allign_tolerance,
+            self.deceleration_radius,
+            self.time_to_target,
+        );
+        steering_acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Face3d, Face3dBuilder};
+    use SteeringStatus;
+    use super::super::{StaticLocation3d, SteeringAcceleration3d, SteeringAcceleration3dCalculator,
+                       SteeringBehavior3d};
+    use super::super::test_common::TestSteerable3d;
+    use nalgebra::{UnitQuaternion, Vector3};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn face(target: Vector3<f32>) -> Face3d<f32> {
+        let owner = TestSteerable3d::new(Vector3::new(0.0, 0.0, 0.0));
+        Face3d {
+            behavior: RefCell::new(SteeringBehavior3d {
+                enabled: true,
+                limiter: None,
+                owner: Rc::new(RefCell::new(owner)),
+            }),
+            target: Rc::new(RefCell::new(
+                StaticLocation3d::new(target, UnitQuaternion::identity()),
+            )),
+            forward: Vector3::y(),
+            allign_tolerance: 0.01,
+            deceleration_radius: 0.1,
+            time_to_target: 1.0,
+        }
+    }
+
+    fn face_towards(target: Vector3<f32>) -> Vector3<f32> {
+        let sa = Rc::new(RefCell::new(SteeringAcceleration3d::default()));
+        let angular = face(target).calculate_steering(sa).borrow().angular;
+        angular
+    }
+
+    #[test]
+    fn test_turn_direction() {
+        let right = face_towards(Vector3::new(1.0, 0.0, 0.0));
+        let left = face_towards(Vector3::new(-1.0, 0.0, 0.0));
+        assert!(right.z < 0.0);
+        assert!(left.z > 0.0);
+        assert!((right.z + left.z).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_pitch_up() {
+        let up = face_towards(Vector3::new(0.0, 1.0, 1.0));
+        assert!(up.x > 0.0);
+        assert!(up.y.abs() < 1e-6 && up.z.abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_already_facing() {
+        assert_eq!(
+            Vector3::new(0.0f32, 0.0, 0.0),
+            face_towards(Vector3::new(0.0, 5.0, 0.0))
+        );
+    }
+
+    #[test]
+    fn status_reports_alignment() {
+        let sa = Rc::new(RefCell::new(SteeringAcceleration3d::default()));
+        let (sa, status) = face(Vector3::new(0.0, 5.0, 0.0)).calculate_steering_with_status(sa);
+        assert_eq!(SteeringStatus::Aligned, status);
+        assert!(sa.borrow().is_zero());
+        let sa = Rc::new(RefCell::new(SteeringAcceleration3d::default()));
+        let (_, status) = face(Vector3::new(1.0, 0.0, 0.0)).calculate_steering_with_status(sa);
+        assert_eq!(SteeringStatus::InProgress, status);
+        let lost = face(Vector3::new(::std::f32::NAN, 0.0, 0.0));
+        assert_eq!(SteeringStatus::TargetLost, lost.get_status());
+    }
+
+    #[test]
+    fn builder_rejects_invalid_parameters() {
+        let builder = Face3dBuilder::default()
+            .behavior(RefCell::new(SteeringBehavior3d {
+                enabled: true,
+                limiter: None,
+                owner: Rc::new(RefCell::new(TestSteerable3d::new(Vector3::new(0.0, 0.0, 0.0)))),
+            }))
+            .target(Rc::new(RefCell::new(StaticLocation3d::new(
+                Vector3::new(0.0f32, 5.0, 0.0),
+                UnitQuaternion::identity(),
+            ))))
+            .allign_tolerance(0.01)
+            .deceleration_radius(0.1)
+            .time_to_target(1.0);
+        assert!(builder.build().is_ok());
+        assert_eq!(
+            Err("forward must be a finite non-zero vector".to_string()),
+            builder.forward(Vector3::new(0.0, 0.0, 0.0)).build().map(|_| ())
+        );
+        assert_eq!(
+            Err("forward must be a finite non-zero vector".to_string()),
+            builder
+                .forward(Vector3::new(::std::f32::NAN, 1.0, 0.0))
+                .build()
+                .map(|_| ())
+        );
+        assert_eq!(
+            Err("allign_tolerance must not be a negative number".to_string()),
+            builder.allign_tolerance(-0.1).build().map(|_| ())
+        );
+        assert_eq!(
+            Err("time_to_target must be a positive number".to_string()),
+            builder.time_to_target(-1.0).build().map(|_| ())
+        );
+
+        let mut face = face(Vector3::new(1.0, 0.0, 0.0));
+        face.forward = Vector3::new(0.0, 0.0, ::std::f32::INFINITY);
+        assert!(face.validate().is_err());
+    }
+}
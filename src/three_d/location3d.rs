@@ -0,0 +1,11 @@
+use nalgebra::{UnitQuaternion, Vector3};
+use alga::general::Real;
+
+/// Anything that has a position and a full 3D orientation
+pub trait Location3d<T: Real> {
+    /// returns the position of the location
+    fn get_position(&self) -> &Vector3<T>;
+
+    /// returns the rotation from world axes to the local axes of the location
+    fn get_orientation(&self) -> &UnitQuaternion<T>;
+}
@@ -0,0 +1,260 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use validation;
+use {SteeringError, SteeringStatus};
+use num_traits::identities::Zero;
+use super::{HasSteeringBehavior3d, SteeringAcceleration3d, SteeringAcceleration3dCalculator,
+            SteeringBehavior3d};
+use super::reach_orientation3d::{reach_orientation, shortest_arc};
+
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+///LookWhereYouAreGoing behavior rotates owner so that its forward axis points along
+///its linear velocity. It only produces an angular acceleration component.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct LookWhereYouAreGoing3d<T>
+where
+    T: Real,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior3d<T>>,
+    /// Forward axis of the owner in its local frame, positive y axis by default
+    #[builder(default = "Vector3::y()")]
+    pub forward: Vector3<T>,
+    /// Stop rotating if remaining angle is smaller than this
+    pub allign_tolerance: T,
+    /// Reduce the angular speed if remaining angle is smaller than this
+    pub deceleration_radius: T,
+    /// How quick should we align with the velocity
+    pub time_to_target: T,
+}
+
+/// Tunable parameters of `LookWhereYouAreGoing3d` which can be loaded from data files. Owner and
+/// target are not part of them, they are supplied when the behavior is created.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LookWhereYouAreGoing3dParameters<T: Real> {
+    /// Forward axis of the owner in its local frame
+    pub forward: Vector3<T>,
+    /// Stop rotating if remaining angle is smaller than this
+    pub allign_tolerance: T,
+    /// Reduce the angular speed if remaining angle is smaller than this
+    pub deceleration_radius: T,
+    /// How quick should we reach the goal orientation
+    pub time_to_target: T,
+}
+
+impl<T: Real> LookWhereYouAreGoing3dBuilder<T> {
+    fn validate(&self) -> Result<(), String> {
+        validation::direction("forward", self.forward)?;
+        validation::arrival(
+            "allign_tolerance",
+            self.allign_tolerance,
+            self.deceleration_radius,
+            self.time_to_target,
+        )
+    }
+}
+
+impl<T: Real> LookWhereYouAreGoing3d<T> {
+    /// Creates the behavior using parameters loaded from a data file
+    pub fn with_parameters(
+        behavior: SteeringBehavior3d<T>,
+        parameters: &LookWhereYouAreGoing3dParameters<T>,
+    ) -> LookWhereYouAreGoing3d<T> {
+        LookWhereYouAreGoing3d {
+            behavior: RefCell::new(behavior),
+            forward: parameters.forward,
+            allign_tolerance: parameters.allign_tolerance,
+            deceleration_radius: parameters.deceleration_radius,
+            time_to_target: parameters.time_to_target,
+        }
+    }
+
+    /// returns the tunable parameters of the behavior
+    pub fn parameters(&self) -> LookWhereYouAreGoing3dParameters<T> {
+        LookWhereYouAreGoing3dParameters {
+            forward: self.forward,
+            allign_tolerance: self.allign_tolerance,
+            deceleration_radius: self.deceleration_radius,
+            time_to_target: self.time_to_target,
+        }
+    }
+
+    /// Replaces the tunable parameters of the behavior
+    pub fn set_parameters(&mut self, parameters: &LookWhereYouAreGoing3dParameters<T>) {
+        self.forward = parameters.forward;
+        self.allign_tolerance = parameters.allign_tolerance;
+        self.deceleration_radius = parameters.deceleration_radius;
+        self.time_to_target = parameters.time_to_target;
+    }
+}
+
+impl<T: Real> HasSteeringBehavior3d<T> for LookWhereYouAreGoing3d<T> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior3d<T>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real> SteeringAcceleration3dCalculator<T> for LookWhereYouAreGoing3d<T> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        validation::direction("forward", Some(self.forward))
+            .and_then(|_| {
+                validation::arrival(
+                    "allign_tolerance",
+                    Some(self.allign_tolerance),
+                    Some(self.deceleration_radius),
+                    Some(self.time_to_target),
+                )
+            })
+            .map_err(SteeringError::InvalidParameter)
+    }
+
+    fn get_status(&self) -> SteeringStatus {
+        let behavior = self.behavior.borrow();
+        let owner = behavior.owner.borrow();
+        let velocity = owner.get_linear_velocity();
+        if velocity.is_zero() ||
+            (*owner.get_orientation() * self.forward).angle(velocity) <= self.allign_tolerance
+        {
+            SteeringStatus::Aligned
+        } else {
+            SteeringStatus::InProgress
+        }
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration3d<T>>>,
+    ) -> Rc<RefCell<SteeringAcceleration3d<T>>> {
+        let behavior = self.behavior.borrow();
+        let owner_orientation = *behavior.owner.borrow().get_orientation();
+        let velocity = *behavior.owner.borrow().get_linear_velocity();
+
+        let threshold = match behavior.limiter {
+            Some(ref lim) => lim.borrow().get_zero_linear_speed_threshold(),
+            None => T::zero(),
+        };
+        if velocity.norm_squared() < threshold || velocity.is_zero() {
+            steering_acceleration.borrow_mut().set_zero();
+            return steering_acceleration;
+        }
+
+        let target_orientation = shortest_arc(&(owner_orientation * self.forward), &velocity) *
+            owner_orientation;
+        reach_orientation(
+            &behavior,
+            &mut steering_acceleration.borrow_mut(),
+            &target_orientation,
+            self.allign_tolerance,
+            self.deceleration_radius,
+            self.time_to_target,
+        );
+        steering_acceleration
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{LookWhereYouAreGoing3d, LookWhereYouAreGoing3dBuilder};
+    use SteeringStatus;
+    use super::super::{SteeringAcceleration3d, SteeringAcceleration3dCalculator,
+                       SteeringBehavior3d};
+    use super::super::test_common::TestSteerable3d;
+    use nalgebra::Vector3;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn look(velocity: Vector3<f32>) -> LookWhereYouAreGoing3d<f32> {
+        let mut owner = TestSteerable3d::new(Vector3::new(0.0, 0.0, 0.0));
+        owner.linear_velocity = velocity;
+        LookWhereYouAreGoing3d {
+            behavior: RefCell::new(SteeringBehavior3d {
+                enabled: true,
+                limiter: None,
+                owner: Rc::new(RefCell::new(owner)),
+            }),
+            forward: Vector3::y(),
+            allign_tolerance: 0.01,
+            deceleration_radius: 1.0,
+            time_to_target: 1.0,
+        }
+    }
+
+    fn angular_along(velocity: Vector3<f32>) -> Vector3<f32> {
+        let sa = Rc::new(RefCell::new(SteeringAcceleration3d::default()));
+        let angular = look(velocity).calculate_steering(sa).borrow().angular;
+        angular
+    }
+
+    #[test]
+    fn turns_towards_velocity() {
+        let right = angular_along(Vector3::new(3.0, 0.0, 0.0));
+        assert!((right - Vector3::new(0.0, 0.0, -1.0)).norm() < 1e-5);
+        let climb = angular_along(Vector3::new(0.0, 1.0, 1.0));
+        assert!(climb.x > 0.0 && climb.y.abs() < 1e-5 && climb.z.abs() < 1e-5);
+        // moving backwards turns around some axis at full speed
+        let back = angular_along(Vector3::new(0.0, -2.0, 0.0));
+        assert!((back.norm() - 1.0).abs() < 1e-5);
+        assert!(back.y.abs() < 1e-5);
+    }
+
+    #[test]
+    fn slows_down_within_deceleration_radius() {
+        let velocity = Vector3::new((0.5f32).sin(), (0.5f32).cos(), 0.0);
+        let angular = angular_along(velocity * 4.0);
+        assert!((angular - Vector3::new(0.0, 0.0, -0.5)).norm() < 1e-5);
+        assert_eq!(Vector3::new(0.0, 0.0, 0.0), angular_along(Vector3::new(0.0, 4.0, 0.0)));
+    }
+
+    #[test]
+    fn does_not_turn_without_velocity() {
+        let mut standing = look(Vector3::new(0.0, 0.0, 0.0));
+        let sa = Rc::new(RefCell::new(SteeringAcceleration3d::default()));
+        let (sa, status) = standing.calculate_steering_with_status(sa);
+        assert!(sa.borrow().is_zero());
+        assert_eq!(SteeringStatus::Aligned, status);
+        let moving = look(Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(SteeringStatus::InProgress, moving.get_status());
+    }
+
+    #[test]
+    fn builder_rejects_invalid_parameters() {
+        let builder = LookWhereYouAreGoing3dBuilder::default()
+            .behavior(RefCell::new(SteeringBehavior3d {
+                enabled: true,
+                limiter: None,
+                owner: Rc::new(RefCell::new(TestSteerable3d::new(Vector3::new(0.0, 0.0, 0.0)))),
+            }))
+            .allign_tolerance(0.01f32)
+            .deceleration_radius(1.0)
+            .time_to_target(1.0);
+        assert!(builder.build().is_ok());
+        assert_eq!(
+            Err("forward must be a finite non-zero vector".to_string()),
+            builder.forward(Vector3::new(0.0, 0.0, 0.0)).build().map(|_| ())
+        );
+        assert_eq!(
+            Err("forward must be a finite non-zero vector".to_string()),
+            builder
+                .forward(Vector3::new(0.0, ::std::f32::INFINITY, 0.0))
+                .build()
+                .map(|_| ())
+        );
+        assert_eq!(
+            Err("allign_tolerance must not be a negative number".to_string()),
+            builder.allign_tolerance(-0.1).build().map(|_| ())
+        );
+        assert_eq!(
+            Err("time_to_target must be a positive number".to_string()),
+            builder.time_to_target(0.0).build().map(|_| ())
+        );
+
+        let mut look = look(Vector3::new(1.0, 0.0, 0.0));
+        look.forward = Vector3::new(0.0, 0.0, 0.0);
+        assert!(look.validate().is_err());
+    }
+}
@@ -0,0 +1,24 @@
+mod location3d;
+mod static_location3d;
+mod steerable3d;
+mod steering_acceleration3d;
+mod steering_behavior3d;
+mod reach_orientation3d;
+mod face3d;
+mod look_where_you_are_going3d;
+mod banking;
+#[cfg(test)]
+mod test_common;
+
+pub use self::location3d::Location3d;
+pub use self::static_location3d::StaticLocation3d;
+pub use self::steerable3d::Steerable3d;
+pub use self::steering_acceleration3d::{SteeringAcceleration3d, SteeringAcceleration3dCalculator};
+pub use self::steering_behavior3d::{HasSteeringBehavior3d, SteeringBehavior3d};
+pub use self::reach_orientation3d::{shortest_arc, ReachOrientation3d, ReachOrientation3dBuilder,
+                                      ReachOrientation3dParameters};
+pub use self::face3d::{Face3d, Face3dBuilder, Face3dParameters};
+pub use self::look_where_you_are_going3d::{LookWhereYouAreGoing3d,
+                                              LookWhereYouAreGoing3dBuilder,
+                                              LookWhereYouAreGoing3dParameters};
+pub use self::banking::{Banking, FlightAttitude, FlyingSteering};
@@ -0,0 +1,312 @@
+use nalgebra::{UnitQuaternion, Vector3};
+use alga::general::Real;
+use validation;
+use {SteeringError, SteeringStatus};
+use steering_status::target_status;
+use num_traits::identities::Zero;
+use super::{HasSteeringBehavior3d, Location3d, SteeringAcceleration3d,
+            SteeringAcceleration3dCalculator, SteeringBehavior3d};
+
+use std::cell::RefMut;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+///ReachOrientation behavior aims to rotate owner until its orientation matches the
+///orientation of the target. Rotation always follows the shortest arc between the two
+///orientations. It only produces an angular acceleration component.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder)]
+pub struct ReachOrientation3d<T>
+where
+    T: Real,
+{
+    /// Common behavior attributes
+    pub behavior: RefCell<SteeringBehavior3d<T>>,
+    /// Location whose orientation will be reached
+    pub target: Rc<RefCell<Location3d<T>>>,
+    /// Stop rotating if remaining angle is smaller than this
+    pub allign_tolerance: T,
+    /// Reduce the angular speed if remaining angle is smaller than this
+    pub deceleration_radius: T,
+    /// How quick should we reach the target orientation
+    pub time_to_target: T,
+}
+
+/// Tunable parameters of `ReachOrientation3d` which can be loaded from data files. Owner and
+/// target are not part of them, they are supplied when the behavior is created.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ReachOrientation3dParameters<T: Real> {
+    /// Stop rotating if remaining angle is smaller than this
+    pub allign_tolerance: T,
+    /// Reduce the angular speed if remaining angle is smaller than this
+    pub deceleration_radius: T,
+    /// How quick should we reach the goal orientation
+    pub time_to_target: T,
+}
+
+impl<T: Real> ReachOrientation3dBuilder<T> {
+    fn validate(&self) -> Result<(), String> {
+        validation::arrival(
+            "allign_tolerance",
+            self.allign_tolerance,
+            self.deceleration_radius,
+            self.time_to_target,
+        )
+    }
+}
+
+impl<T: Real> ReachOrientation3d<T> {
+    /// Creates the behavior using parameters loaded from a data file
+    pub fn with_parameters(
+        behavior: SteeringBehavior3d<T>,
+        target: Rc<RefCell<Location3d<T>>>,
+        parameters: &ReachOrientation3dParameters<T>,
+    ) -> ReachOrientation3d<T> {
+        ReachOrientation3d {
+            behavior: RefCell::new(behavior),
+            target: target,
+            allign_tolerance: parameters.allign_tolerance,
+            deceleration_radius: parameters.deceleration_radius,
+            time_to_target: parameters.time_to_target,
+        }
+    }
+
+    /// returns the tunable parameters of the behavior
+    pub fn parameters(&self) -> ReachOrientation3dParameters<T> {
+        ReachOrientation3dParameters {
+            allign_tolerance: self.allign_tolerance,
+            deceleration_radius: self.deceleration_radius,
+            time_to_target: self.time_to_target,
+        }
+    }
+
+    /// Replaces the tunable parameters of the behavior
+    pub fn set_parameters(&mut self, parameters: &ReachOrientation3dParameters<T>) {
+        self.allign_tolerance = parameters.allign_tolerance;
+        self.deceleration_radius = parameters.deceleration_radius;
+        self.time_to_target = parameters.time_to_target;
+    }
+}
+
+impl<T: Real> HasSteeringBehavior3d<T> for ReachOrientation3d<T> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior3d<T>> {
+        self.behavior.borrow_mut()
+    }
+}
+
+impl<T: Real> SteeringAcceleration3dCalculator<T> for ReachOrientation3d<T> {
+    fn validate(&self) -> Result<(), SteeringError> {
+        validation::arrival(
+            "allign_tolerance",
+            Some(self.allign_tolerance),
+            Some(self.deceleration_radius),
+            Some(self.time_to_target),
+        ).map_err(SteeringError::InvalidParameter)
+    }
+
+    fn get_status(&self) -> SteeringStatus {
+        let target = self.target.borrow();
+        target_status(target.get_position(), || {
+            let behavior = self.behavior.borrow();
+            let owner = behavior.owner.borrow();
+            if owner.get_orientation().angle_to(target.get_orientation()) <= self.allign_tolerance {
+                SteeringStatus::Aligned
+            } else {
+                SteeringStatus::InProgress
+            }
+        })
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration3d<T>>>,
+    ) -> Rc<RefCell<SteeringAcceleration3d<T>>> {
+        reach_orientation(
+            &self.behavior.borrow(),
+            &mut steering_acceleration.borrow_mut(),
+            self.target.borrow().get_orientation(),
+            self.allign_tolerance,
+            self.deceleration_radius,
+            self.time_to_target,
+        );
+        steering_acceleration
+    }
+}
+
+/// Calculates the angular acceleration that rotates owner of the behavior towards
+/// target orientation along the shortest arc
+pub fn reach_orientation<T: Real>(
+    behavior: &SteeringBehavior3d<T>,
+    steering_acceleration: &mut SteeringAcceleration3d<T>,
+    target_orientation: &UnitQuaternion<T>,
+    allign_tolerance: T,
+    deceleration_radius: T,
+    time_to_target: T,
+) {
+    let owner = behavior.owner.borrow();
+    let rotation = owner.get_orientation().rotation_to(target_orientation);
+    let abs_rotation = rotation.angle();
+    let axis = match rotation.axis() {
+        Some(axis) => axis,
+        None => {
+            steering_acceleration.set_zero();
+            return;
+        }
+    };
+    if abs_rotation <= allign_tolerance {
+        steering_acceleration.set_zero();
+        return;
+    }
+
+    let mut target_rotation = match behavior.limiter {
+        Some(ref lim) => lim.borrow().get_max_angular_speed(),
+        None => T::one(),
+    };
+    if abs_rotation < deceleration_radius {
+        target_rotation *= abs_rotation / deceleration_radius;
+    }
+
+    steering_acceleration.angular =
+        (axis.unwrap() * target_rotation - *owner.get_angular_velocity()) / time_to_target;
+    if let Some(ref lim) = behavior.limiter {
+        let max_acceleration = lim.borrow().get_max_angular_acceleration();
+        let angular_acceleration = steering_acceleration.angular.norm();
+        if angular_acceleration > max_acceleration {
+            steering_acceleration.angular *= max_acceleration / angular_acceleration;
+        }
+    }
+    steering_acceleration.linear = Vector3::zero();
+}
+
+/// Returns the smallest rotation which turns `from` direction into `to` direction.
+/// Opposite directions are turned around an arbitrary perpendicular axis.
+pub fn shortest_arc<T: Real>(from: &Vector3<T>, to: &Vector3<T>) -> UnitQuaternion<T> {
+    match UnitQuaternion::rotation_between(from, to) {
+        Some(rotation) => rotation,
+        None => {
+            let mut perpendicular = from.cross(&Vector3::x());
+            if perpendicular.norm_squared() <= T::default_epsilon() {
+                perpendicular = from.cross(&Vector3::y());
+            }
+            let axis = ::nalgebra::Unit::new_normalize(perpendicular);
+            UnitQuaternion::from_axis_angle(&axis, T::pi())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{shortest_arc, ReachOrientation3d, ReachOrientation3dBuilder};
+    use SteeringStatus;
+    use super::super::{StaticLocation3d, SteeringAcceleration3d, SteeringAcceleration3dCalculator,
+                       SteeringBehavior3d};
+    use super::super::test_common::TestSteerable3d;
+    use nalgebra::{UnitQuaternion, Vector3};
+    use std::cell::RefCell;
+    use std::f32::consts::PI;
+    use std::rc::Rc;
+
+    fn reach(target: UnitQuaternion<f32>, owner: TestSteerable3d) -> ReachOrientation3d<f32> {
+        ReachOrientation3d {
+            behavior: RefCell::new(SteeringBehavior3d {
+                enabled: true,
+                limiter: None,
+                owner: Rc::new(RefCell::new(owner)),
+            }),
+            target: Rc::new(RefCell::new(
+                StaticLocation3d::new(Vector3::new(0.0, 5.0, 0.0), target),
+            )),
+            allign_tolerance: 0.1,
+            deceleration_radius: 1.0,
+            time_to_target: 1.0,
+        }
+    }
+
+    fn about_z(angle: f32) -> UnitQuaternion<f32> {
+        UnitQuaternion::from_axis_angle(&Vector3::z_axis(), angle)
+    }
+
+    fn angular_towards(target: UnitQuaternion<f32>) -> Vector3<f32> {
+        let owner = TestSteerable3d::new(Vector3::new(0.0, 0.0, 0.0));
+        let sa = Rc::new(RefCell::new(SteeringAcceleration3d::default()));
+        let angular = reach(target, owner).calculate_steering(sa).borrow().angular;
+        angular
+    }
+
+    #[test]
+    fn turns_along_the_shortest_arc() {
+        // three quarters of a turn counterclockwise are reached turning clockwise
+        let angular = angular_towards(about_z(1.5 * PI));
+        assert!((angular - Vector3::new(0.0, 0.0, -1.0)).norm() < 1e-5);
+        let angular = angular_towards(about_z(0.5 * PI));
+        assert!((angular - Vector3::new(0.0, 0.0, 1.0)).norm() < 1e-5);
+
+        let from = Vector3::new(0.0f32, 1.0, 0.0);
+        let arc = shortest_arc(&from, &Vector3::new(1.0, 0.0, 0.0));
+        assert!((arc.angle() - PI / 2.0).abs() < 1e-5);
+        // opposite directions still turn onto each other
+        let arc = shortest_arc(&from, &Vector3::new(0.0, -1.0, 0.0));
+        assert!((arc * from - Vector3::new(0.0, -1.0, 0.0)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn slows_down_within_deceleration_radius() {
+        let angular = angular_towards(about_z(0.5));
+        assert!((angular - Vector3::new(0.0, 0.0, 0.5)).norm() < 1e-5);
+        let angular = angular_towards(UnitQuaternion::from_axis_angle(&Vector3::x_axis(), 0.25));
+        assert!((angular - Vector3::new(0.25, 0.0, 0.0)).norm() < 1e-5);
+        // within the tolerance nothing is left to do
+        let angular = angular_towards(about_z(0.05));
+        assert_eq!(Vector3::new(0.0, 0.0, 0.0), angular);
+    }
+
+    #[test]
+    fn accounts_for_current_rotation() {
+        let mut owner = TestSteerable3d::new(Vector3::new(0.0, 0.0, 0.0));
+        owner.angular_velocity = Vector3::new(0.0, 0.0, 0.75);
+        let target = about_z(2.0);
+        let sa = Rc::new(RefCell::new(SteeringAcceleration3d::default()));
+        let angular = reach(target, owner).calculate_steering(sa).borrow().angular;
+        assert!((angular - Vector3::new(0.0, 0.0, 0.25)).norm() < 1e-5);
+    }
+
+    #[test]
+    fn status_reports_alignment() {
+        let owner = TestSteerable3d::new(Vector3::new(0.0, 0.0, 0.0));
+        let aligned = reach(UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 0.05), owner);
+        assert_eq!(SteeringStatus::Aligned, aligned.get_status());
+        let owner = TestSteerable3d::new(Vector3::new(0.0, 0.0, 0.0));
+        let turning = reach(UnitQuaternion::from_axis_angle(&Vector3::y_axis(), 0.5), owner);
+        assert_eq!(SteeringStatus::InProgress, turning.get_status());
+    }
+
+    #[test]
+    fn builder_rejects_invalid_parameters() {
+        let builder = ReachOrientation3dBuilder::default()
+            .behavior(RefCell::new(SteeringBehavior3d {
+                enabled: true,
+                limiter: None,
+                owner: Rc::new(RefCell::new(TestSteerable3d::new(Vector3::new(0.0, 0.0, 0.0)))),
+            }))
+            .target(Rc::new(RefCell::new(
+                StaticLocation3d::new(Vector3::new(0.0f32, 5.0, 0.0), about_z(PI)),
+            )))
+            .allign_tolerance(0.1)
+            .deceleration_radius(1.0)
+            .time_to_target(1.0);
+        assert!(builder.build().is_ok());
+        assert_eq!(
+            Err("allign_tolerance must not be a negative number".to_string()),
+            builder.allign_tolerance(-0.1).build().map(|_| ())
+        );
+        assert_eq!(
+            Err("time_to_target must be a positive number".to_string()),
+            builder.time_to_target(-1.0).build().map(|_| ())
+        );
+        assert_eq!(
+            Err("allign_tolerance must not be greater than deceleration_radius".to_string()),
+            builder.allign_tolerance(2.0).build().map(|_| ())
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use nalgebra::{UnitQuaternion, Vector3};
+use alga::general::Real;
+use super::Location3d;
+
+/// A fixed point in space with a fixed orientation that can be used as a behavior target
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StaticLocation3d<T: Real> {
+    /// position of the location
+    pub position: Vector3<T>,
+    /// orientation of the location
+    pub orientation: UnitQuaternion<T>,
+}
+
+impl<T: Real> StaticLocation3d<T> {
+    /// Creates a location at given position with given orientation
+    pub fn new(position: Vector3<T>, orientation: UnitQuaternion<T>) -> StaticLocation3d<T> {
+        StaticLocation3d {
+            position: position,
+            orientation: orientation,
+        }
+    }
+}
+
+impl<T: Real> Location3d<T> for StaticLocation3d<T> {
+    fn get_position(&self) -> &Vector3<T> {
+        &self.position
+    }
+
+    fn get_orientation(&self) -> &UnitQuaternion<T> {
+        &self.orientation
+    }
+}
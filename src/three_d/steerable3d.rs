@@ -0,0 +1,15 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use super::Location3d;
+
+/// Steerable agent interface for agents rotating freely in space
+pub trait Steerable3d<T: Real>: Location3d<T> {
+    /// returns the linear velocity vector of the agent
+    fn get_linear_velocity(&self) -> &Vector3<T>;
+
+    /// returns angular velocity of the agent as a rotation axis scaled by radians per second
+    fn get_angular_velocity(&self) -> &Vector3<T>;
+
+    /// returns bounding sphere radius of the agent
+    fn get_bounding_radius(&self) -> T;
+}
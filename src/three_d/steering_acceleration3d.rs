@@ -0,0 +1,145 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use num_traits::identities::Zero;
+use super::HasSteeringBehavior3d;
+use steering_vector::is_finite;
+use {SteeringError, SteeringStatus};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Result of a 3D steering behaviour computation. Angular component is a rotation
+/// axis scaled by the angular acceleration in radians per second squared.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SteeringAcceleration3d<T: Real> {
+    /// linear acceleration component
+    pub linear: Vector3<T>,
+    /// angular acceleration component
+    pub angular: Vector3<T>,
+}
+
+impl<T: Real> SteeringAcceleration3d<T> {
+    pub fn default() -> SteeringAcceleration3d<T> {
+        SteeringAcceleration3d {
+            linear: Vector3::zero(),
+            angular: Vector3::zero(),
+        }
+    }
+
+    /// Creates a steering acceleration struct using given linear and angular components
+    pub fn new(
+        linear_acceleration: Vector3<T>,
+        angular_acceleration: Vector3<T>,
+    ) -> SteeringAcceleration3d<T> {
+        SteeringAcceleration3d {
+            linear: linear_acceleration,
+            angular: angular_acceleration,
+        }
+    }
+
+    /// Tests whether both linear and angular acceleration compenents are zero
+    pub fn is_zero(&self) -> bool {
+        self.angular.is_zero() && self.linear.is_zero()
+    }
+
+    /// Tests whether both linear and angular acceleration components are finite numbers
+    pub fn is_finite(&self) -> bool {
+        self.linear.iter().chain(self.angular.iter()).all(|c| is_finite(*c))
+    }
+
+    /// Sets both compononents to zero
+    pub fn set_zero(&mut self) -> &mut Self {
+        self.angular = Vector3::zero();
+        self.linear = Vector3::zero();
+        self
+    }
+
+    ///
+    pub fn add(&mut self, other: SteeringAcceleration3d<T>) -> &mut Self {
+        self.angular += other.angular;
+        self.linear += other.linear;
+        self
+    }
+
+    ///
+    pub fn scl(&mut self, scale: T) -> &mut Self {
+        self.angular *= scale;
+        self.linear *= scale;
+        self
+    }
+
+    ///
+    pub fn mul_add(&mut self, other: SteeringAcceleration3d<T>, scale: T) -> &mut Self {
+        self.angular += other.angular * scale;
+        self.linear += other.linear * scale;
+        self
+    }
+
+    ///
+    pub fn calculate_square_magnitude(&self) -> T {
+        self.linear.norm_squared() + self.angular.norm_squared()
+    }
+
+    ///
+    pub fn calculate_magnitude(&self) -> T {
+        self.calculate_square_magnitude().sqrt()
+    }
+}
+
+pub trait SteeringAcceleration3dCalculator<T: Real>: HasSteeringBehavior3d<T> {
+    fn calculate_steering(
+        &mut self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration3d<T>>>,
+    ) -> Rc<RefCell<SteeringAcceleration3d<T>>> {
+        if self.get_steering_behavior().enabled {
+            self.calculate_real_steering(steering_acceleration.clone());
+            steering_acceleration
+        } else {
+            steering_acceleration.borrow_mut().set_zero();
+            steering_acceleration
+        }
+    }
+
+    /// Checks that parameters of the behavior make sense
+    fn validate(&self) -> Result<(), SteeringError> {
+        Ok(())
+    }
+
+    /// returns how far the behavior got towards its goal, behaviors without a goal
+    /// are always in progress
+    fn get_status(&self) -> SteeringStatus {
+        SteeringStatus::InProgress
+    }
+
+    /// Calculates steering like `calculate_steering` does and returns the status
+    /// of the behavior alongside the acceleration
+    fn calculate_steering_with_status(
+        &mut self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration3d<T>>>,
+    ) -> (Rc<RefCell<SteeringAcceleration3d<T>>>, SteeringStatus) {
+        let steering_acceleration = self.calculate_steering(steering_acceleration);
+        (steering_acceleration, self.get_status())
+    }
+
+    /// Calculates steering like `calculate_steering` does but fails instead of
+    /// returning an acceleration which is not a finite number or calculating with
+    /// invalid parameters
+    fn try_calculate_steering(
+        &mut self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration3d<T>>>,
+    ) -> Result<Rc<RefCell<SteeringAcceleration3d<T>>>, SteeringError> {
+        self.validate()?;
+        let steering_acceleration = self.calculate_steering(steering_acceleration);
+        if steering_acceleration.borrow().is_finite() {
+            Ok(steering_acceleration)
+        } else {
+            Err(SteeringError::NonFiniteAcceleration)
+        }
+    }
+
+    fn calculate_real_steering(
+        &self,
+        steering_acceleration: Rc<RefCell<SteeringAcceleration3d<T>>>,
+    ) -> Rc<RefCell<SteeringAcceleration3d<T>>>;
+}
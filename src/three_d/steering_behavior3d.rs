@@ -0,0 +1,26 @@
+use limiter::Limiter;
+use alga::general::Real;
+
+use std::cell::RefCell;
+use std::cell::RefMut;
+use std::rc::Rc;
+
+use super::Steerable3d;
+/// Common properties of 3D steering behaviors
+#[builder(pattern = "immutable")]
+#[derive(Builder, Clone)]
+pub struct SteeringBehavior3d<T>
+where
+    T: Real,
+{
+    /// is this behavior enabled
+    pub enabled: bool,
+    /// limitations on speed and velocity calculations
+    pub limiter: Option<Rc<RefCell<Limiter<T>>>>,
+    /// agent whose steering is calculated
+    pub owner: Rc<RefCell<Steerable3d<T>>>,
+}
+
+pub trait HasSteeringBehavior3d<T: Real> {
+    fn get_steering_behavior(&mut self) -> RefMut<SteeringBehavior3d<T>>;
+}
@@ -0,0 +1,44 @@
+use super::{Location3d, Steerable3d};
+use nalgebra::{UnitQuaternion, Vector3};
+
+pub struct TestSteerable3d {
+    pub position: Vector3<f32>,
+    pub orientation: UnitQuaternion<f32>,
+    pub linear_velocity: Vector3<f32>,
+    pub angular_velocity: Vector3<f32>,
+}
+
+impl TestSteerable3d {
+    pub fn new(position: Vector3<f32>) -> Self {
+        TestSteerable3d {
+            position: position,
+            orientation: UnitQuaternion::identity(),
+            linear_velocity: Vector3::new(0.0, 0.0, 0.0),
+            angular_velocity: Vector3::new(0.0, 0.0, 0.0),
+        }
+    }
+}
+
+impl Location3d<f32> for TestSteerable3d {
+    fn get_position(&self) -> &Vector3<f32> {
+        &self.position
+    }
+
+    fn get_orientation(&self) -> &UnitQuaternion<f32> {
+        &self.orientation
+    }
+}
+
+impl Steerable3d<f32> for TestSteerable3d {
+    fn get_linear_velocity(&self) -> &Vector3<f32> {
+        &self.linear_velocity
+    }
+
+    fn get_angular_velocity(&self) -> &Vector3<f32> {
+        &self.angular_velocity
+    }
+
+    fn get_bounding_radius(&self) -> f32 {
+        1.0
+    }
+}
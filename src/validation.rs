@@ -0,0 +1,90 @@
+use alga::general::Real;
+use steering_vector::{is_finite, SteeringVector};
+
+/// Checks that parameter is a finite number greater than zero
+pub fn positive<T: Real>(name: &str, value: Option<T>) -> Result<(), String> {
+    match value {
+        Some(value) if !(value > T::zero()) || !is_finite(value) => {
+            Err(format!("{} must be a positive number", name))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that parameter is a finite number not less than zero
+pub fn non_negative<T: Real>(name: &str, value: Option<T>) -> Result<(), String> {
+    match value {
+        Some(value) if !(value >= T::zero()) || !is_finite(value) => {
+            Err(format!("{} must not be a negative number", name))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a direction parameter is a finite vector which is not zero
+pub fn direction<T: Real, V: SteeringVector<T>>(
+    name: &str,
+    value: Option<V>,
+) -> Result<(), String> {
+    match value {
+        Some(value) if value.is_zero() || !value.is_finite() => {
+            Err(format!("{} must be a finite non-zero vector", name))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks that a parameter is not greater than another one
+pub fn not_greater<T: Real>(
+    name: &str,
+    value: Option<T>,
+    other_name: &str,
+    other: Option<T>,
+) -> Result<(), String> {
+    match (value, other) {
+        (Some(value), Some(other)) if value > other => {
+            Err(format!("{} must not be greater than {}", name, other_name))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks tolerance, deceleration radius and time to target parameters shared by
+/// behaviors which slow down when approaching their goal
+pub fn arrival<T: Real>(
+    tolerance_name: &str,
+    tolerance: Option<T>,
+    deceleration_radius: Option<T>,
+    time_to_target: Option<T>,
+) -> Result<(), String> {
+    non_negative(tolerance_name, tolerance)?;
+    non_negative("deceleration_radius", deceleration_radius)?;
+    positive("time_to_target", time_to_target)?;
+    not_greater(
+        tolerance_name,
+        tolerance,
+        "deceleration_radius",
+        deceleration_radius,
+    )
+}
+
+/// Checks wander circle parameters and the parameters used to face the wander
+/// target
+pub fn wander<T: Real>(
+    wander_offset: Option<T>,
+    wander_radius: Option<T>,
+    wander_rate: Option<T>,
+    allign_tolerance: Option<T>,
+    deceleration_radius: Option<T>,
+    time_to_target: Option<T>,
+) -> Result<(), String> {
+    non_negative("wander_offset", wander_offset)?;
+    non_negative("wander_radius", wander_radius)?;
+    non_negative("wander_rate", wander_rate)?;
+    arrival(
+        "allign_tolerance",
+        allign_tolerance,
+        deceleration_radius,
+        time_to_target,
+    )
+}
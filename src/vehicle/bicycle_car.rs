@@ -0,0 +1,169 @@
+use alga::general::Real;
+use {SteeringAcceleration, SteeringVector};
+use super::{VehicleControls, VehicleModel, VehicleState};
+use super::vehicle_model::{achieved_acceleration, clamp, longitudinal_controls};
+use validation;
+
+/// Kinematic bicycle model of a car with Ackermann steering. Front wheels turn at
+/// most `max_steering_angle`, which bounds the turning radius to
+/// `wheelbase / tan(max_steering_angle)`. The car does not drive backwards, braking
+/// stops it.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BicycleCar<T>
+where
+    T: Real,
+{
+    /// Distance between front and rear axles
+    pub wheelbase: T,
+    /// Largest front wheel angle in radians
+    pub max_steering_angle: T,
+    /// Acceleration at full throttle
+    pub max_acceleration: T,
+    /// Deceleration at full brake
+    pub max_deceleration: T,
+    /// Top speed of the car
+    pub max_speed: T,
+}
+
+impl<T: Real> BicycleCarBuilder<T> {
+    fn validate(&self) -> Result<(), String> {
+        validation::positive("wheelbase", self.wheelbase)?;
+        validation::positive("max_steering_angle", self.max_steering_angle)?;
+        match self.max_steering_angle {
+            Some(angle) if angle >= T::frac_pi_2() => {
+                return Err("max_steering_angle must be less than a right angle".to_string())
+            }
+            _ => (),
+        }
+        validation::positive("max_acceleration", self.max_acceleration)?;
+        validation::positive("max_deceleration", self.max_deceleration)?;
+        validation::positive("max_speed", self.max_speed)
+    }
+}
+
+impl<T: Real> BicycleCar<T> {
+    /// returns the smallest turning radius of the car
+    pub fn min_turning_radius(&self) -> T {
+        self.wheelbase / self.max_steering_angle.tan()
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> VehicleModel<T, V> for BicycleCar<T> {
+    fn controls(
+        &self,
+        state: &VehicleState<T, V>,
+        desired: &SteeringAcceleration<T, V>,
+    ) -> VehicleControls<T> {
+        let speed = state.forward_speed();
+        let (throttle, brake) = longitudinal_controls(
+            desired.linear.dot(&state.forward()),
+            self.max_acceleration,
+            self.max_deceleration,
+        );
+        let lateral = desired.linear.dot(&state.left());
+        let steering_angle = if speed > T::default_epsilon() {
+            (lateral * self.wheelbase / (speed * speed)).atan()
+        } else if lateral > T::zero() {
+            self.max_steering_angle
+        } else if lateral < T::zero() {
+            -self.max_steering_angle
+        } else {
+            T::zero()
+        };
+        VehicleControls {
+            throttle: throttle,
+            brake: brake,
+            steer: clamp(steering_angle / self.max_steering_angle, -T::one(), T::one()),
+        }
+    }
+
+    fn step(
+        &self,
+        state: &mut VehicleState<T, V>,
+        controls: &VehicleControls<T>,
+        delta_time: T,
+    ) -> SteeringAcceleration<T, V> {
+        let before = state.clone();
+        let acceleration =
+            controls.throttle * self.max_acceleration - controls.brake * self.max_deceleration;
+        let speed = clamp(
+            state.forward_speed() + acceleration * delta_time,
+            T::zero(),
+            self.max_speed,
+        );
+        let steering_angle = clamp(controls.steer, -T::one(), T::one()) * self.max_steering_angle;
+        state.angular_velocity = speed * steering_angle.tan() / self.wheelbase;
+        state.heading += state.angular_velocity * delta_time;
+        state.velocity = state.forward().scale(speed);
+        state.position += state.velocity.scale(delta_time);
+        achieved_acceleration(&before, state, delta_time)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BicycleCar, BicycleCarBuilder};
+    use super::super::{VehicleModel, VehicleState};
+    use super::super::super::SteeringAcceleration;
+    use nalgebra::Vector2;
+
+    fn car() -> BicycleCar<f32> {
+        BicycleCar {
+            wheelbase: 2.0,
+            max_steering_angle: 0.5,
+            max_acceleration: 4.0,
+            max_deceleration: 8.0,
+            max_speed: 20.0,
+        }
+    }
+
+    #[test]
+    fn test_turning_radius() {
+        let car = car();
+        let mut state = VehicleState::new(Vector2::new(0.0f32, 0.0), 0.0, 1.0);
+        state.velocity = Vector2::new(0.0, 10.0);
+        let hard_left = SteeringAcceleration::new(Vector2::new(-1000.0f32, 0.0), 0.0);
+        let controls = car.controls(&state, &hard_left);
+        assert_eq!(1.0, controls.steer);
+        car.step(&mut state, &controls, 0.1);
+        let radius = state.forward_speed() / state.angular_velocity;
+        assert!((radius - car.min_turning_radius()).abs() < 1e-3);
+        assert!(state.angular_velocity > 0.0);
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_parameters() {
+        let builder = BicycleCarBuilder::default()
+            .wheelbase(2.0f32)
+            .max_steering_angle(0.5)
+            .max_acceleration(4.0)
+            .max_deceleration(8.0)
+            .max_speed(20.0);
+        assert!(builder.build().is_ok());
+        assert_eq!(
+            Err("wheelbase must be a positive number".to_string()),
+            builder.wheelbase(0.0).build().map(|_| ())
+        );
+        assert_eq!(
+            Err("max_steering_angle must be a positive number".to_string()),
+            builder.max_steering_angle(0.0).build().map(|_| ())
+        );
+        assert!(builder.max_steering_angle(1.6).build().is_err());
+    }
+
+    #[test]
+    fn test_brake_does_not_reverse() {
+        let car = car();
+        let mut state = VehicleState::new(Vector2::new(0.0f32, 0.0), 0.0, 1.0);
+        state.velocity = Vector2::new(0.0, 1.0);
+        let backwards = SteeringAcceleration::new(Vector2::new(0.0f32, -100.0), 0.0);
+        let controls = car.controls(&state, &backwards);
+        assert_eq!(1.0, controls.brake);
+        assert_eq!(0.0, controls.throttle);
+        let achieved = car.step(&mut state, &controls, 1.0);
+        assert_eq!(0.0, state.forward_speed());
+        assert_eq!(Vector2::new(0.0, -1.0), achieved.linear);
+    }
+}
@@ -0,0 +1,136 @@
+use alga::general::Real;
+use {SteeringAcceleration, SteeringVector};
+use super::{VehicleControls, VehicleModel, VehicleState};
+use super::vehicle_model::{achieved_acceleration, clamp, longitudinal_controls};
+use validation;
+
+/// Boat pushed by a propeller and turned by a rudder. The hull keeps its momentum
+/// while turning, sideways velocity only fades with lateral friction which makes the
+/// boat drift in turns. Rudder needs water flow, it reaches full effect at `rudder_speed`.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Boat<T>
+where
+    T: Real,
+{
+    /// Acceleration at full forward thrust
+    pub max_thrust: T,
+    /// Acceleration at full reverse thrust
+    pub max_reverse_thrust: T,
+    /// Turn rate at full rudder and full rudder effect
+    pub max_turn_rate: T,
+    /// Forward speed at which rudder reaches full effect
+    pub rudder_speed: T,
+    /// Rate at which sideways velocity fades, per second
+    pub lateral_friction: T,
+    /// Rate at which forward velocity fades, per second
+    pub drag: T,
+}
+
+impl<T: Real> BoatBuilder<T> {
+    fn validate(&self) -> Result<(), String> {
+        validation::positive("max_thrust", self.max_thrust)?;
+        validation::non_negative("max_reverse_thrust", self.max_reverse_thrust)?;
+        validation::non_negative("max_turn_rate", self.max_turn_rate)?;
+        validation::positive("rudder_speed", self.rudder_speed)?;
+        validation::non_negative("lateral_friction", self.lateral_friction)?;
+        validation::non_negative("drag", self.drag)
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> VehicleModel<T, V> for Boat<T> {
+    fn controls(
+        &self,
+        state: &VehicleState<T, V>,
+        desired: &SteeringAcceleration<T, V>,
+    ) -> VehicleControls<T> {
+        let forward = desired.linear.dot(&state.forward());
+        let lateral = desired.linear.dot(&state.left());
+        let (throttle, brake) =
+            longitudinal_controls(forward, self.max_thrust, self.max_reverse_thrust);
+        let heading_error = lateral.atan2(forward.abs());
+        VehicleControls {
+            throttle: throttle,
+            brake: brake,
+            steer: clamp(heading_error / T::frac_pi_2(), -T::one(), T::one()),
+        }
+    }
+
+    fn step(
+        &self,
+        state: &mut VehicleState<T, V>,
+        controls: &VehicleControls<T>,
+        delta_time: T,
+    ) -> SteeringAcceleration<T, V> {
+        let before = state.clone();
+        let forward_speed = state.forward_speed();
+        let thrust = controls.throttle * self.max_thrust -
+            controls.brake * self.max_reverse_thrust - self.drag * forward_speed;
+        state.velocity += state.forward().scale(thrust * delta_time);
+
+        let rudder_effect = clamp(forward_speed.abs() / self.rudder_speed, T::zero(), T::one());
+        state.angular_velocity =
+            clamp(controls.steer, -T::one(), T::one()) * self.max_turn_rate * rudder_effect;
+        state.heading += state.angular_velocity * delta_time;
+
+        let forward = state.forward();
+        let left = state.left();
+        let lateral_speed =
+            state.velocity.dot(&left) * (-self.lateral_friction * delta_time).exp();
+        state.velocity = forward.scale(state.velocity.dot(&forward)) + left.scale(lateral_speed);
+        state.position += state.velocity.scale(delta_time);
+        achieved_acceleration(&before, state, delta_time)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Boat, BoatBuilder};
+    use super::super::{VehicleControls, VehicleModel, VehicleState};
+    use nalgebra::Vector2;
+
+    #[test]
+    fn test_drift_in_turn() {
+        let boat = Boat {
+            max_thrust: 2.0f32,
+            max_reverse_thrust: 1.0,
+            max_turn_rate: 1.0,
+            rudder_speed: 2.0,
+            lateral_friction: 0.5,
+            drag: 0.0,
+        };
+        let mut state = VehicleState::new(Vector2::new(0.0f32, 0.0), 0.0, 1.0);
+        state.velocity = Vector2::new(0.0, 5.0);
+        let controls = VehicleControls {
+            throttle: 0.0,
+            brake: 0.0,
+            steer: 1.0,
+        };
+        boat.step(&mut state, &controls, 0.5);
+        let lateral_speed = state.velocity.dot(&state.left());
+        assert!(state.heading > 0.0);
+        assert!(lateral_speed < 0.0);
+        assert!(state.velocity.norm() < 5.0);
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_parameters() {
+        let builder = BoatBuilder::default()
+            .max_thrust(2.0f32)
+            .max_reverse_thrust(1.0)
+            .max_turn_rate(1.0)
+            .rudder_speed(2.0)
+            .lateral_friction(0.5)
+            .drag(0.0);
+        assert!(builder.build().is_ok());
+        assert_eq!(
+            Err("rudder_speed must be a positive number".to_string()),
+            builder.rudder_speed(0.0).build().map(|_| ())
+        );
+        assert_eq!(
+            Err("drag must not be a negative number".to_string()),
+            builder.drag(-1.0).build().map(|_| ())
+        );
+    }
+}
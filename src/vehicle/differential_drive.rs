@@ -0,0 +1,179 @@
+use alga::general::Real;
+use {SteeringAcceleration, SteeringVector};
+use super::{VehicleControls, VehicleModel, VehicleState};
+use super::vehicle_model::{achieved_acceleration, clamp, longitudinal_controls};
+use validation;
+
+/// Differential drive robot with two independently driven wheels. It can turn in
+/// place but it can not move sideways. Wheel speeds are limited, turning has priority
+/// over driving forward when both can not be satisfied.
+#[builder(pattern = "immutable", build_fn(validate = "Self::validate"))]
+#[derive(Builder, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DifferentialDrive<T>
+where
+    T: Real,
+{
+    /// Distance between the two wheels
+    pub track_width: T,
+    /// Largest speed of a single wheel
+    pub max_wheel_speed: T,
+    /// Acceleration at full throttle
+    pub max_acceleration: T,
+    /// Deceleration at full brake
+    pub max_deceleration: T,
+}
+
+impl<T: Real> DifferentialDriveBuilder<T> {
+    fn validate(&self) -> Result<(), String> {
+        validation::positive("track_width", self.track_width)?;
+        validation::positive("max_wheel_speed", self.max_wheel_speed)?;
+        validation::positive("max_acceleration", self.max_acceleration)?;
+        validation::positive("max_deceleration", self.max_deceleration)
+    }
+}
+
+impl<T: Real> DifferentialDrive<T> {
+    /// returns the turn rate when wheels spin at full speed in opposite directions
+    pub fn max_turn_rate(&self) -> T {
+        (self.max_wheel_speed + self.max_wheel_speed) / self.track_width
+    }
+
+    /// returns left and right wheel speeds producing given forward speed and turn rate
+    pub fn wheel_speeds(&self, speed: T, angular_velocity: T) -> (T, T) {
+        let half_track = self.track_width / (T::one() + T::one());
+        (
+            speed - angular_velocity * half_track,
+            speed + angular_velocity * half_track,
+        )
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> VehicleModel<T, V> for DifferentialDrive<T> {
+    fn controls(
+        &self,
+        state: &VehicleState<T, V>,
+        desired: &SteeringAcceleration<T, V>,
+    ) -> VehicleControls<T> {
+        let forward = desired.linear.dot(&state.forward());
+        let lateral = desired.linear.dot(&state.left());
+        let (throttle, brake) =
+            longitudinal_controls(forward, self.max_acceleration, self.max_deceleration);
+        let heading_error = lateral.atan2(forward);
+        VehicleControls {
+            throttle: throttle,
+            brake: brake,
+            steer: clamp(heading_error / T::frac_pi_2(), -T::one(), T::one()),
+        }
+    }
+
+    fn step(
+        &self,
+        state: &mut VehicleState<T, V>,
+        controls: &VehicleControls<T>,
+        delta_time: T,
+    ) -> SteeringAcceleration<T, V> {
+        let before = state.clone();
+        let acceleration =
+            controls.throttle * self.max_acceleration - controls.brake * self.max_deceleration;
+        let angular_velocity = clamp(controls.steer, -T::one(), T::one()) * self.max_turn_rate();
+        let turning_speed = angular_velocity.abs() * self.track_width / (T::one() + T::one());
+        let max_speed = self.max_wheel_speed - turning_speed;
+        let speed = clamp(
+            state.forward_speed() + acceleration * delta_time,
+            T::zero(),
+            max_speed,
+        );
+        state.angular_velocity = angular_velocity;
+        state.heading += angular_velocity * delta_time;
+        state.velocity = state.forward().scale(speed);
+        state.position += state.velocity.scale(delta_time);
+        achieved_acceleration(&before, state, delta_time)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DifferentialDrive, DifferentialDriveBuilder};
+    use super::super::{VehicleControls, VehicleModel, VehicleState};
+    use super::super::super::SteeringAcceleration;
+    use nalgebra::Vector2;
+
+    fn robot() -> DifferentialDrive<f32> {
+        DifferentialDrive {
+            track_width: 0.5,
+            max_wheel_speed: 1.0,
+            max_acceleration: 2.0,
+            max_deceleration: 4.0,
+        }
+    }
+
+    #[test]
+    fn test_wheel_speeds() {
+        let robot = robot();
+        assert_eq!(4.0, robot.max_turn_rate());
+        assert_eq!((0.5, 1.5), robot.wheel_speeds(1.0, 2.0));
+        assert_eq!((-1.0, 1.0), robot.wheel_speeds(0.0, robot.max_turn_rate()));
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_parameters() {
+        let builder = DifferentialDriveBuilder::default()
+            .track_width(0.5f32)
+            .max_wheel_speed(1.0)
+            .max_acceleration(2.0)
+            .max_deceleration(4.0);
+        assert_eq!(Ok(robot()), builder.build());
+        assert_eq!(
+            Err("track_width must be a positive number".to_string()),
+            builder.track_width(0.0).build()
+        );
+    }
+
+    #[test]
+    fn test_turn_in_place() {
+        let robot = robot();
+        let mut state = VehicleState::new(Vector2::new(1.0f32, 2.0), 0.0, 0.3);
+        let controls = VehicleControls {
+            throttle: 0.0,
+            brake: 0.0,
+            steer: 1.0,
+        };
+        robot.step(&mut state, &controls, 0.1);
+        assert_eq!(Vector2::new(1.0, 2.0), state.position);
+        assert_eq!(4.0, state.angular_velocity);
+        assert!((state.heading - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_turning_limits_speed() {
+        let robot = robot();
+        let mut state = VehicleState::new(Vector2::new(0.0f32, 0.0), 0.0, 0.3);
+        state.velocity = state.forward();
+        let controls = VehicleControls {
+            throttle: 1.0,
+            brake: 0.0,
+            steer: 0.5,
+        };
+        robot.step(&mut state, &controls, 0.1);
+        // turning at 2 takes 0.5 of the wheel speed of 1
+        assert!((state.forward_speed() - 0.5).abs() < 1e-6);
+        let (left, right) = robot.wheel_speeds(state.forward_speed(), state.angular_velocity);
+        assert!(left.abs() <= 1.0 + 1e-6 && right.abs() <= 1.0 + 1e-6);
+    }
+
+    #[test]
+    fn test_no_sideways_motion() {
+        let robot = robot();
+        let mut state = VehicleState::new(Vector2::new(0.0f32, 0.0), 0.0, 0.3);
+        let sideways = SteeringAcceleration::new(state.left() * 10.0, 0.0);
+        let controls = robot.controls(&state, &sideways);
+        assert_eq!(1.0, controls.steer);
+        assert_eq!(0.0, controls.throttle);
+
+        state.velocity = state.left();
+        robot.step(&mut state, &VehicleControls::idle(), 0.1);
+        assert!(state.velocity.dot(&state.left()).abs() < 1e-6);
+        assert!((state.velocity - state.forward() * state.forward_speed()).norm() < 1e-6);
+    }
+}
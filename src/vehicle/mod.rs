@@ -0,0 +1,12 @@
+//! Kinematic models of vehicles which can not accelerate in every direction. A model
+//! converts the acceleration calculated by any steering behavior into throttle, brake
+//! and steering commands and then into the motion the vehicle can actually achieve.
+mod vehicle_model;
+mod bicycle_car;
+mod differential_drive;
+mod boat;
+
+pub use self::vehicle_model::{VehicleControls, VehicleModel, VehicleState};
+pub use self::bicycle_car::{BicycleCar, BicycleCarBuilder};
+pub use self::differential_drive::{DifferentialDrive, DifferentialDriveBuilder};
+pub use self::boat::{Boat, BoatBuilder};
@@ -0,0 +1,210 @@
+use nalgebra::Vector3;
+use alga::general::Real;
+use {Location, Steerable, SteeringAcceleration, SteeringVector};
+
+/// Commands a driver can give to a vehicle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VehicleControls<T: Real> {
+    /// forward drive in range [0, 1]
+    pub throttle: T,
+    /// braking or reverse drive in range [0, 1]
+    pub brake: T,
+    /// turning command in range [-1, 1], positive values turn left
+    pub steer: T,
+}
+
+impl<T: Real> VehicleControls<T> {
+    /// Creates controls with all commands released
+    pub fn idle() -> VehicleControls<T> {
+        VehicleControls {
+            throttle: T::zero(),
+            brake: T::zero(),
+            steer: T::zero(),
+        }
+    }
+}
+
+/// Motion state of a vehicle moving on the plane perpendicular to z axis. Heading is
+/// measured like all orientations in this crate, zero heading drives along positive y.
+/// A vehicle state is a `Steerable` so it can be the owner of any behavior.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VehicleState<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// position of the vehicle
+    pub position: V,
+    /// heading of the vehicle in radians
+    pub heading: T,
+    /// linear velocity of the vehicle
+    pub velocity: V,
+    /// turn rate of the vehicle in radians per second
+    pub angular_velocity: T,
+    /// bounding circle radius of the vehicle
+    pub bounding_radius: T,
+}
+
+impl<T: Real, V: SteeringVector<T>> VehicleState<T, V> {
+    /// Creates a vehicle at rest
+    pub fn new(position: V, heading: T, bounding_radius: T) -> VehicleState<T, V> {
+        VehicleState {
+            position: position,
+            heading: heading,
+            velocity: V::zero(),
+            angular_velocity: T::zero(),
+            bounding_radius: bounding_radius,
+        }
+    }
+
+    /// returns unit vector pointing to the heading of the vehicle
+    pub fn forward(&self) -> V {
+        V::angle_to_vector(self.heading)
+    }
+
+    /// returns unit vector pointing to the left of the vehicle
+    pub fn left(&self) -> V {
+        V::angle_to_vector(self.heading + T::frac_pi_2())
+    }
+
+    /// returns the signed speed along the heading of the vehicle
+    pub fn forward_speed(&self) -> T {
+        self.velocity.dot(&self.forward())
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Location<T, V> for VehicleState<T, V> {
+    fn get_position(&self) -> &V {
+        &self.position
+    }
+
+    fn get_orientation(&self) -> T {
+        self.heading
+    }
+}
+
+impl<T: Real, V: SteeringVector<T>> Steerable<T, V> for VehicleState<T, V> {
+    fn get_linear_velocity(&self) -> &V {
+        &self.velocity
+    }
+
+    fn get_angular_velocity(&self) -> T {
+        self.angular_velocity
+    }
+
+    fn get_bounding_radius(&self) -> T {
+        self.bounding_radius
+    }
+}
+
+/// Kinematic model of a vehicle which can not accelerate in every direction
+pub trait VehicleModel<T: Real, V: SteeringVector<T> = Vector3<T>> {
+    /// Converts the acceleration desired by a steering behavior into controls this
+    /// vehicle is able to execute
+    fn controls(
+        &self,
+        state: &VehicleState<T, V>,
+        desired: &SteeringAcceleration<T, V>,
+    ) -> VehicleControls<T>;
+
+    /// Advances vehicle state by `delta_time` seconds under given controls and
+    /// returns the acceleration vehicle actually achieved
+    fn step(
+        &self,
+        state: &mut VehicleState<T, V>,
+        controls: &VehicleControls<T>,
+        delta_time: T,
+    ) -> SteeringAcceleration<T, V>;
+
+    /// Converts desired acceleration into controls and advances vehicle state with them
+    fn drive(
+        &self,
+        state: &mut VehicleState<T, V>,
+        desired: &SteeringAcceleration<T, V>,
+        delta_time: T,
+    ) -> SteeringAcceleration<T, V> {
+        let controls = self.controls(state, desired);
+        self.step(state, &controls, delta_time)
+    }
+}
+
+/// Splits desired acceleration into throttle and brake commands
+pub fn longitudinal_controls<T: Real>(
+    longitudinal_acceleration: T,
+    max_acceleration: T,
+    max_deceleration: T,
+) -> (T, T) {
+    if longitudinal_acceleration >= T::zero() {
+        (
+            clamp(longitudinal_acceleration / max_acceleration, T::zero(), T::one()),
+            T::zero(),
+        )
+    } else {
+        (
+            T::zero(),
+            clamp(-longitudinal_acceleration / max_deceleration, T::zero(), T::one()),
+        )
+    }
+}
+
+/// Returns the acceleration achieved between two states, zero if no time passed
+pub fn achieved_acceleration<T: Real, V: SteeringVector<T>>(
+    before: &VehicleState<T, V>,
+    after: &VehicleState<T, V>,
+    delta_time: T,
+) -> SteeringAcceleration<T, V> {
+    if delta_time.is_zero() {
+        return SteeringAcceleration::default();
+    }
+    SteeringAcceleration::new(
+        (after.velocity - before.velocity).scale(T::one() / delta_time),
+        (after.angular_velocity - before.angular_velocity) / delta_time,
+    )
+}
+
+/// Limits value into given range
+pub fn clamp<T: Real>(value: T, min: T, max: T) -> T {
+    if value < min {
+        min
+    } else if value > max {
+        max
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{achieved_acceleration, VehicleControls, VehicleModel, VehicleState};
+    use super::super::DifferentialDrive;
+    use nalgebra::Vector2;
+
+    #[test]
+    fn test_achieved_acceleration() {
+        let before = VehicleState::new(Vector2::new(0.0f32, 0.0), 0.0, 0.3);
+        let mut after = before.clone();
+        after.velocity = Vector2::new(0.0, 0.5);
+        after.angular_velocity = 1.0;
+        let acceleration = achieved_acceleration(&before, &after, 0.5);
+        assert_eq!(Vector2::new(0.0, 1.0), acceleration.linear);
+        assert_eq!(2.0, acceleration.angular);
+
+        let acceleration = achieved_acceleration(&before, &after, 0.0);
+        assert!(acceleration.is_zero());
+    }
+
+    #[test]
+    fn test_step_without_time() {
+        let robot = DifferentialDrive {
+            track_width: 0.5f32,
+            max_wheel_speed: 1.0,
+            max_acceleration: 2.0,
+            max_deceleration: 4.0,
+        };
+        let mut state = VehicleState::new(Vector2::new(0.0f32, 0.0), 0.0, 0.3);
+        let controls = VehicleControls {
+            throttle: 1.0,
+            brake: 0.0,
+            steer: 0.5,
+        };
+        let acceleration = robot.step(&mut state, &controls, 0.0);
+        assert!(acceleration.is_zero());
+        assert_eq!(Vector2::new(0.0, 0.0), state.position);
+    }
+}